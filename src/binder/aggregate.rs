@@ -11,11 +11,11 @@ use crate::{
 };
 use crate::binder::{BindError, InputRefType};
 use crate::planner::LogicalPlan;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 
 use super::Binder;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     pub fn bind_aggregate(
         &mut self,
         children: LogicalPlan,
@@ -144,7 +144,13 @@ impl<S: Storage> Binder<S> {
                 self.visit_column_agg_expr(left_expr, is_select)?;
                 self.visit_column_agg_expr(right_expr, is_select)?;
             }
+            ScalarExpression::Coalesce { exprs, .. } => {
+                for expr in exprs {
+                    self.visit_column_agg_expr(expr, is_select)?;
+                }
+            }
             ScalarExpression::Constant(_)
+            | ScalarExpression::Parameter { .. }
             | ScalarExpression::ColumnRef { .. }
             | ScalarExpression::InputRef { .. } => {}
         }
@@ -314,8 +320,19 @@ impl<S: Storage> Binder<S> {
                 self.validate_having_orderby(right_expr)?;
                 Ok(())
             }
+            ScalarExpression::Coalesce { exprs, .. } => {
+                if self.context.group_by_exprs.contains(expr) {
+                    return Ok(());
+                }
+                for expr in exprs {
+                    self.validate_having_orderby(expr)?;
+                }
+                Ok(())
+            }
 
-            ScalarExpression::Constant(_) | ScalarExpression::InputRef { .. } => Ok(()),
+            ScalarExpression::Constant(_)
+            | ScalarExpression::Parameter { .. }
+            | ScalarExpression::InputRef { .. } => Ok(()),
         }
     }
 }
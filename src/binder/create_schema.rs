@@ -0,0 +1,37 @@
+use sqlparser::ast::{ObjectName, SchemaName};
+use crate::binder::{Binder, BindError, lower_case_name};
+use crate::planner::LogicalPlan;
+use crate::planner::operator::create_schema::CreateSchemaOperator;
+use crate::planner::operator::Operator;
+use crate::storage::Transaction;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    pub(crate) fn bind_create_schema(
+        &mut self,
+        schema_name: &SchemaName,
+        if_not_exists: bool
+    ) -> Result<LogicalPlan, BindError> {
+        let SchemaName::Simple(name) = schema_name else {
+            return Err(BindError::UnsupportedStmt(format!("create schema {}", schema_name)));
+        };
+        let schema_name = bind_schema_name(name)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::CreateSchema(
+                CreateSchemaOperator {
+                    schema_name,
+                    if_not_exists,
+                }
+            ),
+            childrens: vec![],
+        })
+    }
+}
+
+/// The schema and the table are separated by `.` in storage
+pub(crate) fn bind_schema_name(name: &ObjectName) -> Result<String, BindError> {
+    match lower_case_name(name).0.as_slice() {
+        [schema] if !schema.value.contains('.') => Ok(schema.value.clone()),
+        _ => Err(BindError::InvalidSchemaName(name.to_string())),
+    }
+}
@@ -1,40 +1,47 @@
 use std::collections::HashSet;
-use std::sync::Arc;
 use itertools::Itertools;
-use sqlparser::ast::{ColumnDef, ObjectName, TableConstraint};
+use sqlparser::ast::{ColumnDef, ObjectName, Query, TableConstraint};
 
 use super::Binder;
-use crate::binder::{BindError, lower_case_name, split_name};
-use crate::catalog::ColumnCatalog;
+use crate::binder::BindError;
+use crate::catalog::{ColumnCatalog, ColumnDesc, TableCatalog};
 use crate::planner::LogicalPlan;
 use crate::planner::operator::create_table::CreateTableOperator;
 use crate::planner::operator::Operator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     // TODO: TableConstraint
-    pub(crate) fn bind_create_table(
+    pub(crate) async fn bind_create_table(
         &mut self,
         name: &ObjectName,
         columns: &[ColumnDef],
-        constraints: &[TableConstraint]
+        constraints: &[TableConstraint],
+        query: Option<&Query>
     ) -> Result<LogicalPlan, BindError> {
-        let name = lower_case_name(&name);
-        let (_, name) = split_name(&name)?;
-        let table_name = Arc::new(name.to_string());
+        let table_name = self.bind_new_table_name(name)?;
+
+        let query_plan = match query {
+            Some(query) => Some(self.bind_query(query).await?),
+            None => None,
+        };
+        let columns = match (&query_plan, columns.is_empty()) {
+            // the columns of `CREATE TABLE ... AS SELECT` are inferred from the query,
+            // which are stored with a generated row key if the query outputs no primary key
+            (Some(query_plan), true) => Self::with_row_key(self.infer_columns(query_plan)),
+            _ => columns
+                .iter()
+                .map(|col| ColumnCatalog::from(col.clone()))
+                .collect_vec(),
+        };
 
         // check duplicated column names
         let mut set = HashSet::new();
         for col in columns.iter() {
-            let col_name = &col.name.value;
-            if !set.insert(col_name.clone()) {
-                return Err(BindError::AmbiguousColumn(col_name.to_string()));
+            if !set.insert(col.name.clone()) {
+                return Err(BindError::AmbiguousColumn(col.name.to_string()));
             }
         }
-        let columns = columns
-            .iter()
-            .map(|col| ColumnCatalog::from(col.clone()))
-            .collect_vec();
 
         let primary_key_count = columns
             .iter()
@@ -47,6 +54,16 @@ impl<S: Storage> Binder<S> {
             ));
         }
 
+        let childrens = match query_plan {
+            Some(query_plan) => {
+                let target_columns = TableCatalog::new(table_name.clone(), columns.clone())?.visible_columns();
+                self.check_query_output(&query_plan, &target_columns)?;
+
+                vec![Self::bind_insert_plan(table_name.clone(), target_columns, false, None, query_plan)]
+            }
+            None => vec![],
+        };
+
         let plan = LogicalPlan {
             operator: Operator::CreateTable(
                 CreateTableOperator {
@@ -54,28 +71,63 @@ impl<S: Storage> Binder<S> {
                     columns
                 }
             ),
-            childrens: vec![],
+            childrens,
         };
         Ok(plan)
     }
+
+    /// The primary key and unique constraints are kept only when the query outputs
+    /// exactly one primary key column, e.g. `CREATE TABLE t2 AS SELECT * FROM t1`.
+    pub(crate) fn infer_columns(&self, query_plan: &LogicalPlan) -> Vec<ColumnCatalog> {
+        let output_columns = self.query_output_columns(query_plan);
+        let keep_constraints = output_columns
+            .iter()
+            .filter(|col| col.desc.is_primary)
+            .count() == 1;
+
+        output_columns
+            .iter()
+            .map(|col| ColumnCatalog::new(
+                col.name.clone(),
+                col.nullable,
+                ColumnDesc::new(
+                    *col.datatype(),
+                    keep_constraints && col.desc.is_primary,
+                    keep_constraints && col.desc.is_unique
+                ),
+                None
+            ))
+            .collect_vec()
+    }
+
+    /// The inferred columns followed by the generated row key if none of them is the primary key.
+    pub(crate) fn with_row_key(mut columns: Vec<ColumnCatalog>) -> Vec<ColumnCatalog> {
+        if !columns.iter().any(|col| col.desc.is_primary) {
+            columns.push(ColumnCatalog::new_row_key());
+        }
+        columns
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
     use tempfile::TempDir;
     use super::*;
     use crate::binder::BinderContext;
-    use crate::catalog::ColumnDesc;
+    use crate::binder::test::build_test_catalog;
     use crate::storage::kip::KipStorage;
+    use crate::storage::Storage;
     use crate::types::LogicalType;
 
     #[tokio::test]
     async fn test_create_bind() {
         let temp_dir = TempDir::new().expect("unable to create temporary working directory");
         let storage = KipStorage::new(temp_dir.path()).await.unwrap();
+        let transaction = storage.transaction().await.unwrap();
 
         let sql = "create table t1 (id int primary key, name varchar(10) null)";
-        let binder = Binder::new(BinderContext::new(storage));
+        let binder = Binder::new(BinderContext::new(&transaction));
         let stmt = crate::parser::parse_sql(sql).unwrap();
         let plan1 = binder.bind(&stmt[0]).await.unwrap();
 
@@ -93,4 +145,52 @@ mod tests {
         }
 
     }
+
+    #[tokio::test]
+    async fn test_create_as_select_bind() {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = build_test_catalog(temp_dir.path()).await.unwrap();
+        let transaction = storage.transaction().await.unwrap();
+
+        let sql = "create table t3 as select c1, c2 + 1 as c3 from t1";
+        let binder = Binder::new(BinderContext::new(&transaction));
+        let stmt = crate::parser::parse_sql(sql).unwrap();
+        let plan = binder.bind(&stmt[0]).await.unwrap();
+
+        match plan.operator {
+            Operator::CreateTable(op) => {
+                assert_eq!(op.columns[0].name, "c1".to_string());
+                assert_eq!(op.columns[0].desc, ColumnDesc::new(LogicalType::Integer, true, false));
+                assert_eq!(op.columns[1].name, "c3".to_string());
+                assert_eq!(op.columns[1].nullable, true);
+                assert_eq!(op.columns[1].desc, ColumnDesc::new(LogicalType::Integer, false, false));
+            }
+            _ => unreachable!()
+        }
+        match &plan.childrens[0].operator {
+            Operator::Insert(op) => {
+                assert_eq!(op.table_name, Arc::new("t3".to_string()));
+                assert_eq!(op.columns.len(), 2);
+            }
+            _ => unreachable!()
+        }
+
+        let sql = "create table t4 as select c2 from t1";
+        let binder = Binder::new(BinderContext::new(&transaction));
+        let stmt = crate::parser::parse_sql(sql).unwrap();
+        let plan = binder.bind(&stmt[0]).await.unwrap();
+
+        match plan.operator {
+            Operator::CreateTable(op) => {
+                assert_eq!(op.columns.len(), 2);
+                assert_eq!(op.columns[0].desc, ColumnDesc::new(LogicalType::Integer, false, false));
+                assert_eq!(op.columns[1], ColumnCatalog::new_row_key());
+            }
+            _ => unreachable!()
+        }
+        match &plan.childrens[0].operator {
+            Operator::Insert(op) => assert_eq!(op.columns.len(), 1),
+            _ => unreachable!()
+        }
+    }
 }
@@ -0,0 +1,138 @@
+use std::collections::HashSet;
+use itertools::Itertools;
+use sqlparser::ast::{Ident, ObjectName, Query};
+use crate::binder::{Binder, BindError};
+use crate::catalog::{TableCatalog, TableName, ViewCatalog};
+use crate::expression::ScalarExpression;
+use crate::planner::{is_same_column, LogicalPlan};
+use crate::planner::operator::create_table::CreateTableOperator;
+use crate::planner::operator::create_view::CreateViewOperator;
+use crate::planner::operator::Operator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// The query is bound to check it and to name the columns of the view, but only its SQL is stored.
+    ///
+    /// A materialized view is created with the table of its rows, which is filled by the query at once.
+    pub(crate) async fn bind_create_view(
+        &mut self,
+        name: &ObjectName,
+        columns: &[Ident],
+        query: &Query,
+        or_replace: bool,
+        materialized: bool
+    ) -> Result<LogicalPlan, BindError> {
+        if or_replace && materialized {
+            return Err(BindError::UnsupportedStmt("CREATE OR REPLACE MATERIALIZED VIEW".to_string()));
+        }
+        let view_name = self.bind_new_table_name(name)?;
+        let query_plan = self.bind_query(query).await?;
+
+        if self.context.views.lock().iter().any(|view| view.name == view_name) {
+            return Err(BindError::InvalidTable(format!("view {} references itself", view_name)));
+        }
+        let output_columns = self.query_output_columns(&query_plan);
+        let columns = if columns.is_empty() {
+            output_columns
+                .iter()
+                .map(|col| col.name.clone())
+                .collect_vec()
+        } else if columns.len() == output_columns.len() {
+            columns
+                .iter()
+                .map(|ident| ident.value.to_lowercase())
+                .collect_vec()
+        } else {
+            return Err(BindError::ColumnCountMismatch(columns.len(), output_columns.len()));
+        };
+
+        let mut set = HashSet::new();
+        for column in columns.iter() {
+            if !set.insert(column) {
+                return Err(BindError::AmbiguousColumn(column.to_string()));
+            }
+        }
+
+        let childrens = if materialized {
+            vec![self.bind_materialized_table(view_name.clone(), &columns, query_plan)?]
+        } else {
+            vec![]
+        };
+
+        Ok(LogicalPlan {
+            operator: Operator::CreateView(
+                CreateViewOperator {
+                    view: ViewCatalog {
+                        name: view_name,
+                        columns,
+                        query: query.to_string(),
+                        search_path: self.context.search_path.clone(),
+                        materialized,
+                    },
+                    or_replace,
+                }
+            ),
+            childrens,
+        })
+    }
+
+    /// `CREATE TABLE ... AS SELECT` of the table which stores the rows of the materialized view.
+    ///
+    /// The primary key and unique constraints of the query are kept like `CREATE TABLE ... AS SELECT`,
+    /// otherwise the first column is the primary key if it is unique and not null,
+    /// i.e. the only grouping column or the column of an aggregation without `GROUP BY`,
+    /// and the rows of any other query are stored with a generated row key.
+    ///
+    /// `CREATE INDEX` is not supported, so the indexes of the view are only the kept unique constraints
+    fn bind_materialized_table(
+        &self,
+        table_name: TableName,
+        columns: &[String],
+        query_plan: LogicalPlan
+    ) -> Result<LogicalPlan, BindError> {
+        let mut table_columns = self.infer_columns(&query_plan);
+
+        for (column, name) in table_columns.iter_mut().zip(columns) {
+            column.name = name.clone();
+        }
+        if !table_columns.iter().any(|column| column.desc.is_primary) {
+            let is_unique = match self.context.group_by_exprs.as_slice() {
+                [] => !self.context.agg_calls.is_empty(),
+                [_] => self.is_grouped_by_first_column(&query_plan),
+                _ => false,
+            };
+
+            if is_unique && !table_columns[0].nullable {
+                table_columns[0].desc.is_primary = true;
+            }
+        }
+        let table_columns = Self::with_row_key(table_columns);
+        let target_columns = TableCatalog::new(table_name.clone(), table_columns.clone())?.visible_columns();
+        self.check_query_output(&query_plan, &target_columns)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::CreateTable(
+                CreateTableOperator {
+                    table_name: table_name.clone(),
+                    columns: table_columns,
+                }
+            ),
+            childrens: vec![Self::bind_insert_plan(table_name, target_columns, false, None, query_plan)],
+        })
+    }
+
+    fn is_grouped_by_first_column(&self, query_plan: &LogicalPlan) -> bool {
+        let empty_tuple = Tuple { id: None, columns: vec![], values: vec![] };
+        let group_by_column = self.context.group_by_exprs[0].output_columns(&empty_tuple);
+        let first_column = &self.query_output_columns(query_plan)[0];
+
+        match first_column.ref_expr.as_ref().map(ScalarExpression::unpack_alias) {
+            Some(ScalarExpression::ColumnRef(column)) => is_same_column(column, &group_by_column),
+            // the output of the aggregation is the aggregate calls followed by the grouping columns
+            Some(ScalarExpression::InputRef { index, .. }) => *index == self.context.agg_calls.len(),
+            Some(_) => false,
+            None => is_same_column(first_column, &group_by_column),
+        }
+    }
+}
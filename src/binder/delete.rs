@@ -1,35 +1,53 @@
-use sqlparser::ast::{Expr, TableFactor, TableWithJoins};
-use crate::binder::{Binder, BindError, lower_case_name, split_name};
+use sqlparser::ast::{Expr, ObjectName, TableFactor, TableWithJoins};
+use crate::binder::{Binder, BindError, lower_case_name};
 use crate::planner::LogicalPlan;
 use crate::planner::operator::delete::DeleteOperator;
 use crate::planner::operator::Operator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// `DELETE t1 FROM t1 JOIN t2` names the target table explicitly,
+    /// otherwise it is the first table of FROM.
     pub(crate) async fn bind_delete(
         &mut self,
+        tables: &[ObjectName],
         from: &TableWithJoins,
+        using: &Option<Vec<TableWithJoins>>,
         selection: &Option<Expr>,
     ) -> Result<LogicalPlan, BindError> {
-        if let TableFactor::Table { name, .. } = &from.relation {
-            let name = lower_case_name(name);
-            let (_, name) = split_name(&name)?;
-            let (table_name, mut plan) = self._bind_single_table_ref(None, name).await?;
+        let name = match (tables.first(), &from.relation) {
+            (Some(name), _) | (None, TableFactor::Table { name, .. }) => name,
+            _ => unreachable!("only table")
+        };
 
-            if let Some(predicate) = selection {
-                plan = self.bind_where(plan, predicate).await?;
-            }
+        let (left_table, mut plan) = self.bind_table_with_joins(from).await?;
+        let alias = match lower_case_name(name).0.as_slice() {
+            [alias] => self.context.table_aliases.get(&alias.value).cloned(),
+            _ => None,
+        };
+        let table_name = match alias {
+            Some(table_name) => table_name,
+            None => self.bind_table_name(name)?,
+        };
 
-            Ok(LogicalPlan {
-                operator: Operator::Delete(
-                    DeleteOperator {
-                        table_name
-                    }
-                ),
-                childrens: vec![plan],
-            })
-        } else {
-            unreachable!("only table")
+        if !self.context.bind_table.contains_key(&table_name) {
+            return Err(BindError::InvalidTable(format!("{} is not in FROM", table_name)));
         }
+        self.check_writable(&table_name)?;
+
+        if let Some(using) = using {
+            plan = self.bind_extra_tables(left_table, plan, using, selection).await?;
+        } else if let Some(predicate) = selection {
+            plan = self.bind_where(plan, predicate).await?;
+        }
+
+        Ok(LogicalPlan {
+            operator: Operator::Delete(
+                DeleteOperator {
+                    table_name
+                }
+            ),
+            childrens: vec![plan],
+        })
     }
-}
\ No newline at end of file
+}
@@ -2,9 +2,9 @@ use crate::binder::Binder;
 use crate::expression::ScalarExpression;
 use crate::planner::LogicalPlan;
 use crate::planner::operator::aggregate::AggregateOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     pub fn bind_distinct(
         &mut self,
         children: LogicalPlan,
@@ -0,0 +1,29 @@
+use sqlparser::ast::ObjectName;
+use crate::binder::{Binder, BindError};
+use crate::binder::create_schema::bind_schema_name;
+use crate::planner::LogicalPlan;
+use crate::planner::operator::drop_schema::DropSchemaOperator;
+use crate::planner::operator::Operator;
+use crate::storage::Transaction;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    pub(crate) fn bind_drop_schema(
+        &mut self,
+        name: &ObjectName,
+        if_exists: bool,
+        cascade: bool
+    ) -> Result<LogicalPlan, BindError> {
+        let schema_name = bind_schema_name(name)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::DropSchema(
+                DropSchemaOperator {
+                    schema_name,
+                    if_exists,
+                    cascade,
+                }
+            ),
+            childrens: vec![],
+        })
+    }
+}
@@ -1,19 +1,17 @@
-use std::sync::Arc;
 use sqlparser::ast::ObjectName;
-use crate::binder::{Binder, BindError, lower_case_name, split_name};
+use crate::binder::{Binder, BindError};
 use crate::planner::LogicalPlan;
 use crate::planner::operator::drop_table::DropTableOperator;
 use crate::planner::operator::Operator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     pub(crate) fn bind_drop_table(
         &mut self,
         name: &ObjectName
     ) -> Result<LogicalPlan, BindError> {
-        let name = lower_case_name(&name);
-        let (_, name) = split_name(&name)?;
-        let table_name = Arc::new(name.to_string());
+        let table_name = self.bind_table_name(name)?;
+        self.check_writable(&table_name)?;
 
         let plan = LogicalPlan {
             operator: Operator::DropTable(
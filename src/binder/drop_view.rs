@@ -0,0 +1,28 @@
+use sqlparser::ast::ObjectName;
+use crate::binder::{Binder, BindError};
+use crate::planner::LogicalPlan;
+use crate::planner::operator::drop_view::DropViewOperator;
+use crate::planner::operator::Operator;
+use crate::storage::Transaction;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    pub(crate) fn bind_drop_view(
+        &mut self,
+        name: &ObjectName,
+        if_exists: bool,
+        materialized: bool
+    ) -> Result<LogicalPlan, BindError> {
+        let view_name = self.bind_table_name(name)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::DropView(
+                DropViewOperator {
+                    view_name,
+                    if_exists,
+                    materialized,
+                }
+            ),
+            childrens: vec![],
+        })
+    }
+}
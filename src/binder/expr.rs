@@ -1,18 +1,20 @@
 use crate::binder::BindError;
 use itertools::Itertools;
-use sqlparser::ast::{BinaryOperator, Expr, Function, FunctionArg, FunctionArgExpr, Ident, UnaryOperator};
+use sqlparser::ast::{BinaryOperator, Expr, Function, FunctionArg, FunctionArgExpr, Ident, UnaryOperator, Value};
 use std::slice;
 use std::sync::Arc;
 use async_recursion::async_recursion;
 use crate::expression::agg::AggKind;
 
-use super::Binder;
+use super::{Binder, Parameters};
+use crate::catalog::{ColumnRef, table_name_in_schema};
 use crate::expression::ScalarExpression;
-use crate::storage::Storage;
+use crate::planner::operator::insert::{EXCLUDED_TABLE_NAME, excluded_column};
+use crate::storage::Transaction;
 use crate::types::LogicalType;
 use crate::types::value::DataValue;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     #[async_recursion]
     pub(crate) async fn bind_expr(&mut self, expr: &Expr) -> Result<ScalarExpression, BindError> {
         match expr {
@@ -25,6 +27,7 @@ impl<S: Storage> Binder<S> {
             Expr::BinaryOp { left, right, op} => {
                 self.bind_binary_op_internal(left, right, op).await
             }
+            Expr::Value(Value::Placeholder(placeholder)) => self.bind_placeholder(placeholder),
             Expr::Value(v) => Ok(ScalarExpression::Constant(Arc::new(v.into()))),
             Expr::Function(func) => self.bind_agg_call(func).await,
             Expr::Nested(expr) => self.bind_expr(expr).await,
@@ -44,10 +47,10 @@ impl<S: Storage> Binder<S> {
             .iter()
             .map(|ident| Ident::new(ident.value.to_lowercase()))
             .collect_vec();
-        let (_schema_name, table_name, column_name) = match idents.as_slice() {
-            [column] => (None, None, &column.value),
-            [table, column] => (None, Some(&table.value), &column.value),
-            [schema, table, column] => (Some(&schema.value), Some(&table.value), &column.value),
+        let (table_name, column_name) = match idents.as_slice() {
+            [column] => (None, &column.value),
+            [table, column] => (Some(table.value.clone()), &column.value),
+            [schema, table, column] => (Some(table_name_in_schema(&schema.value, &table.value)), &column.value),
             _ => {
                 return Err(BindError::InvalidColumn(
                     idents
@@ -60,20 +63,35 @@ impl<S: Storage> Binder<S> {
             }
         };
 
-        if let Some(table) = table_name.or(bind_table_name) {
+        let is_schema_qualified = idents.len() == 3;
+
+        if let Some(table) = table_name.as_ref().or(bind_table_name) {
+            let excluded_table = self.context.excluded_table
+                .as_ref()
+                .filter(|_| table == EXCLUDED_TABLE_NAME && !is_schema_qualified);
+            let table = excluded_table
+                .or_else(|| self.context.table_aliases.get(table).filter(|_| !is_schema_qualified))
+                .map(|table_name| table_name.as_ref())
+                .unwrap_or(table);
             let table_catalog = self
-                .context
-                .storage
-                .table(table)
-                .await
+                .bound_table(table)
                 .ok_or_else(|| BindError::InvalidTable(table.to_string()))?;
 
             let column_catalog = table_catalog
                 .get_column_by_name(column_name)
                 .ok_or_else(|| BindError::InvalidColumn(column_name.to_string()))?;
-            Ok(ScalarExpression::ColumnRef(column_catalog.clone()))
+            if excluded_table.is_some() {
+                return Ok(ScalarExpression::ColumnRef(excluded_column(column_catalog)));
+            }
+            Ok(Self::column_ref_expr(column_catalog.clone()))
         } else {
             // handle col syntax
+            if let Some(using_column) = self.context.using_columns
+                .iter()
+                .find(|using_column| &using_column.name == column_name)
+            {
+                return Ok(using_column.expr.clone());
+            }
             let mut got_column = None;
             for (_, (table_catalog, _)) in &self.context.bind_table {
                 if let Some(column_catalog) = table_catalog.get_column_by_name(column_name) {
@@ -90,7 +108,7 @@ impl<S: Storage> Binder<S> {
             }
             let column_catalog =
                 got_column.ok_or_else(|| BindError::InvalidColumn(column_name.to_string()))?;
-            Ok(ScalarExpression::ColumnRef(column_catalog.clone()))
+            Ok(Self::column_ref_expr(column_catalog.clone()))
         }
     }
 
@@ -103,6 +121,9 @@ impl<S: Storage> Binder<S> {
         let left_expr = Box::new(self.bind_expr(left).await?);
         let right_expr = Box::new(self.bind_expr(right).await?);
 
+        self.infer_parameter_type(left, &right_expr.return_type());
+        self.infer_parameter_type(right, &left_expr.return_type());
+
         let ty = match op {
             BinaryOperator::Plus | BinaryOperator::Minus | BinaryOperator::Multiply |
             BinaryOperator::Divide | BinaryOperator::Modulo => {
@@ -193,10 +214,81 @@ impl<S: Storage> Binder<S> {
                 args,
                 ty,
             },
+            // MySQL `ON DUPLICATE KEY UPDATE c = VALUES(c)`, the same as `EXCLUDED.c`
+            "values" if self.context.excluded_table.is_some() => match &args[0] {
+                ScalarExpression::ColumnRef(column) => ScalarExpression::ColumnRef(excluded_column(column)),
+                expr => return Err(BindError::InvalidColumn(format!("{:?}", expr))),
+            },
             _ => todo!(),
         })
     }
 
+    /// `$n`, the other styles of placeholders are numbered by the parser.
+    fn bind_placeholder(&mut self, placeholder: &str) -> Result<ScalarExpression, BindError> {
+        let index = placeholder.strip_prefix('$')
+            .and_then(|index| index.parse::<usize>().ok())
+            .filter(|index| *index > 0)
+            .ok_or_else(|| BindError::InvalidParameter(placeholder.to_string()))?;
+
+        match &self.context.parameters {
+            Parameters::None => Err(BindError::InvalidParameter(
+                format!("{} outside of a prepared statement", placeholder)
+            )),
+            Parameters::Infer(types) => {
+                let mut types = types.lock();
+
+                if types.len() < index {
+                    types.resize(index, LogicalType::SqlNull);
+                }
+
+                Ok(ScalarExpression::Constant(Arc::new(DataValue::Null)))
+            }
+            Parameters::Values(values) => values.get(index - 1)
+                .cloned()
+                .map(ScalarExpression::Constant)
+                .ok_or_else(|| BindError::InvalidParameter(format!("{} has no value", placeholder))),
+            Parameters::Template { types, .. } => types.get(index - 1)
+                .map(|ty| ScalarExpression::Parameter { index, ty: *ty })
+                .ok_or_else(|| BindError::InvalidParameter(format!("{} has no value", placeholder))),
+        }
+    }
+
+    /// The type of a placeholder is taken from the first usage with a known type,
+    /// e.g. the other side of a comparison or the assigned column.
+    pub(crate) fn infer_parameter_type(&mut self, expr: &Expr, ty: &LogicalType) {
+        if let (Expr::Value(Value::Placeholder(placeholder)), Parameters::Infer(types)) = (expr, &self.context.parameters) {
+            let index = placeholder.strip_prefix('$')
+                .and_then(|index| index.parse::<usize>().ok());
+            let mut types = types.lock();
+
+            if let Some(parameter_type) = index.and_then(|index| types.get_mut(index - 1)) {
+                if *parameter_type == LogicalType::SqlNull {
+                    *parameter_type = *ty;
+                }
+            }
+        }
+    }
+
+    /// The column renamed by a view refers to the column of the table under it,
+    /// so the column is still pruned and pushed down by the optimizer.
+    pub(crate) fn column_ref_expr(column: ColumnRef) -> ScalarExpression {
+        match &column.ref_expr {
+            Some(expr) if Self::aliased_table_column(expr).is_some() => expr.clone(),
+            _ => ScalarExpression::ColumnRef(column),
+        }
+    }
+
+    /// The column of a table renamed by the alias, e.g. `a AS x`.
+    pub(crate) fn aliased_table_column(expr: &ScalarExpression) -> Option<&ColumnRef> {
+        match expr {
+            ScalarExpression::Alias { expr, .. } => match expr.as_ref() {
+                ScalarExpression::ColumnRef(col) if col.table_name.is_some() => Some(col),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     fn wildcard_expr() -> ScalarExpression {
         ScalarExpression::Constant(Arc::new(DataValue::Utf8(Some("*".to_string()))))
     }
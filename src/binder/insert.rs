@@ -1,34 +1,35 @@
 use std::slice;
 use std::sync::Arc;
-use sqlparser::ast::{Expr, Ident, ObjectName};
-use crate::binder::{Binder, BindError, lower_case_name, split_name};
-use crate::catalog::ColumnRef;
+use sqlparser::ast::{ConflictTarget, DoUpdate, Ident, ObjectName, OnConflictAction, OnInsert, Query, SetExpr};
+use crate::binder::{Binder, BinderContext, BindError, Parameters, ValuesSlot};
+use crate::catalog::{ColumnRef, TableName};
 use crate::expression::ScalarExpression;
 use crate::expression::value_compute::unary_op;
 use crate::planner::LogicalPlan;
-use crate::planner::operator::insert::InsertOperator;
+use crate::planner::operator::insert::{ConflictAction, InsertOperator, OnConflict};
 use crate::planner::operator::Operator;
 use crate::planner::operator::values::ValuesOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::value::{DataValue, ValueRef};
+use crate::types::tuple::Tuple;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     pub(crate) async fn bind_insert(
         &mut self,
         name: ObjectName,
         idents: &[Ident],
-        expr_rows: &Vec<Vec<Expr>>,
-        is_overwrite: bool
+        source: &Query,
+        is_overwrite: bool,
+        on: &Option<OnInsert>
     ) -> Result<LogicalPlan, BindError> {
-        let name = lower_case_name(&name);
-        let (_, name) = split_name(&name)?;
-        let table_name = Arc::new(name.to_string());
+        let table_name = self.bind_table_name(&name)?;
+        self.check_writable(&table_name)?;
 
-        if let Some(table) = self.context.storage.table(&table_name).await {
+        if let Some(table) = self.context.transaction.table(&table_name) {
             let mut columns = Vec::new();
 
             if idents.is_empty() {
-                columns = table.all_columns();
+                columns = table.visible_columns();
             } else {
                 let bind_table_name = Some(table_name.to_string());
                 for ident in idents {
@@ -41,13 +42,30 @@ impl<S: Storage> Binder<S> {
                     }
                 }
             }
+            let on_conflict = match on {
+                Some(on) => Some(self.bind_on_conflict(&table_name, on).await?),
+                None => None,
+            };
+            let values = match source.body.as_ref() {
+                SetExpr::Values(values) => values,
+                _ => {
+                    let query_plan = self.bind_query(source).await?;
+                    self.check_query_output(&query_plan, &columns)?;
+
+                    return Ok(Self::bind_insert_plan(table_name, columns, is_overwrite, on_conflict, query_plan));
+                }
+            };
+            let expr_rows = &values.rows;
             let mut rows = Vec::with_capacity(expr_rows.len());
 
             for expr_row in expr_rows {
                 let mut row = Vec::with_capacity(expr_row.len());
 
                 for (i, expr) in expr_row.into_iter().enumerate() {
-                    match &self.bind_expr(expr).await? {
+                    let value_expr = self.bind_expr(expr).await?;
+                    self.infer_parameter_type(expr, columns[i].datatype());
+
+                    match &value_expr {
                         ScalarExpression::Constant(value) => {
                             // Check if the value length is too long
                             value.check_len(columns[i].datatype())?;
@@ -55,6 +73,14 @@ impl<S: Storage> Binder<S> {
                                 .cast(columns[i].datatype())?;
                             row.push(Arc::new(cast_value))
                         },
+                        ScalarExpression::Parameter { index, .. } => {
+                            let Parameters::Template { values_slots, .. } = &self.context.parameters else {
+                                unreachable!()
+                            };
+                            values_slots.lock().push(ValuesSlot { row: rows.len(), column: i, index: *index });
+
+                            row.push(Arc::new(DataValue::none(columns[i].datatype())))
+                        }
                         ScalarExpression::Unary { expr, op, .. } => {
                             if let ScalarExpression::Constant(value) = expr.as_ref() {
                                 row.push(Arc::new(unary_op(value, op)?))
@@ -68,22 +94,152 @@ impl<S: Storage> Binder<S> {
 
                 rows.push(row);
             }
-            let values_plan = self.bind_values(rows, columns);
+            let values_plan = self.bind_values(rows, columns.clone());
 
-            Ok(LogicalPlan {
-                operator: Operator::Insert(
-                    InsertOperator {
-                        table_name,
-                        is_overwrite,
-                    }
-                ),
-                childrens: vec![values_plan],
-            })
+            Ok(Self::bind_insert_plan(table_name, columns, is_overwrite, on_conflict, values_plan))
         } else {
             Err(BindError::InvalidTable(format!("not found table {}", table_name)))
         }
     }
 
+    pub(crate) fn bind_insert_plan(
+        table_name: TableName,
+        columns: Vec<ColumnRef>,
+        is_overwrite: bool,
+        on_conflict: Option<OnConflict>,
+        children: LogicalPlan
+    ) -> LogicalPlan {
+        LogicalPlan {
+            operator: Operator::Insert(
+                InsertOperator {
+                    table_name,
+                    columns,
+                    is_overwrite,
+                    on_conflict,
+                }
+            ),
+            childrens: vec![children],
+        }
+    }
+
+    /// `ON CONFLICT` and `ON DUPLICATE KEY UPDATE`, the expressions are bound on the target table
+    /// and the `EXCLUDED` pseudo-table.
+    async fn bind_on_conflict(
+        &mut self,
+        table_name: &TableName,
+        on: &OnInsert
+    ) -> Result<OnConflict, BindError> {
+        let mut binder = Binder::new(
+            BinderContext::new(self.context.transaction)
+                .with_parameters(self.context.parameters.clone())
+                .with_search_path(self.context.search_path.clone())
+        );
+        let _ = binder._bind_single_table_ref(None, table_name).await?;
+        binder.context.excluded_table = Some(table_name.clone());
+
+        let (conflict_target, action) = match on {
+            OnInsert::DuplicateKeyUpdate(assignments) => {
+                let value_exprs = binder.bind_assignments(table_name, assignments).await?;
+
+                return Ok(OnConflict {
+                    target_columns: vec![],
+                    action: ConflictAction::DoUpdate { value_exprs, filter: None },
+                });
+            }
+            OnInsert::OnConflict(sqlparser::ast::OnConflict { conflict_target, action }) => (conflict_target, action),
+            _ => return Err(BindError::UnsupportedStmt(on.to_string())),
+        };
+        let mut target_columns = Vec::new();
+
+        match conflict_target {
+            Some(ConflictTarget::Columns(idents)) => {
+                let bind_table_name = Some(table_name.to_string());
+
+                for ident in idents {
+                    match binder.bind_column_ref_from_identifiers(
+                        slice::from_ref(ident),
+                        bind_table_name.as_ref()
+                    ).await? {
+                        ScalarExpression::ColumnRef(catalog) => {
+                            if !catalog.desc.is_primary && !catalog.desc.is_unique {
+                                return Err(BindError::InvalidColumn(
+                                    format!("{} is neither primary key nor unique", catalog.name)
+                                ));
+                            }
+                            target_columns.push(catalog)
+                        },
+                        _ => unreachable!()
+                    }
+                }
+            }
+            Some(ConflictTarget::OnConstraint(name)) => {
+                return Err(BindError::UnsupportedStmt(format!("ON CONFLICT ON CONSTRAINT {}", name)));
+            }
+            None => (),
+        }
+        let action = match action {
+            OnConflictAction::DoNothing => ConflictAction::DoNothing,
+            OnConflictAction::DoUpdate(DoUpdate { assignments, selection }) => {
+                let value_exprs = binder.bind_assignments(table_name, assignments).await?;
+                let filter = match selection {
+                    Some(predicate) => Some(binder.bind_expr(predicate).await?),
+                    None => None,
+                };
+
+                ConflictAction::DoUpdate { value_exprs, filter }
+            }
+        };
+
+        Ok(OnConflict { target_columns, action })
+    }
+
+    /// The output columns of the query must be castable to the target columns one by one.
+    pub(crate) fn check_query_output(
+        &self,
+        query_plan: &LogicalPlan,
+        columns: &[ColumnRef]
+    ) -> Result<(), BindError> {
+        let output_columns = self.query_output_columns(query_plan);
+
+        if output_columns.len() != columns.len() {
+            return Err(BindError::ColumnCountMismatch(columns.len(), output_columns.len()));
+        }
+        for (output_column, column) in output_columns.iter().zip(columns) {
+            let (from, to) = (output_column.datatype(), column.datatype());
+
+            if DataValue::none(from).cast(to).is_err() {
+                return Err(BindError::ColumnTypeMismatch(format!("{:?}", from), format!("{:?}", to)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The columns output by the top projection of a bound query.
+    pub(crate) fn query_output_columns(&self, query_plan: &LogicalPlan) -> Vec<ColumnRef> {
+        match &query_plan.operator {
+            Operator::Project(op) => {
+                // Tips: the output of Aggregate is AggCall First
+                let empty_tuple = Tuple { id: None, columns: vec![], values: vec![] };
+                let tuple = Tuple {
+                    id: None,
+                    columns: self.context.agg_calls
+                        .iter()
+                        .chain(self.context.group_by_exprs.iter())
+                        .map(|expr| expr.output_columns(&empty_tuple))
+                        .collect(),
+                    values: vec![],
+                };
+
+                op.columns
+                    .iter()
+                    .map(|expr| expr.output_columns(&tuple))
+                    .collect()
+            }
+            _ => self.query_output_columns(&query_plan.childrens[0]),
+        }
+    }
+
     pub(crate) fn bind_values(
         &mut self,
         rows: Vec<Vec<ValueRef>>,
@@ -1,50 +1,128 @@
 pub mod aggregate;
+mod create_schema;
 mod create_table;
+mod create_view;
 pub mod expr;
 mod select;
 mod insert;
 mod update;
 mod delete;
+mod drop_schema;
 mod drop_table;
+mod drop_view;
 mod truncate;
+mod refresh_materialized_view;
 mod distinct;
 mod show;
 
-use std::collections::BTreeMap;
-use sqlparser::ast::{Ident, ObjectName, ObjectType, SetExpr, Statement};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use parking_lot::Mutex;
+use sqlparser::ast::{Ident, ObjectName, ObjectType, SelectItem, ShowCreateObject, Statement};
 
-use crate::catalog::{DEFAULT_SCHEMA_NAME, CatalogError, TableName, TableCatalog};
+use crate::catalog::{DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, CatalogError, TableName, TableCatalog, ColumnRef, INFORMATION_SCHEMA_NAME, split_table_name, table_name_in_schema, ViewCatalog};
 use crate::expression::ScalarExpression;
 use crate::planner::LogicalPlan;
+use crate::planner::operator::delete::DeleteOperator;
+use crate::planner::operator::insert::InsertOperator;
+use crate::planner::operator::Operator;
+use crate::planner::operator::update::UpdateOperator;
 use crate::planner::operator::join::JoinType;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::errors::TypeError;
+use crate::types::LogicalType;
+use crate::types::value::ValueRef;
 
 pub enum InputRefType {
     AggCall,
     GroupBy
 }
 
+/// A column merged by `JOIN ... USING` or `NATURAL JOIN`,
+/// which replaces the join columns of both sides in `SELECT *` and unqualified references.
 #[derive(Clone)]
-pub struct BinderContext<S: Storage> {
-    pub(crate) storage: S,
+pub struct UsingColumn {
+    pub(crate) name: String,
+    pub(crate) expr: ScalarExpression,
+    pub(crate) hidden_columns: Vec<ColumnRef>,
+}
+
+/// The parameters `$1, $2, ...` of a prepared statement.
+#[derive(Clone, Default)]
+pub enum Parameters {
+    /// Not a prepared statement, the placeholders are invalid
+    #[default]
+    None,
+    /// The placeholders are bound as NULL to infer their types from the usages,
+    /// `SqlNull` for the unknown ones
+    Infer(Arc<Mutex<Vec<LogicalType>>>),
+    Values(Vec<ValueRef>),
+    /// The placeholders are bound as `ScalarExpression::Parameter` of the inferred types to cache the plan,
+    /// and the ones of `INSERT ... VALUES` are collected as slots of the `Values` rows
+    Template {
+        types: Vec<LogicalType>,
+        values_slots: Arc<Mutex<Vec<ValuesSlot>>>,
+    },
+}
+
+/// The value at `row` and `column` of the `Values` in a cached plan, which is given by the parameter `$index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValuesSlot {
+    pub(crate) row: usize,
+    pub(crate) column: usize,
+    pub(crate) index: usize,
+}
+
+#[derive(Clone)]
+pub struct BinderContext<'a, T: Transaction> {
+    pub(crate) transaction: &'a T,
     pub(crate) bind_table: BTreeMap<TableName, (TableCatalog, Option<JoinType>)>,
+    pub(crate) using_columns: Vec<UsingColumn>,
+    /// Mapping from table aliases to table names
+    pub(crate) table_aliases: BTreeMap<String, TableName>,
+    /// The target table of `INSERT ... ON CONFLICT DO UPDATE`, whose proposed row is `EXCLUDED`
+    pub(crate) excluded_table: Option<TableName>,
+    pub(crate) parameters: Parameters,
+    /// The schemas where the unqualified table names are looked up in order,
+    /// and the new tables are created in the first one
+    pub(crate) search_path: Vec<String>,
+    /// The views expanded by the statement, including the ones referenced by other views
+    pub(crate) views: Arc<Mutex<Vec<ViewCatalog>>>,
+    /// The tables scanned by the views of the query, which can not be scanned again by it
+    pub(crate) view_tables: BTreeSet<TableName>,
     aliases: BTreeMap<String, ScalarExpression>,
     group_by_exprs: Vec<ScalarExpression>,
     pub(crate) agg_calls: Vec<ScalarExpression>,
 }
 
-impl<S: Storage> BinderContext<S> {
-    pub fn new(storage: S) -> Self {
+impl<'a, T: Transaction> BinderContext<'a, T> {
+    pub fn new(transaction: &'a T) -> Self {
         BinderContext {
-            storage,
+            transaction,
             bind_table: Default::default(),
+            using_columns: vec![],
+            table_aliases: Default::default(),
+            excluded_table: None,
+            parameters: Parameters::None,
+            search_path: vec![DEFAULT_SCHEMA_NAME.to_string()],
+            views: Default::default(),
+            view_tables: Default::default(),
             aliases: Default::default(),
             group_by_exprs: vec![],
             agg_calls: Default::default(),
         }
     }
 
+    pub fn with_parameters(mut self, parameters: Parameters) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn with_search_path(mut self, search_path: Vec<String>) -> Self {
+        self.search_path = search_path;
+        self
+    }
+
     // Tips: The order of this index is based on Aggregate being bound first.
     pub fn input_ref_index(&self, ty: InputRefType) -> usize {
         match ty {
@@ -70,56 +148,61 @@ impl<S: Storage> BinderContext<S> {
     }
 }
 
-pub struct Binder<S: Storage> {
-    context: BinderContext<S>,
+pub struct Binder<'a, T: Transaction> {
+    context: BinderContext<'a, T>,
 }
 
-impl<S: Storage> Binder<S> {
-    pub fn new(context: BinderContext<S>) -> Self {
+impl<'a, T: Transaction> Binder<'a, T> {
+    pub fn new(context: BinderContext<'a, T>) -> Self {
         Binder { context }
     }
 
     pub async fn bind(mut self, stmt: &Statement) -> Result<LogicalPlan, BindError> {
         let plan = match stmt {
             Statement::Query(query) => self.bind_query(query).await?,
-            Statement::CreateTable { name, columns, constraints, .. } => {
-                self.bind_create_table(name, &columns, &constraints)?
+            Statement::CreateTable { name, columns, constraints, query, .. } => {
+                self.bind_create_table(name, &columns, &constraints, query.as_deref()).await?
             },
-            Statement::Drop { object_type, names, .. } => {
+            Statement::Drop { object_type, names, if_exists, cascade, .. } => {
                 match object_type {
                     ObjectType::Table => {
                         self.bind_drop_table(&names[0])?
                     }
+                    ObjectType::Schema => {
+                        self.bind_drop_schema(&names[0], *if_exists, *cascade)?
+                    }
+                    ObjectType::View => {
+                        self.bind_drop_view(&names[0], *if_exists, false)?
+                    }
                     _ => todo!()
                 }
             }
-            Statement::Insert { table_name, columns, source, overwrite, .. } => {
-                if let SetExpr::Values(values) = source.body.as_ref() {
-                    self.bind_insert(
-                        table_name.to_owned(),
-                        columns,
-                        &values.rows,
-                        *overwrite
-                    ).await?
-                } else {
-                    todo!()
-                }
+            Statement::CreateView { or_replace, materialized, name, columns, query, .. } => {
+                self.bind_create_view(name, columns, query, *or_replace, *materialized).await?
             }
-            Statement::Update { table, selection, assignments, .. } => {
-                if !table.joins.is_empty() {
-                    unimplemented!()
-                } else {
-                    self.bind_update(table, selection, assignments).await?
-                }
+            Statement::CreateSchema { schema_name, if_not_exists } => {
+                self.bind_create_schema(schema_name, *if_not_exists)?
             }
-            Statement::Delete { from, selection, .. } => {
-                let table = &from[0];
+            Statement::Insert { table_name, columns, source, overwrite, returning, on, .. } => {
+                let plan = self.bind_insert(
+                    table_name.to_owned(),
+                    columns,
+                    source,
+                    *overwrite,
+                    on
+                ).await?;
 
-                if !table.joins.is_empty() {
-                    unimplemented!()
-                } else {
-                    self.bind_delete(table, selection).await?
-                }
+                self.bind_dml_returning(plan, returning).await?
+            }
+            Statement::Update { table, selection, assignments, from, returning } => {
+                let plan = self.bind_update(table, selection, assignments, from).await?;
+
+                self.bind_dml_returning(plan, returning).await?
+            }
+            Statement::Delete { tables, from, using, selection, returning } => {
+                let plan = self.bind_delete(tables, &from[0], using, selection).await?;
+
+                self.bind_dml_returning(plan, returning).await?
             }
             Statement::Truncate { table_name, .. } => {
                 self.bind_truncate(table_name).await?
@@ -127,12 +210,111 @@ impl<S: Storage> Binder<S> {
             Statement::ShowTables { .. } => {
                 self.bind_show_tables()?
             }
+            Statement::ExplainTable { table_name, .. } => {
+                self.bind_show_columns(table_name)?
+            }
+            Statement::ShowColumns { table_name, filter: None, .. } => {
+                self.bind_show_columns(table_name)?
+            }
+            Statement::ShowCreate { obj_type: ShowCreateObject::Table, obj_name } => {
+                self.bind_show_create_table(obj_name)?
+            }
             _ => return Err(BindError::UnsupportedStmt(stmt.to_string())),
         };
         Ok(plan)
     }
 }
 
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// The name in storage of the table or the view, an unqualified one refers to the first schema
+    /// of the search path which has it, or the first schema if none of them has.
+    pub(crate) fn bind_table_name(&self, name: &ObjectName) -> Result<TableName, BindError> {
+        let name = lower_case_name(name);
+        let (schema, table) = split_name(&name)?;
+        let table_name = match schema {
+            Some(schema) => table_name_in_schema(schema, table),
+            None => {
+                let mut table_names = self.context.search_path
+                    .iter()
+                    .map(|schema| table_name_in_schema(schema, table));
+                let first = table_names.next().unwrap_or_else(|| table.to_string());
+
+                if self.has_relation(&first) {
+                    first
+                } else {
+                    table_names
+                        .find(|table_name| self.has_relation(table_name))
+                        .unwrap_or(first)
+                }
+            }
+        };
+
+        Ok(Arc::new(table_name))
+    }
+
+    /// Whether there is a table or a view with the name in storage.
+    fn has_relation(&self, table_name: &String) -> bool {
+        self.context.transaction.table(table_name).is_some()
+            || self.context.transaction.view(table_name).is_some()
+    }
+
+    /// The catalog of the table or the view bound by the query, or the table in storage otherwise.
+    pub(crate) fn bound_table(&self, table_name: &String) -> Option<&TableCatalog> {
+        self.context.bind_table
+            .get(table_name)
+            .map(|(table_catalog, _)| table_catalog)
+            .or_else(|| self.context.transaction.table(table_name))
+    }
+
+    /// The tables of `information_schema` are read only,
+    /// and the table of a materialized view is only written by `REFRESH MATERIALIZED VIEW`.
+    fn check_writable(&self, table_name: &TableName) -> Result<(), BindError> {
+        if split_table_name(table_name).0 == INFORMATION_SCHEMA_NAME {
+            return Err(BindError::InvalidTable(format!("{} is read only", table_name)));
+        }
+        if self.context.transaction.view(table_name).is_some_and(|view| view.materialized) {
+            return Err(BindError::InvalidTable(format!("{} is a materialized view", table_name)));
+        }
+
+        Ok(())
+    }
+
+    /// The name in storage of the new table, an unqualified one is created in the first schema of the search path.
+    fn bind_new_table_name(&self, name: &ObjectName) -> Result<TableName, BindError> {
+        let name = lower_case_name(name);
+        let (schema, table) = split_name(&name)?;
+        let schema = schema
+            .or_else(|| self.context.search_path.first().map(String::as_str))
+            .unwrap_or(DEFAULT_SCHEMA_NAME);
+
+        if table.contains('.') {
+            return Err(BindError::InvalidTableName(name.0.clone()));
+        }
+        let table_name = Arc::new(table_name_in_schema(schema, table));
+        self.check_writable(&table_name)?;
+
+        Ok(table_name)
+    }
+
+    async fn bind_dml_returning(
+        &mut self,
+        plan: LogicalPlan,
+        returning: &Option<Vec<SelectItem>>
+    ) -> Result<LogicalPlan, BindError> {
+        let table_name = match &plan.operator {
+            Operator::Insert(InsertOperator { table_name, .. })
+            | Operator::Update(UpdateOperator { table_name, .. })
+            | Operator::Delete(DeleteOperator { table_name }) => table_name.clone(),
+            _ => unreachable!("only DML has RETURNING"),
+        };
+
+        match returning {
+            Some(items) => self.bind_returning(&table_name, plan, items).await,
+            None => Ok(plan),
+        }
+    }
+}
+
 /// Convert an object name into lower case
 fn lower_case_name(name: &ObjectName) -> ObjectName {
     ObjectName(
@@ -143,11 +325,12 @@ fn lower_case_name(name: &ObjectName) -> ObjectName {
     )
 }
 
-/// Split an object name into `(schema name, table name)`.
-fn split_name(name: &ObjectName) -> Result<(&str, &str), BindError> {
+/// Split an object name into `(schema name, table name)`, the schema is `None` if unqualified.
+fn split_name(name: &ObjectName) -> Result<(Option<&str>, &str), BindError> {
     Ok(match name.0.as_slice() {
-        [table] => (DEFAULT_SCHEMA_NAME, &table.value),
-        [schema, table] => (&schema.value, &table.value),
+        [table] => (None, &table.value),
+        [schema, table] => (Some(&schema.value), &table.value),
+        [database, schema, table] if database.value == DEFAULT_DATABASE_NAME => (Some(&schema.value), &table.value),
         _ => return Err(BindError::InvalidTableName(name.0.clone())),
     })
 }
@@ -160,14 +343,22 @@ pub enum BindError {
     InvalidTable(String),
     #[error("invalid table name: {0:?}")]
     InvalidTableName(Vec<Ident>),
+    #[error("invalid schema name: {0}")]
+    InvalidSchemaName(String),
     #[error("invalid column {0}")]
     InvalidColumn(String),
     #[error("ambiguous column {0}")]
     AmbiguousColumn(String),
     #[error("binary operator types mismatch: {0} != {1}")]
     BinaryOpTypeMismatch(String, String),
+    #[error("column count mismatch: expected {0}, got {1}")]
+    ColumnCountMismatch(usize, usize),
+    #[error("column types mismatch: {0} can not be cast to {1}")]
+    ColumnTypeMismatch(String, String),
     #[error("subquery in FROM must have an alias")]
     SubqueryMustHaveAlias,
+    #[error("invalid parameter {0}")]
+    InvalidParameter(String),
     #[error("agg miss: {0}")]
     AggMiss(String),
     #[error("catalog error: {0}")]
@@ -187,26 +378,29 @@ pub mod test {
     use crate::binder::{Binder, BinderContext};
     use crate::execution::ExecutorError;
     use crate::storage::kip::KipStorage;
-    use crate::storage::{Storage, StorageError};
+    use crate::storage::{Storage, StorageError, Transaction};
 
     pub(crate) async fn build_test_catalog(path: impl Into<PathBuf> + Send) -> Result<KipStorage, StorageError> {
         let storage = KipStorage::new(path).await?;
+        let transaction = storage.transaction().await?;
 
-        let _ = storage.create_table(
+        let _ = transaction.create_table(
             Arc::new("t1".to_string()),
             vec![
                 ColumnCatalog::new("c1".to_string(), false, ColumnDesc::new(Integer, true, false), None),
                 ColumnCatalog::new("c2".to_string(), false, ColumnDesc::new(Integer, false, true), None),
             ]
-        ).await?;
+        )?;
 
-        let _ = storage.create_table(
+        let _ = transaction.create_table(
             Arc::new("t2".to_string()),
             vec![
                 ColumnCatalog::new("c3".to_string(), false, ColumnDesc::new(Integer, true, false), None),
                 ColumnCatalog::new("c4".to_string(), false, ColumnDesc::new(Integer, false, false), None),
             ]
-        ).await?;
+        )?;
+
+        transaction.commit().await?;
 
         Ok(storage)
     }
@@ -214,7 +408,8 @@ pub mod test {
     pub async fn select_sql_run(sql: &str) -> Result<LogicalPlan, ExecutorError> {
         let temp_dir = TempDir::new().expect("unable to create temporary working directory");
         let storage = build_test_catalog(temp_dir.path()).await?;
-        let binder = Binder::new(BinderContext::new(storage));
+        let transaction = storage.transaction().await?;
+        let binder = Binder::new(BinderContext::new(&transaction));
         let stmt = crate::parser::parse_sql(sql)?;
 
         Ok(binder.bind(&stmt[0]).await?)
@@ -0,0 +1,42 @@
+use sqlparser::ast::ObjectName;
+use crate::binder::{Binder, BinderContext, BindError};
+use crate::planner::LogicalPlan;
+use crate::planner::operator::Operator;
+use crate::planner::operator::refresh_materialized_view::RefreshMaterializedViewOperator;
+use crate::storage::Transaction;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// The query of the view is bound again with the search path when it was created,
+    /// and its rows are inserted into the table of the view.
+    pub(crate) async fn bind_refresh_materialized_view(
+        &mut self,
+        name: &ObjectName
+    ) -> Result<LogicalPlan, BindError> {
+        let view_name = self.bind_table_name(name)?;
+        let view = self.context.transaction
+            .view(&view_name)
+            .filter(|view| view.materialized)
+            .ok_or_else(|| BindError::InvalidTable(format!("{} is not a materialized view", view_name)))?;
+        let target_columns = self.context.transaction
+            .table(&view_name)
+            .ok_or_else(|| BindError::InvalidTable(format!("not found table {}", view_name)))?
+            .visible_columns();
+
+        let mut binder = Binder::new(
+            BinderContext::new(self.context.transaction)
+                .with_search_path(view.search_path.clone())
+        );
+        let query = Self::parse_view_query(&view)?;
+        let query_plan = binder.bind_query(&query).await?;
+        binder.check_query_output(&query_plan, &target_columns)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::RefreshMaterializedView(
+                RefreshMaterializedViewOperator {
+                    view_name: view_name.clone(),
+                }
+            ),
+            childrens: vec![Self::bind_insert_plan(view_name, target_columns, false, None, query_plan)],
+        })
+    }
+}
@@ -15,22 +15,26 @@ use crate::{
     types::value::DataValue,
 };
 
-use super::Binder;
+use super::{Binder, BinderContext, UsingColumn};
 
-use crate::catalog::{ColumnCatalog, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, TableCatalog, TableName};
+use crate::catalog::{ColumnCatalog, TableCatalog, TableName, ViewCatalog};
 use itertools::Itertools;
 use sqlparser::ast;
-use sqlparser::ast::{Distinct, Expr, Ident, Join, JoinConstraint, JoinOperator, Offset, OrderByExpr, Query, Select, SelectItem, SetExpr, TableFactor, TableWithJoins};
+use sqlparser::ast::{Distinct, Expr, Join, JoinConstraint, JoinOperator, LockClause, LockType, NonBlock, Offset, OrderByExpr, Query, Select, SelectItem, SetExpr, Statement, TableFactor, TableWithJoins};
 use crate::binder::BindError;
 use crate::execution::executor::dql::join::joins_nullable;
 use crate::expression::BinaryOperator;
-use crate::planner::LogicalPlan;
+use crate::parser::parse_sql;
+use crate::planner::{is_same_column, LogicalPlan};
 use crate::planner::operator::join::JoinCondition;
+use crate::planner::operator::lock::LockOperator;
 use crate::planner::operator::sort::{SortField, SortOperator};
-use crate::storage::Storage;
+use crate::storage::lock::{LockMode, LockWait};
+use crate::storage::Transaction;
 use crate::types::LogicalType;
+use crate::types::tuple::Tuple;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     #[async_recursion]
     pub(crate) async fn bind_query(&mut self, query: &Query) -> Result<LogicalPlan, BindError> {
         if let Some(_with) = &query.with {
@@ -38,7 +42,7 @@ impl<S: Storage> Binder<S> {
         }
 
         let mut plan = match query.body.borrow() {
-            SetExpr::Select(select) => self.bind_select(select, &query.order_by).await,
+            SetExpr::Select(select) => self.bind_select(select, &query.order_by, &query.locks).await,
             SetExpr::Query(query) => self.bind_query(query).await,
             _ => unimplemented!(),
         }?;
@@ -57,6 +61,7 @@ impl<S: Storage> Binder<S> {
         &mut self,
         select: &Select,
         orderby: &[OrderByExpr],
+        locks: &[LockClause],
     ) -> Result<LogicalPlan, BindError> {
         let mut plan = self.bind_table_ref(&select.from).await?;
 
@@ -103,11 +108,70 @@ impl<S: Storage> Binder<S> {
             plan = self.bind_sort(plan, orderby);
         }
 
+        if !locks.is_empty() {
+            plan = self.bind_lock(plan, select, locks)?;
+        }
+
         plan = self.bind_project(plan, select_list);
 
         Ok(plan)
     }
 
+    /// The lock is placed under the projection which drops the tuple ids,
+    /// and above the sort so `LIMIT` counts the rows not skipped by `SKIP LOCKED`.
+    fn bind_lock(
+        &mut self,
+        children: LogicalPlan,
+        select: &Select,
+        locks: &[LockClause],
+    ) -> Result<LogicalPlan, BindError> {
+        let unsupported = |reason: &str| Err(BindError::UnsupportedStmt(format!("{} with FOR UPDATE/SHARE", reason)));
+
+        if locks.len() > 1 {
+            return unsupported("multiple locking clauses");
+        }
+        if select.from.len() != 1 || !select.from[0].joins.is_empty() || self.context.bind_table.len() != 1 {
+            return unsupported("multiple tables");
+        }
+        if !self.context.agg_calls.is_empty()
+            || !self.context.group_by_exprs.is_empty()
+            || select.having.is_some()
+            || select.distinct.is_some()
+        {
+            return unsupported("aggregate or DISTINCT");
+        }
+        let LockClause { lock_type, of, nonblock } = &locks[0];
+        let table_name = self.context.bind_table
+            .keys()
+            .next()
+            .cloned()
+            .unwrap();
+
+        if self.context.transaction.table(&table_name).is_none() {
+            return unsupported("views");
+        }
+
+        if let Some(of) = of {
+            let name = of.0.last().map(|ident| ident.value.to_lowercase()).unwrap_or_default();
+            let is_alias = self.context.table_aliases.get(&name) == Some(&table_name);
+
+            if name != table_name.as_str() && !is_alias {
+                return Err(BindError::InvalidTable(format!("{} in FOR UPDATE/SHARE OF", name)));
+            }
+        }
+        let lock_mode = match lock_type {
+            LockType::Share => LockMode::Share,
+            LockType::Update => LockMode::Exclusive,
+        };
+        let lock_wait = match nonblock {
+            None => LockWait::Block,
+            Some(NonBlock::Nowait) => LockWait::NoWait,
+            Some(NonBlock::SkipLocked) => LockWait::SkipLocked,
+        };
+
+        Ok(LockOperator::new(table_name, lock_mode, lock_wait, children))
+    }
+
     pub(crate) async fn bind_table_ref(&mut self, from: &[TableWithJoins]) -> Result<LogicalPlan, BindError> {
         assert!(from.len() < 2, "not support yet.");
         if from.is_empty() {
@@ -117,7 +181,13 @@ impl<S: Storage> Binder<S> {
             });
         }
 
-        let TableWithJoins { relation, joins } = &from[0];
+        let (_, plan) = self.bind_table_with_joins(&from[0]).await?;
+
+        Ok(plan)
+    }
+
+    pub(crate) async fn bind_table_with_joins(&mut self, table: &TableWithJoins) -> Result<(TableName, LogicalPlan), BindError> {
+        let TableWithJoins { relation, joins } = table;
 
         let (left_name, mut plan) = self.bind_single_table_ref(relation, None).await?;
 
@@ -126,30 +196,82 @@ impl<S: Storage> Binder<S> {
                 plan = self.bind_join(left_name.clone(), plan, join).await?;
             }
         }
+        Ok((left_name, plan))
+    }
+
+    /// Join the tables of `UPDATE ... FROM` or `DELETE ... USING` to the target table,
+    /// the WHERE clause becomes the condition of the last join so that its equijoin keys are used.
+    pub(crate) async fn bind_extra_tables(
+        &mut self,
+        left_table: TableName,
+        mut plan: LogicalPlan,
+        tables: &[TableWithJoins],
+        selection: &Option<Expr>,
+    ) -> Result<LogicalPlan, BindError> {
+        let always_true = Expr::Value(ast::Value::Boolean(true));
+        let mut joins = Vec::new();
+
+        for TableWithJoins { relation, joins: table_joins } in tables {
+            joins.push(Join {
+                relation: relation.clone(),
+                join_operator: JoinOperator::Inner(JoinConstraint::On(always_true.clone())),
+            });
+            joins.extend(table_joins.iter().cloned());
+        }
+        let mut selection = selection.clone();
+
+        if let (Some(predicate), Some(Join { join_operator: JoinOperator::Inner(JoinConstraint::On(on)), .. }))
+            = (&selection, joins.last_mut())
+        {
+            *on = if on == &always_true {
+                predicate.clone()
+            } else {
+                Expr::BinaryOp {
+                    left: Box::new(on.clone()),
+                    op: ast::BinaryOperator::And,
+                    right: Box::new(predicate.clone()),
+                }
+            };
+            selection = None;
+        }
+        for join in joins.iter() {
+            plan = self.bind_join(left_table.clone(), plan, join).await?;
+        }
+        if let Some(predicate) = &selection {
+            plan = self.bind_where(plan, predicate).await?;
+        }
+
         Ok(plan)
     }
 
     async fn bind_single_table_ref(&mut self, table: &TableFactor, joint_type: Option<JoinType>) -> Result<(TableName, LogicalPlan), BindError> {
         let plan_with_name = match table {
             TableFactor::Table { name, alias, .. } => {
-                let obj_name = name
-                    .0
-                    .iter()
-                    .map(|ident| Ident::new(ident.value.to_lowercase()))
-                    .collect_vec();
-
-                let (_database, _schema, mut table): (&str, &str, &str) = match obj_name.as_slice()
-                {
-                    [table] => (DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, &table.value),
-                    [schema, table] => (DEFAULT_DATABASE_NAME, &schema.value, &table.value),
-                    [database, schema, table] => (&database.value, &schema.value, &table.value),
-                    _ => return Err(BindError::InvalidTableName(obj_name)),
+                let table_name = self.bind_table_name(name)?;
+                let table = name.0
+                    .last()
+                    .map(|ident| ident.value.to_lowercase())
+                    .unwrap_or_default();
+                let (table_name, plan) = match self.context.transaction.view(&table_name).filter(|view| !view.materialized) {
+                    Some(view) => self.bind_view(joint_type, view).await?,
+                    None => self._bind_single_table_ref(joint_type, &table_name).await?,
                 };
+
                 if let Some(alias) = alias {
-                    table = &alias.name.value;
+                    let alias_name = alias.name.value.to_lowercase();
+
+                    if self.context.table_aliases.contains_key(&alias_name) {
+                        return Err(BindError::InvalidTable(format!("{} duplicated", alias_name)));
+                    }
+                    self.context.table_aliases.insert(alias_name, table_name.clone());
+                } else if table_name.as_str() != table {
+                    // the columns of a table in other schemas can be qualified by its name without the schema
+                    let _ = self.context.table_aliases
+                        .entry(table)
+                        .or_insert_with(|| table_name.clone());
                 }
 
-                self._bind_single_table_ref(joint_type, table).await?
+                (table_name, plan)
             }
             _ => unimplemented!(),
         };
@@ -160,15 +282,14 @@ impl<S: Storage> Binder<S> {
     pub(crate) async fn _bind_single_table_ref(&mut self, joint_type: Option<JoinType>, table: &str) -> Result<(Arc<String>, LogicalPlan), BindError> {
         let table_name = Arc::new(table.to_string());
 
-        if self.context.bind_table.contains_key(&table_name) {
+        if self.context.bind_table.contains_key(&table_name) || self.context.view_tables.contains(&table_name) {
             return Err(BindError::InvalidTable(format!("{} duplicated", table)));
         }
 
         let table_catalog = self
             .context
-            .storage
+            .transaction
             .table(&table_name)
-            .await
             .ok_or_else(|| BindError::InvalidTable(format!("bind table {}", table)))?;
 
         self.context.bind_table.insert(table_name.clone(), (table_catalog.clone(), joint_type));
@@ -176,6 +297,125 @@ impl<S: Storage> Binder<S> {
         Ok((table_name.clone(), ScanOperator::new(table_name, &table_catalog)))
     }
 
+    /// Expand the view into the plan of its query, whose output columns are the columns of the view,
+    /// so that the predicates and projections on the view are optimized together with the query.
+    async fn bind_view(&mut self, joint_type: Option<JoinType>, view: ViewCatalog) -> Result<(TableName, LogicalPlan), BindError> {
+        let view_name = view.name.clone();
+
+        if self.context.bind_table.contains_key(&view_name) {
+            return Err(BindError::InvalidTable(format!("{} duplicated", view_name)));
+        }
+        let query = Self::parse_view_query(&view)?;
+        let mut context = BinderContext::new(self.context.transaction)
+            .with_search_path(view.search_path.clone());
+        context.views = self.context.views.clone();
+
+        let mut binder = Binder::new(context);
+        let mut plan = binder.bind_query(&query).await?;
+        let query_columns = binder.query_output_columns(&plan);
+
+        // the tables of the query may be recreated with other columns after the view is created
+        if query_columns.len() != view.columns.len() {
+            return Err(BindError::ColumnCountMismatch(view.columns.len(), query_columns.len()));
+        }
+        let empty_tuple = Tuple { id: None, columns: vec![], values: vec![] };
+        let exprs = query_columns
+            .iter()
+            .zip(view.columns.iter())
+            .map(|(col, name)| {
+                let expr = match col.ref_expr.as_ref().and_then(Self::aliased_table_column) {
+                    Some(table_column) => ScalarExpression::ColumnRef(table_column.clone()),
+                    None => ScalarExpression::ColumnRef(col.clone()),
+                };
+
+                if &expr.output_columns(&empty_tuple).name == name {
+                    expr
+                } else {
+                    ScalarExpression::Alias { expr: Box::new(expr), alias: name.clone() }
+                }
+            })
+            .collect_vec();
+        let columns = exprs
+            .iter()
+            .map(|expr| expr.output_columns(&empty_tuple))
+            .collect_vec();
+
+        // Tips: the outer query refers to the renamed columns of the tables by `column_ref_expr`,
+        // so the view outputs the columns of the tables themselves, which can be pruned and pushed down
+        Self::unalias_table_columns(&mut plan);
+        let exprs = exprs
+            .into_iter()
+            .map(|expr| match Self::aliased_table_column(&expr) {
+                Some(table_column) => ScalarExpression::ColumnRef(table_column.clone()),
+                None => expr,
+            })
+            .collect_vec();
+
+        if exprs.iter().any(|expr| matches!(expr, ScalarExpression::Alias { .. })) {
+            plan = LogicalPlan {
+                operator: Operator::Project(ProjectOperator { columns: exprs }),
+                childrens: vec![plan],
+            };
+        }
+        // Tips: the columns of a table scanned twice could not be told apart
+        for table_name in plan.referenced_tables() {
+            if self.context.bind_table.contains_key(&table_name) || !self.context.view_tables.insert(table_name.clone()) {
+                return Err(BindError::InvalidTable(format!("{} duplicated", table_name)));
+            }
+        }
+        let table_catalog = TableCatalog::new_view(view_name.clone(), columns)?;
+
+        self.context.bind_table.insert(view_name.clone(), (table_catalog, joint_type));
+        self.context.views.lock().push(view);
+
+        Ok((view_name, plan))
+    }
+
+    /// Replace the renamed columns of the tables in the output of the query with the columns themselves.
+    fn unalias_table_columns(plan: &mut LogicalPlan) {
+        match &mut plan.operator {
+            Operator::Project(op) => {
+                for expr in op.columns.iter_mut() {
+                    if let Some(table_column) = Self::aliased_table_column(expr) {
+                        *expr = ScalarExpression::ColumnRef(table_column.clone());
+                    }
+                }
+            }
+            _ => Self::unalias_table_columns(&mut plan.childrens[0]),
+        }
+    }
+
+    pub(crate) fn parse_view_query(view: &ViewCatalog) -> Result<Box<Query>, BindError> {
+        match parse_sql(&view.query).map(|mut stmts| stmts.pop()) {
+            Ok(Some(Statement::Query(query))) => Ok(query),
+            _ => Err(BindError::InvalidTable(format!("the query of view {}", view.name))),
+        }
+    }
+
+    /// Project the rows written by INSERT, UPDATE or DELETE, where only the columns
+    /// of the target table can be referenced.
+    pub(crate) async fn bind_returning(
+        &mut self,
+        table_name: &TableName,
+        children: LogicalPlan,
+        items: &[SelectItem],
+    ) -> Result<LogicalPlan, BindError> {
+        let mut binder = Binder::new(
+            BinderContext::new(self.context.transaction)
+                .with_parameters(self.context.parameters.clone())
+                .with_search_path(self.context.search_path.clone())
+        );
+        let _ = binder._bind_single_table_ref(None, table_name).await?;
+        let mut select_list = binder.normalize_select_item(items).await?;
+        binder.extract_select_aggregate(&mut select_list)?;
+
+        if !binder.context.agg_calls.is_empty() {
+            return Err(BindError::AggMiss("aggregate functions are not allowed in RETURNING".to_string()));
+        }
+
+        Ok(self.bind_project(children, select_list))
+    }
+
     /// Normalize select item.
     ///
     /// - Qualified name, e.g. `SELECT t.a FROM t`
@@ -190,7 +430,12 @@ impl<S: Storage> Binder<S> {
                 SelectItem::UnnamedExpr(expr) => select_items.push(self.bind_expr(expr).await?),
                 SelectItem::ExprWithAlias { expr, alias } => {
                     let expr = self.bind_expr(expr).await?;
-                    let alias_name = alias.to_string();
+                    // the unquoted alias is folded to lower case as the column names,
+                    // which the plan cache relies on to ignore the case of unquoted words
+                    let alias_name = match alias.quote_style {
+                        Some(_) => alias.to_string(),
+                        None => alias.value.to_lowercase(),
+                    };
 
                     self.context.add_alias(alias_name.clone(), expr.clone());
 
@@ -210,16 +455,26 @@ impl<S: Storage> Binder<S> {
         Ok(select_items)
     }
 
+    /// The merged columns of `USING` and `NATURAL` joins come first, and appear only once.
     async fn bind_all_column_refs(&mut self) -> Result<Vec<ScalarExpression>, BindError> {
-        let mut exprs = vec![];
+        let mut exprs = self.context.using_columns
+            .iter()
+            .map(|using_column| using_column.expr.clone())
+            .collect_vec();
+        let hidden_columns = self.context.using_columns
+            .iter()
+            .flat_map(|using_column| using_column.hidden_columns.iter())
+            .collect_vec();
+
         for table_name in self.context.bind_table.keys().cloned() {
-            let table = self.context
-                .storage
-                .table(&table_name)
-                .await
+            let table = self
+                .bound_table(&table_name)
                 .ok_or_else(|| BindError::InvalidTable(table_name.to_string()))?;
-            for col in table.all_columns() {
-                exprs.push(ScalarExpression::ColumnRef(col));
+            for col in table.visible_columns() {
+                if hidden_columns.iter().any(|hidden| is_same_column(hidden, &col)) {
+                    continue;
+                }
+                exprs.push(Self::column_ref_expr(col));
             }
         }
 
@@ -243,14 +498,12 @@ impl<S: Storage> Binder<S> {
 
         let (right_table, right) = self.bind_single_table_ref(relation, Some(join_type)).await?;
 
-        let left_table = self.context.storage
-            .table(&left_table)
-            .await
+        let left_table = self
+            .bound_table(&left_table)
             .cloned()
             .ok_or_else(|| BindError::InvalidTable(format!("Left: {} not found", left_table)))?;
-        let right_table = self.context.storage
-            .table(&right_table)
-            .await
+        let right_table = self
+            .bound_table(&right_table)
             .cloned()
             .ok_or_else(|| BindError::InvalidTable(format!("Right: {} not found", right_table)))?;
 
@@ -258,6 +511,7 @@ impl<S: Storage> Binder<S> {
             Some(constraint) => self.bind_join_constraint(
                 &left_table,
                 &right_table,
+                join_type,
                 constraint
             ).await?,
             None => JoinCondition::None,
@@ -380,7 +634,7 @@ impl<S: Storage> Binder<S> {
 
         for column in select_items {
             if let ScalarExpression::ColumnRef(col) = column {
-                if let Some(nullable) = table_force_nullable.get(col.table_name.as_ref().unwrap()) {
+                if let Some(nullable) = col.table_name.as_ref().and_then(|table_name| table_force_nullable.get(table_name)) {
                     let mut new_col = ColumnCatalog::clone(col);
                     new_col.nullable = *nullable;
 
@@ -394,6 +648,7 @@ impl<S: Storage> Binder<S> {
         &mut self,
         left_table: &TableCatalog,
         right_table: &TableCatalog,
+        join_type: JoinType,
         constraint: &JoinConstraint,
     ) -> Result<JoinCondition, BindError> {
         match constraint {
@@ -420,8 +675,86 @@ impl<S: Storage> Binder<S> {
                     filter: join_filter,
                 })
             }
-            _ => unimplemented!("not supported join constraint {:?}", constraint),
+            JoinConstraint::Using(idents) => {
+                let names = idents
+                    .iter()
+                    .map(|ident| ident.value.to_lowercase())
+                    .collect_vec();
+
+                self.bind_using_columns(left_table, right_table, join_type, names)
+            }
+            JoinConstraint::Natural => {
+                let names = left_table
+                    .visible_columns()
+                    .into_iter()
+                    .map(|col| col.name.clone())
+                    .filter(|name| right_table.contains_column(name))
+                    .collect_vec();
+
+                self.bind_using_columns(left_table, right_table, join_type, names)
+            }
+            JoinConstraint::None => unimplemented!("not supported join constraint {:?}", constraint),
+        }
+    }
+
+    /// Join on the equality of the same-named columns, and merge them into one output column:
+    /// the left column for inner and left joins, the right column for right joins,
+    /// and the first non-NULL one of both for full joins.
+    fn bind_using_columns(
+        &mut self,
+        left_table: &TableCatalog,
+        right_table: &TableCatalog,
+        join_type: JoinType,
+        names: Vec<String>,
+    ) -> Result<JoinCondition, BindError> {
+        let mut on_keys = Vec::with_capacity(names.len());
+
+        for name in names {
+            let (left_col, right_col) = match (left_table.get_column_by_name(&name), right_table.get_column_by_name(&name)) {
+                (Some(left_col), Some(right_col)) => (left_col.clone(), right_col.clone()),
+                _ => return Err(BindError::InvalidColumn(format!("{} in using", name))),
+            };
+            let using_position = self.context.using_columns
+                .iter()
+                .position(|using_column| using_column.name == name);
+            // the column merged by a previous join is used as the left key of the next one.
+            let left_expr = match using_position {
+                Some(i) => self.context.using_columns[i].expr.clone(),
+                None => ScalarExpression::ColumnRef(left_col.clone()),
+            };
+            let right_expr = ScalarExpression::ColumnRef(right_col.clone());
+
+            let expr = match join_type {
+                JoinType::Right => right_expr.clone(),
+                JoinType::Full => ScalarExpression::Alias {
+                    expr: Box::new(ScalarExpression::Coalesce {
+                        exprs: vec![left_expr.clone(), right_expr.clone()],
+                        ty: *left_col.datatype(),
+                    }),
+                    alias: name.clone(),
+                },
+                _ => left_expr.clone(),
+            };
+            on_keys.push((left_expr, right_expr));
+
+            match using_position {
+                Some(i) => {
+                    let using_column = &mut self.context.using_columns[i];
+                    using_column.expr = expr;
+                    using_column.hidden_columns.push(right_col);
+                }
+                None => self.context.using_columns.push(UsingColumn {
+                    name,
+                    expr,
+                    hidden_columns: vec![left_col, right_col],
+                }),
+            }
         }
+
+        Ok(JoinCondition::On {
+            on: on_keys,
+            filter: None,
+        })
     }
 
     /// for sqlrs
@@ -453,14 +786,14 @@ impl<S: Storage> Binder<S> {
                     match (&left, &right) {
                         // example: foo = bar
                         (ScalarExpression::ColumnRef(l), ScalarExpression::ColumnRef(r)) => {
+                            let is_column_of = |col: &ColumnCatalog, schema: &TableCatalog| {
+                                col.table_name.as_ref() == Some(&schema.name)
+                            };
+
                             // reorder left and right joins keys to pattern: (left, right)
-                            if left_schema.contains_column(&l.name)
-                                && right_schema.contains_column(&r.name)
-                            {
+                            if is_column_of(l, left_schema) && is_column_of(r, right_schema) {
                                 accum.push((left, right));
-                            } else if left_schema.contains_column(&r.name)
-                                && right_schema.contains_column(&l.name)
-                            {
+                            } else if is_column_of(r, left_schema) && is_column_of(l, right_schema) {
                                 accum.push((right, left));
                             } else {
                                 accum_filter.push(self.bind_expr(expr).await?);
@@ -509,6 +842,8 @@ impl<S: Storage> Binder<S> {
 mod tests {
     use crate::binder::test::select_sql_run;
     use crate::execution::ExecutorError;
+    use crate::planner::operator::join::JoinCondition;
+    use crate::planner::operator::Operator;
 
     #[tokio::test]
     async fn test_select_bind() -> Result<(), ExecutorError> {
@@ -563,4 +898,23 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_using_join_bind() -> Result<(), ExecutorError> {
+        // the using column must exist on both sides
+        assert!(select_sql_run("select * from t1 join t2 using (c1)").await.is_err());
+
+        // no common column
+        let plan = select_sql_run("select * from t1 natural join t2").await?;
+        match &plan.childrens[0].operator {
+            Operator::Join(op) => assert!(matches!(&op.on, JoinCondition::On { on, .. } if on.is_empty())),
+            _ => unreachable!()
+        }
+        match &plan.operator {
+            Operator::Project(op) => assert_eq!(op.columns.len(), 4),
+            _ => unreachable!()
+        }
+
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -1,10 +1,12 @@
+use sqlparser::ast::ObjectName;
 use crate::binder::{Binder, BindError};
+use crate::catalog::TableName;
 use crate::planner::LogicalPlan;
 use crate::planner::operator::Operator;
-use crate::planner::operator::show::ShowTablesOperator;
-use crate::storage::Storage;
+use crate::planner::operator::show::{ShowColumnsOperator, ShowCreateTableOperator, ShowIndexesOperator, ShowTablesOperator};
+use crate::storage::Transaction;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     pub(crate) fn bind_show_tables(
         &mut self,
     ) -> Result<LogicalPlan, BindError> {
@@ -16,4 +18,41 @@ impl<S: Storage> Binder<S> {
         };
         Ok(plan)
     }
-}
\ No newline at end of file
+
+    pub(crate) fn bind_show_columns(&mut self, name: &ObjectName) -> Result<LogicalPlan, BindError> {
+        let table_name = self.bind_show_table_name(name)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::ShowColumns(ShowColumnsOperator { table_name }),
+            childrens: vec![],
+        })
+    }
+
+    pub(crate) fn bind_show_indexes(&mut self, name: &ObjectName) -> Result<LogicalPlan, BindError> {
+        let table_name = self.bind_show_table_name(name)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::ShowIndexes(ShowIndexesOperator { table_name }),
+            childrens: vec![],
+        })
+    }
+
+    pub(crate) fn bind_show_create_table(&mut self, name: &ObjectName) -> Result<LogicalPlan, BindError> {
+        let table_name = self.bind_show_table_name(name)?;
+
+        Ok(LogicalPlan {
+            operator: Operator::ShowCreateTable(ShowCreateTableOperator { table_name }),
+            childrens: vec![],
+        })
+    }
+
+    fn bind_show_table_name(&self, name: &ObjectName) -> Result<TableName, BindError> {
+        let table_name = self.bind_table_name(name)?;
+
+        if self.context.transaction.table(&table_name).is_none() {
+            return Err(BindError::InvalidTable(format!("bind table {}", table_name)));
+        }
+
+        Ok(table_name)
+    }
+}
@@ -1,70 +1,89 @@
 use std::slice;
-use std::sync::Arc;
 use sqlparser::ast::{Assignment, Expr, TableFactor, TableWithJoins};
-use crate::binder::{Binder, BindError, lower_case_name, split_name};
+use crate::binder::{Binder, BindError};
+use crate::catalog::{ColumnRef, TableName};
 use crate::expression::ScalarExpression;
 use crate::planner::LogicalPlan;
 use crate::planner::operator::Operator;
 use crate::planner::operator::update::UpdateOperator;
-use crate::storage::Storage;
-use crate::types::value::ValueRef;
+use crate::storage::Transaction;
+use crate::types::value::DataValue;
 
-impl<S: Storage> Binder<S> {
+impl<'a, T: Transaction> Binder<'a, T> {
     pub(crate) async fn bind_update(
         &mut self,
         to: &TableWithJoins,
         selection: &Option<Expr>,
-        assignments: &[Assignment]
+        assignments: &[Assignment],
+        from: &Option<TableWithJoins>
     ) -> Result<LogicalPlan, BindError> {
         if let TableFactor::Table { name, .. } = &to.relation {
-            let name = lower_case_name(&name);
-            let (_, name) = split_name(&name)?;
-            let table_name = Arc::new(name.to_string());
-
-            let mut plan = self.bind_table_ref(slice::from_ref(to)).await?;
-
-            if let Some(predicate) = selection {
-                plan = self.bind_where(plan, predicate).await?;
-            }
-
-            let bind_table_name = Some(table_name.to_string());
+            let table_name = self.bind_table_name(name)?;
 
-            let mut columns = Vec::with_capacity(assignments.len());
-            let mut row = Vec::with_capacity(assignments.len());
+            let (left_table, mut plan) = self.bind_table_with_joins(to).await?;
 
-            for assignment in assignments {
-                let value = match self.bind_expr(&assignment.value).await? {
-                    ScalarExpression::Constant(value) => Ok::<ValueRef, BindError>(value),
-                    _ => unreachable!(),
-                }?;
+            self.check_writable(&left_table)?;
 
-                for ident in &assignment.id {
-                    match self.bind_column_ref_from_identifiers(
-                        slice::from_ref(&ident),
-                        bind_table_name.as_ref()
-                    ).await? {
-                        ScalarExpression::ColumnRef(catalog) => {
-                            value.check_len(catalog.datatype())?;
-                            columns.push(catalog);
-                            row.push(value.clone());
-                        },
-                        _ => unreachable!()
-                    }
-                }
+            if let Some(from) = from {
+                plan = self.bind_extra_tables(left_table, plan, slice::from_ref(from), selection).await?;
+            } else if let Some(predicate) = selection {
+                plan = self.bind_where(plan, predicate).await?;
             }
 
-            let values_plan = self.bind_values(vec![row], columns);
+            let value_exprs = self.bind_assignments(&table_name, assignments).await?;
 
             Ok(LogicalPlan {
                 operator: Operator::Update(
                     UpdateOperator {
-                        table_name
+                        table_name,
+                        value_exprs,
                     }
                 ),
-                childrens: vec![plan, values_plan],
+                childrens: vec![plan],
             })
         } else {
             unreachable!("only table")
         }
     }
+
+    /// The new value of each assigned column, which must be castable to the column type.
+    pub(crate) async fn bind_assignments(
+        &mut self,
+        table_name: &TableName,
+        assignments: &[Assignment]
+    ) -> Result<Vec<(ColumnRef, ScalarExpression)>, BindError> {
+        let bind_table_name = Some(table_name.to_string());
+        let mut value_exprs = Vec::with_capacity(assignments.len());
+
+        for assignment in assignments {
+            let expr = self.bind_expr(&assignment.value).await?;
+
+            for ident in &assignment.id {
+                match self.bind_column_ref_from_identifiers(
+                    slice::from_ref(&ident),
+                    bind_table_name.as_ref()
+                ).await? {
+                    ScalarExpression::ColumnRef(catalog) => {
+                        self.infer_parameter_type(&assignment.value, catalog.datatype());
+
+                        if let ScalarExpression::Constant(value) = &expr {
+                            value.check_len(catalog.datatype())?;
+                        }
+                        let ty = expr.return_type();
+
+                        if DataValue::none(&ty).cast(catalog.datatype()).is_err() {
+                            return Err(BindError::ColumnTypeMismatch(
+                                format!("{:?}", ty),
+                                format!("{:?}", catalog.datatype())
+                            ));
+                        }
+                        value_exprs.push((catalog, expr.clone()));
+                    },
+                    _ => unreachable!()
+                }
+            }
+        }
+
+        Ok(value_exprs)
+    }
 }
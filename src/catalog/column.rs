@@ -1,3 +1,4 @@
+use std::fmt;
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use sqlparser::ast::{ColumnDef, ColumnOption};
@@ -8,6 +9,9 @@ use crate::types::{ColumnId, LogicalType};
 
 pub type ColumnRef = Arc<ColumnCatalog>;
 
+/// The name of the generated primary key, see `ColumnCatalog::new_row_key`
+pub(crate) static ROW_KEY_COLUMN_NAME: &str = "_row_key";
+
 #[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
 pub struct ColumnCatalog {
     pub id: Option<ColumnId>,
@@ -46,6 +50,16 @@ impl ColumnCatalog {
         }
     }
 
+    /// The primary key generated for the table whose columns have none,
+    /// e.g. `CREATE TABLE ... AS SELECT` of a query without the primary key.
+    /// The column is hidden from `SELECT *` and the `INSERT` without columns, which generates its values.
+    pub(crate) fn new_row_key() -> ColumnCatalog {
+        let mut column_desc = ColumnDesc::new(LogicalType::UBigint, true, false);
+        column_desc.is_hidden = true;
+
+        ColumnCatalog::new(ROW_KEY_COLUMN_NAME.to_string(), false, column_desc, None)
+    }
+
     pub(crate) fn datatype(&self) -> &LogicalType {
         &self.desc.column_datatype
     }
@@ -55,6 +69,15 @@ impl ColumnCatalog {
     }
 }
 
+impl fmt::Display for ColumnCatalog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.table_name {
+            Some(table_name) => write!(f, "{}.{}", table_name, self.name),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
 impl From<ColumnDef> for ColumnCatalog {
     fn from(column_def: ColumnDef) -> Self {
         let column_name = column_def.name.to_string();
@@ -94,6 +117,7 @@ pub struct ColumnDesc {
     pub(crate) column_datatype: LogicalType,
     pub(crate) is_primary: bool,
     pub(crate) is_unique: bool,
+    pub(crate) is_hidden: bool,
 }
 
 impl ColumnDesc {
@@ -102,6 +126,7 @@ impl ColumnDesc {
             column_datatype,
             is_primary,
             is_unique,
+            is_hidden: false,
         }
     }
 }
@@ -4,16 +4,39 @@ use std::sync::Arc;
 pub(crate) use self::column::*;
 pub(crate) use self::root::*;
 pub(crate) use self::table::*;
+pub(crate) use self::view::*;
 
 /// The type of catalog reference.
 pub type RootRef = Arc<RootCatalog>;
 
 pub(crate) static DEFAULT_DATABASE_NAME: &str = "kipsql";
 pub(crate) static DEFAULT_SCHEMA_NAME: &str = "kipsql";
+/// The schema of the system tables, see `SystemTable`
+pub(crate) static INFORMATION_SCHEMA_NAME: &str = "information_schema";
+
+/// The name of the table in storage, which keeps the tables with the same name
+/// in different schemas apart, e.g. `s1.t1`.
+///
+/// The tables of the default schema are named without the schema
+pub(crate) fn table_name_in_schema(schema_name: &str, table_name: &str) -> String {
+    if schema_name == DEFAULT_SCHEMA_NAME {
+        table_name.to_string()
+    } else {
+        format!("{}.{}", schema_name, table_name)
+    }
+}
+
+/// Split the name of the table in storage into `(schema name, table name)`.
+pub(crate) fn split_table_name(table_name: &str) -> (&str, &str) {
+    table_name
+        .split_once('.')
+        .unwrap_or((DEFAULT_SCHEMA_NAME, table_name))
+}
 
 mod column;
 mod root;
 mod table;
+mod view;
 
 #[derive(thiserror::Error, Debug)]
 pub enum CatalogError {
@@ -21,4 +44,6 @@ pub enum CatalogError {
     NotFound(&'static str, String),
     #[error("duplicated {0}: {1}")]
     Duplicated(&'static str, String),
+    #[error("{0} is not empty: {1}")]
+    NotEmpty(&'static str, String),
 }
@@ -55,6 +55,22 @@ impl TableCatalog {
             .collect()
     }
 
+    /// The columns without the hidden ones, which are expanded by `SELECT *`.
+    pub(crate) fn visible_columns(&self) -> Vec<ColumnRef> {
+        self.columns
+            .values()
+            .filter(|col| !col.desc.is_hidden)
+            .cloned()
+            .collect()
+    }
+
+    /// The generated primary key, if the table is created without one.
+    pub(crate) fn row_key(&self) -> Option<&ColumnRef> {
+        self.columns
+            .values()
+            .find(|col| col.desc.is_hidden && col.desc.is_primary)
+    }
+
     /// Add a column to the table catalog.
     pub(crate) fn add_column(
         &mut self,
@@ -101,6 +117,31 @@ impl TableCatalog {
         Ok(table_catalog)
     }
 
+    /// The catalog of a view, whose columns are the output columns of its query
+    /// that keep their table names and ids to be evaluated by them.
+    pub(crate) fn new_view(
+        name: TableName,
+        columns: Vec<ColumnRef>
+    ) -> Result<TableCatalog, CatalogError> {
+        let mut table_catalog = TableCatalog {
+            name,
+            column_idxs: BTreeMap::new(),
+            columns: BTreeMap::new(),
+            indexes: vec![],
+        };
+
+        for (i, col) in columns.into_iter().enumerate() {
+            let col_id = i as ColumnId;
+
+            if table_catalog.column_idxs.insert(col.name.clone(), col_id).is_some() {
+                return Err(CatalogError::Duplicated("column", col.name.clone()));
+            }
+            table_catalog.columns.insert(col_id, col);
+        }
+
+        Ok(table_catalog)
+    }
+
     pub(crate) fn new_with_indexes(
         name: TableName,
         columns: Vec<ColumnCatalog>,
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use crate::catalog::TableName;
+
+/// A view is stored as the SQL of its query, which is bound again each time the view is referenced.
+///
+/// The rows of a materialized view are stored in the table with the same name instead,
+/// which are recomputed from the query by `REFRESH MATERIALIZED VIEW`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewCatalog {
+    pub name: TableName,
+    /// The names of the output columns of the query
+    pub columns: Vec<String>,
+    pub query: String,
+    /// The search path when the view was created, which resolves the unqualified table names of the query
+    pub search_path: Vec<String>,
+    pub materialized: bool,
+}
@@ -1,22 +1,44 @@
+use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
+use std::sync::Arc;
+use sqlparser::ast::{Expr, Ident, Statement, UnaryOperator, Value};
 use sqlparser::parser::ParserError;
+use tokio::sync::Mutex;
 
-use crate::binder::{BindError, Binder, BinderContext};
+use crate::binder::{BindError, Binder, BinderContext, Parameters, ValuesSlot};
+use crate::catalog::{ColumnCatalog, DEFAULT_SCHEMA_NAME, ViewCatalog};
 use crate::execution::ExecutorError;
-use crate::execution::executor::{build, try_collect};
+use crate::execution::executor::{build, build_analyzed, try_collect};
+use crate::execution::executor::analyze::AnalyzedPlan;
+use crate::expression::value_compute::unary_op;
 use crate::optimizer::heuristic::batch::HepBatchStrategy;
 use crate::optimizer::heuristic::optimizer::HepOptimizer;
 use crate::optimizer::OptimizerError;
 use crate::optimizer::rule::RuleImpl;
-use crate::parser::parse_sql;
+use crate::parser::{normalize_sql, parse_statements, ParsedStatement};
+use crate::planner::cache::{CachedPlan, PlanCache, PlanCacheKey, PlanCacheStats};
 use crate::planner::LogicalPlan;
-use crate::storage::{Storage, StorageError};
+use crate::planner::operator::Operator;
+use crate::storage::{Storage, StorageError, Transaction};
 use crate::storage::kip::KipStorage;
 use crate::storage::memory::MemStorage;
+use crate::types::LogicalType;
 use crate::types::tuple::Tuple;
+use crate::types::value::DataValue;
 
 pub struct Database<S: Storage> {
     pub storage: S,
+    /// The transaction started by `BEGIN`, which is shared by the following statements
+    /// until `COMMIT` or `ROLLBACK`.
+    ///
+    /// The statements are executed one by one under the lock
+    transaction: Mutex<Option<S::TransactionType>>,
+    /// The statements prepared by `PREPARE`, by their lowercase names
+    prepared_statements: parking_lot::Mutex<HashMap<String, Arc<Prepared>>>,
+    plan_cache: PlanCache,
+    /// The schemas set by `SET search_path`, see `BinderContext::search_path`
+    search_path: parking_lot::Mutex<Vec<String>>,
 }
 
 impl Database<MemStorage> {
@@ -24,7 +46,13 @@ impl Database<MemStorage> {
     pub async fn with_mem() -> Self {
         let storage = MemStorage::new();
 
-        Database { storage }
+        Database {
+            storage,
+            transaction: Mutex::new(None),
+            prepared_statements: Default::default(),
+            plan_cache: Default::default(),
+            search_path: parking_lot::Mutex::new(vec![DEFAULT_SCHEMA_NAME.to_string()]),
+        }
     }
 }
 
@@ -33,26 +61,485 @@ impl Database<KipStorage> {
     pub async fn with_kipdb(path: impl Into<PathBuf> + Send) -> Result<Self, DatabaseError> {
         let storage = KipStorage::new(path).await?;
 
-        Ok(Database { storage })
+        Ok(Database {
+            storage,
+            transaction: Mutex::new(None),
+            prepared_statements: Default::default(),
+            plan_cache: Default::default(),
+            search_path: parking_lot::Mutex::new(vec![DEFAULT_SCHEMA_NAME.to_string()]),
+        })
     }
 }
 
 impl<S: Storage> Database<S> {
     /// Create a new Database instance.
     pub fn new(storage: S) -> Result<Self, DatabaseError> {
-        Ok(Database { storage })
+        Ok(Database {
+            storage,
+            transaction: Mutex::new(None),
+            prepared_statements: Default::default(),
+            plan_cache: Default::default(),
+            search_path: parking_lot::Mutex::new(vec![DEFAULT_SCHEMA_NAME.to_string()]),
+        })
     }
 
-    /// Run SQL queries.
-    pub async fn run(&self, sql: &str) -> Result<Vec<Tuple>, DatabaseError> {
+    /// Run SQL queries, the statements are executed in order and each of them has a result.
+    ///
+    /// The execution stops at the first failed statement, whose writes are discarded.
+    /// A single statement keeps the transaction started by `BEGIN` for the later calls,
+    /// while the failure of a statement in a batch of several rolls that transaction back.
+    ///
+    /// The optimized plan of a single query or DML statement is cached by its SQL,
+    /// which is reused while the catalogs of the tables it refers to are unchanged.
+    pub async fn run(&self, sql: &str) -> Result<Vec<QueryResult>, DatabaseError> {
+        let cache_key = PlanCacheKey::new(normalize_sql(sql)?, vec![]);
+        let mut session = self.transaction.lock().await;
+
+        if let Some(result) = self.run_cached(&mut session, &cache_key).await? {
+            return Ok(vec![result]);
+        }
         // parse
-        let stmts = parse_sql(sql)?;
+        let stmts = parse_statements(sql)?;
+        let mut results = Vec::with_capacity(stmts.len());
+        let mut cache_key = (stmts.len() == 1).then_some(cache_key);
+
+        for (index, stmt) in stmts.iter().enumerate() {
+            match self.run_statement(&mut session, stmt, cache_key.take()).await {
+                Ok(result) => results.push(result),
+                Err(err) if stmts.len() > 1 => {
+                    // the later statements of the batch, e.g. `COMMIT`, are not run,
+                    // so the transaction started by `BEGIN` is rolled back as a whole
+                    let _ = session.take();
+
+                    return Err(DatabaseError::Statement { index, source: Box::new(err) });
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// The hits and misses of the plan cache used by `Database::run`.
+    pub fn plan_cache_stats(&self) -> PlanCacheStats {
+        self.plan_cache.stats()
+    }
+
+    /// Run the cached plan of the SQL, `None` if there is no valid one.
+    async fn run_cached(
+        &self,
+        session: &mut Option<S::TransactionType>,
+        cache_key: &PlanCacheKey
+    ) -> Result<Option<QueryResult>, DatabaseError> {
+        let Some(cached) = self.plan_cache.get(cache_key) else {
+            return Ok(None);
+        };
+        let (transaction, is_implicit) = self.begin_statement(session).await?;
+        if !self.plan_cache.check(cache_key, &cached, &transaction) {
+            if !is_implicit {
+                Self::end_statement(session, transaction, is_implicit, Ok(())).await?;
+            }
+            return Ok(None);
+        }
+        let result = Self::execute_cached(&transaction, cache_key, &cached, &Parameters::None).await;
+
+        Self::end_statement(session, transaction, is_implicit, result).await.map(Some)
+    }
+
+    /// Parse and bind the statement once to infer the types of its parameters,
+    /// the placeholders `$1`, `?` or `:name` are bound to the values given to
+    /// `PreparedStatement::execute` in order.
+    ///
+    /// The plan with the placeholders is cached by the SQL and the types of the parameters,
+    /// unless the type of any parameter can not be inferred.
+    pub async fn prepare(&self, sql: &str) -> Result<PreparedStatement<'_, S>, DatabaseError> {
+        let mut stmts = parse_statements(sql)?;
+
+        if stmts.len() != 1 {
+            return Err(BindError::UnsupportedStmt("prepare multiple statements".to_string()).into());
+        }
+        let stmt = match stmts.remove(0) {
+            ParsedStatement::Sql(stmt) => stmt,
+            stmt => return Err(BindError::UnsupportedStmt(format!("prepare {:?}", stmt)).into()),
+        };
+        let session = self.transaction.lock().await;
+        let prepared = self.prepare_statement(&session, stmt, vec![]).await?;
+
+        Ok(PreparedStatement { database: self, prepared })
+    }
+
+    async fn prepare_statement(
+        &self,
+        session: &Option<S::TransactionType>,
+        stmt: Statement,
+        parameter_types: Vec<LogicalType>
+    ) -> Result<Prepared, DatabaseError> {
+        if !matches!(stmt, Statement::Query(_) | Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. }) {
+            return Err(BindError::UnsupportedStmt(format!("prepare {}", stmt)).into());
+        }
+        let new_transaction;
+        let transaction = match session {
+            Some(transaction) => transaction,
+            None => {
+                new_transaction = self.storage.transaction().await?;
+                &new_transaction
+            }
+        };
+        let parameter_types = Arc::new(parking_lot::Mutex::new(parameter_types));
+        let binder = Binder::new(
+            self.binder_context(transaction)
+                .with_parameters(Parameters::Infer(parameter_types.clone()))
+        );
+        let _ = binder.bind(&stmt).await?;
+        let parameter_types = parameter_types.lock().clone();
+        let cache_key = parameter_types.iter()
+            .all(|ty| ty != &LogicalType::SqlNull)
+            .then(|| normalize_sql(&stmt.to_string()))
+            .transpose()?
+            .map(|tokens| PlanCacheKey::new(tokens, parameter_types.clone()));
+
+        Ok(Prepared { statement: stmt, parameter_types, cache_key })
+    }
+
+    async fn run_statement(
+        &self,
+        session: &mut Option<S::TransactionType>,
+        stmt: &ParsedStatement,
+        cache_key: Option<PlanCacheKey>
+    ) -> Result<QueryResult, DatabaseError> {
+        let stmt = match stmt {
+            ParsedStatement::Sql(stmt) => stmt,
+            ParsedStatement::RollbackToSavepoint(name) => {
+                Self::session_mut(session)?.rollback_to_savepoint(&name.value.to_lowercase())?;
+
+                return Ok(QueryResult::Command { tag: CommandTag::Rollback, affected_rows: 0 });
+            }
+            ParsedStatement::ReleaseSavepoint(name) => {
+                Self::session_mut(session)?.release_savepoint(&name.value.to_lowercase())?;
+
+                return Ok(QueryResult::Command { tag: CommandTag::Release, affected_rows: 0 });
+            }
+            ParsedStatement::ShowIndexes(_)
+            | ParsedStatement::RefreshMaterializedView(_)
+            | ParsedStatement::DropMaterializedView { .. } => {
+                let (transaction, is_implicit) = self.begin_statement(session).await?;
+                let result = self.run_extended(&transaction, stmt).await;
+
+                return Self::end_statement(session, transaction, is_implicit, result).await;
+            }
+        };
 
-        if stmts.is_empty() {
-            return Ok(vec![]);
+        self.run_sql(session, stmt, Parameters::None, cache_key).await
+    }
+
+    async fn run_sql(
+        &self,
+        session: &mut Option<S::TransactionType>,
+        stmt: &Statement,
+        parameters: Parameters,
+        cache_key: Option<PlanCacheKey>
+    ) -> Result<QueryResult, DatabaseError> {
+        let prepared;
+        let (stmt, parameters, cache_key) = match stmt {
+            Statement::Execute { name, parameters } => {
+                prepared = self.prepared_statements
+                    .lock()
+                    .get(&name.value.to_lowercase())
+                    .cloned()
+                    .ok_or_else(|| BindError::InvalidParameter(format!("prepared statement {} does not exist", name)))?;
+                let values = parameters.iter()
+                    .map(Self::eval_parameter)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                (&prepared.statement, prepared.parameters(values)?, prepared.cache_key.clone())
+            }
+            stmt => (stmt, parameters, cache_key),
+        };
+        let transaction_tag = match stmt {
+            Statement::StartTransaction { .. } => {
+                if session.is_some() {
+                    return Err(StorageError::TransactionError("there is already a transaction in progress").into());
+                }
+                let _ = session.insert(self.storage.transaction().await?);
+                Some(CommandTag::Begin)
+            }
+            Statement::Commit { .. } => {
+                Self::take_session(session)?.commit().await?;
+                Some(CommandTag::Commit)
+            }
+            Statement::Rollback { .. } => {
+                let _ = Self::take_session(session)?;
+                Some(CommandTag::Rollback)
+            }
+            Statement::Savepoint { name } => {
+                Self::session_mut(session)?.savepoint(name.value.to_lowercase())?;
+                Some(CommandTag::Savepoint)
+            }
+            Statement::SetVariable { variable, value, .. } if Self::is_search_path(&variable.0) => {
+                *self.search_path.lock() = Self::eval_search_path(value)?;
+                // the unqualified table names of the cached plans were resolved by the old search path
+                self.plan_cache.clear();
+                Some(CommandTag::Set)
+            }
+            Statement::Prepare { name, data_types, statement } => {
+                let name = name.value.to_lowercase();
+
+                if self.prepared_statements.lock().contains_key(&name) {
+                    return Err(BindError::InvalidParameter(format!("prepared statement {} already exists", name)).into());
+                }
+                let parameter_types = data_types.iter()
+                    .map(|data_type| LogicalType::try_from(data_type.clone()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(BindError::from)?;
+                let prepared = self.prepare_statement(session, *statement.clone(), parameter_types).await?;
+
+                let _ = self.prepared_statements.lock().insert(name, Arc::new(prepared));
+                Some(CommandTag::Prepare)
+            }
+            Statement::Deallocate { name, .. } => {
+                let name = name.value.to_lowercase();
+                let mut prepared_statements = self.prepared_statements.lock();
+
+                if name == "all" {
+                    prepared_statements.clear();
+                } else if prepared_statements.remove(&name).is_none() {
+                    return Err(BindError::InvalidParameter(format!("prepared statement {} does not exist", name)).into());
+                }
+                Some(CommandTag::Deallocate)
+            }
+            _ => None,
+        };
+        if let Some(tag) = transaction_tag {
+            return Ok(QueryResult::Command { tag, affected_rows: 0 });
+        }
+        if let Statement::ShowVariable { variable } = stmt {
+            if Self::is_search_path(variable) {
+                let column = Arc::new(ColumnCatalog::new_dummy("SEARCH_PATH".to_string()));
+                let search_path = self.search_path.lock().join(", ");
+
+                return Ok(QueryResult::Rows(vec![Tuple {
+                    id: None,
+                    columns: vec![column],
+                    values: vec![Arc::new(DataValue::Utf8(Some(search_path)))],
+                }]));
+            }
         }
+        if let Statement::Explain { analyze, verbose, statement, .. } = stmt {
+            let (transaction, is_implicit) = self.begin_statement(session).await?;
+            let tuples = self.explain(&transaction, statement, parameters, *analyze, *verbose).await;
 
-        let binder = Binder::new(BinderContext::new(self.storage.clone()));
+            return Self::end_statement(session, transaction, is_implicit, tuples).await.map(QueryResult::Rows);
+        }
+        let (transaction, is_implicit) = self.begin_statement(session).await?;
+        let result = self.plan_and_execute(&transaction, stmt, parameters, cache_key).await;
+        let result = Self::end_statement(session, transaction, is_implicit, result).await?;
+
+        // the new table or view may hide the one with the same name in the later schemas of the search path
+        if matches!(result, QueryResult::Command { tag: CommandTag::CreateTable | CommandTag::CreateView | CommandTag::CreateMaterializedView, .. }) {
+            self.plan_cache.clear();
+        }
+
+        Ok(result)
+    }
+
+    async fn run_extended(
+        &self,
+        transaction: &S::TransactionType,
+        stmt: &ParsedStatement
+    ) -> Result<QueryResult, DatabaseError> {
+        let mut binder = Binder::new(self.binder_context(transaction));
+        let plan = match stmt {
+            ParsedStatement::ShowIndexes(name) => binder.bind_show_indexes(name)?,
+            ParsedStatement::RefreshMaterializedView(name) => {
+                let plan = binder.bind_refresh_materialized_view(name).await?;
+
+                Self::default_optimizer(plan).find_best()?
+            }
+            ParsedStatement::DropMaterializedView { name, if_exists } => {
+                binder.bind_drop_view(name, *if_exists, true)?
+            }
+            _ => unreachable!(),
+        };
+
+        Self::execute(transaction, plan).await
+    }
+
+    async fn plan_and_execute(
+        &self,
+        transaction: &S::TransactionType,
+        stmt: &Statement,
+        parameters: Parameters,
+        cache_key: Option<PlanCacheKey>
+    ) -> Result<QueryResult, DatabaseError> {
+        let is_cacheable = matches!(stmt, Statement::Query(_) | Statement::Insert { .. } | Statement::Update { .. } | Statement::Delete { .. });
+        let Some(cache_key) = cache_key.filter(|_| is_cacheable) else {
+            let (plan, _) = self.plan(transaction, stmt, parameters).await?;
+
+            return Self::execute(transaction, plan).await;
+        };
+        if let Some(cached) = self.plan_cache.get(&cache_key) {
+            if self.plan_cache.check(&cache_key, &cached, transaction) {
+                return Self::execute_cached(transaction, &cache_key, &cached, &parameters).await;
+            }
+        }
+        let (Parameters::Values(values), true) = (&parameters, cache_key.is_parameterized()) else {
+            let (plan, views) = self.plan(transaction, stmt, parameters).await?;
+            self.plan_cache.put(cache_key, &plan, vec![], views, transaction);
+
+            return Self::execute(transaction, plan).await;
+        };
+        let (mut plan, values_slots, views) = self.bind_template(transaction, stmt, cache_key.parameter_types()).await?;
+        self.plan_cache.put(cache_key, &plan, values_slots.clone(), views, transaction);
+        plan.bind_parameters(values, &values_slots).map_err(BindError::from)?;
+
+        Self::execute(transaction, Self::default_optimizer(plan).find_best()?).await
+    }
+
+    /// Execute the cached plan, the plan of a parameterized statement is optimized after the values are bound.
+    async fn execute_cached(
+        transaction: &S::TransactionType,
+        cache_key: &PlanCacheKey,
+        cached: &CachedPlan,
+        parameters: &Parameters
+    ) -> Result<QueryResult, DatabaseError> {
+        let plan = match parameters {
+            Parameters::Values(values) if cache_key.is_parameterized() => {
+                Self::default_optimizer(cached.plan(values)?).find_best()?
+            }
+            _ => cached.plan(&[])?,
+        };
+
+        Self::execute(transaction, plan).await
+    }
+
+    /// Bind the statement with the placeholders of its parameters, whose slots in `VALUES` are collected.
+    async fn bind_template(
+        &self,
+        transaction: &S::TransactionType,
+        stmt: &Statement,
+        parameter_types: &[LogicalType]
+    ) -> Result<(LogicalPlan, Vec<ValuesSlot>, Vec<ViewCatalog>), DatabaseError> {
+        let values_slots = Arc::new(parking_lot::Mutex::new(vec![]));
+        let binder_context = self.binder_context(transaction)
+            .with_parameters(Parameters::Template {
+                types: parameter_types.to_vec(),
+                values_slots: values_slots.clone(),
+            });
+        let views = binder_context.views.clone();
+        let plan = Binder::new(binder_context).bind(stmt).await?;
+
+        let values_slots = values_slots.lock().clone();
+        let views = views.lock().clone();
+
+        Ok((plan, values_slots, views))
+    }
+
+    /// The transaction of the statement, which is the implicit one if it is outside `BEGIN`.
+    async fn begin_statement(
+        &self,
+        session: &mut Option<S::TransactionType>
+    ) -> Result<(S::TransactionType, bool), DatabaseError> {
+        Ok(match session.take() {
+            Some(mut transaction) => {
+                if let Err(err) = transaction.mark_statement() {
+                    let _ = session.insert(transaction);
+
+                    return Err(err.into());
+                }
+                (transaction, false)
+            }
+            None => (self.storage.transaction().await?, true),
+        })
+    }
+
+    /// Commit the implicit transaction of the succeeded statement,
+    /// or put the transaction back to the session after discarding the writes of the failed statement.
+    async fn end_statement<R>(
+        session: &mut Option<S::TransactionType>,
+        mut transaction: S::TransactionType,
+        is_implicit: bool,
+        result: Result<R, DatabaseError>
+    ) -> Result<R, DatabaseError> {
+        if is_implicit {
+            // the writes of a transaction are discarded when it is dropped
+            if result.is_ok() {
+                transaction.commit().await?;
+            }
+            return result;
+        }
+        let ended = match result {
+            Ok(_) => transaction.release_statement(),
+            Err(_) => transaction.rollback_statement(),
+        };
+
+        match ended {
+            Ok(()) => {
+                let _ = session.insert(transaction);
+
+                result
+            }
+            // the transaction is rolled back if the writes of the failed statement can not be discarded
+            Err(err) => result.and(Err(err.into())),
+        }
+    }
+
+    /// The parameters of `EXECUTE` must be literals.
+    fn eval_parameter(expr: &Expr) -> Result<DataValue, DatabaseError> {
+        Ok(match expr {
+            Expr::Value(Value::Placeholder(_)) => return Err(BindError::InvalidParameter(expr.to_string()).into()),
+            Expr::Value(value) => value.into(),
+            Expr::Nested(expr) => Self::eval_parameter(expr)?,
+            Expr::UnaryOp { op: op @ (UnaryOperator::Minus | UnaryOperator::Plus), expr } => {
+                unary_op(&Self::eval_parameter(expr)?, &(*op).into()).map_err(BindError::from)?
+            }
+            _ => return Err(BindError::InvalidParameter(expr.to_string()).into()),
+        })
+    }
+
+    fn is_search_path(variable: &[Ident]) -> bool {
+        matches!(variable, [ident] if ident.value.eq_ignore_ascii_case("search_path"))
+    }
+
+    /// `SET search_path = s1, 's2'`, or `DEFAULT` for the default schema.
+    fn eval_search_path(value: &[Expr]) -> Result<Vec<String>, DatabaseError> {
+        if let [Expr::Identifier(ident)] = value {
+            if ident.value.eq_ignore_ascii_case("default") {
+                return Ok(vec![DEFAULT_SCHEMA_NAME.to_string()]);
+            }
+        }
+        value.iter()
+            .map(|expr| match expr {
+                Expr::Identifier(ident) => Ok(ident.value.to_lowercase()),
+                Expr::Value(Value::SingleQuotedString(name)) => Ok(name.to_lowercase()),
+                _ => Err(BindError::InvalidSchemaName(expr.to_string()).into()),
+            })
+            .collect()
+    }
+
+    fn take_session(session: &mut Option<S::TransactionType>) -> Result<S::TransactionType, DatabaseError> {
+        session.take()
+            .ok_or_else(|| StorageError::TransactionError("there is no transaction in progress").into())
+    }
+
+    fn session_mut(session: &mut Option<S::TransactionType>) -> Result<&mut S::TransactionType, DatabaseError> {
+        session.as_mut()
+            .ok_or_else(|| StorageError::TransactionError("savepoints can only be used in transaction blocks").into())
+    }
+
+    fn binder_context<'a>(&self, transaction: &'a S::TransactionType) -> BinderContext<'a, S::TransactionType> {
+        BinderContext::new(transaction).with_search_path(self.search_path.lock().clone())
+    }
+
+    /// Bind and optimize the statement.
+    async fn plan(
+        &self,
+        transaction: &S::TransactionType,
+        stmt: &Statement,
+        parameters: Parameters
+    ) -> Result<(LogicalPlan, Vec<ViewCatalog>), DatabaseError> {
+        let binder_context = self.binder_context(transaction).with_parameters(parameters);
+        let views = binder_context.views.clone();
+        let binder = Binder::new(binder_context);
 
         /// Build a logical plan.
         ///
@@ -61,16 +548,100 @@ impl<S: Storage> Database<S> {
         ///   Sort(a)
         ///     Limit(1)
         ///       Project(a,b)
-        let source_plan = binder.bind(&stmts[0]).await?;
+        let source_plan = binder.bind(stmt).await?;
         // println!("source_plan plan: {:#?}", source_plan);
 
         let best_plan = Self::default_optimizer(source_plan)
             .find_best()?;
         // println!("best_plan plan: {:#?}", best_plan);
 
-        let mut stream = build(best_plan, &self.storage);
+        let views = views.lock().clone();
 
-        Ok(try_collect(&mut stream).await?)
+        Ok((best_plan, views))
+    }
+
+    /// The optimized plan of the statement as rows of indented text,
+    /// which is preceded by the plan after each batch of the optimizer if verbose.
+    ///
+    /// With analyze, the plan is executed and each operator is annotated with its actual rows and time.
+    async fn explain(
+        &self,
+        transaction: &S::TransactionType,
+        stmt: &Statement,
+        parameters: Parameters,
+        analyze: bool,
+        verbose: bool
+    ) -> Result<Vec<Tuple>, DatabaseError> {
+        let binder = Binder::new(self.binder_context(transaction).with_parameters(parameters));
+        let source_plan = binder.bind(stmt).await?;
+        let indent = |lines: Vec<String>| lines.into_iter().map(|line| format!("  {}", line));
+
+        let mut lines = Vec::new();
+        let best_plan = if verbose {
+            let (best_plan, plans) = Self::default_optimizer(source_plan.clone()).find_best_with_batches()?;
+
+            lines.push("Source Plan:".to_string());
+            lines.extend(indent(source_plan.explain()));
+            for (name, plan) in plans {
+                lines.push(format!("{}:", name));
+                lines.extend(indent(plan.explain()));
+            }
+            best_plan
+        } else {
+            Self::default_optimizer(source_plan).find_best()?
+        };
+        if analyze {
+            let analyzed_plan = Self::analyze(transaction, best_plan).await?;
+
+            if verbose {
+                lines.push("Analyzed Plan:".to_string());
+                lines.extend(indent(analyzed_plan.explain()));
+            } else {
+                lines = analyzed_plan.explain();
+            }
+        } else if !verbose {
+            lines = best_plan.explain();
+        }
+        let column = Arc::new(ColumnCatalog::new_dummy("PLAN".to_string()));
+        let tuples = lines.into_iter()
+            .map(|line| Tuple {
+                id: None,
+                columns: vec![column.clone()],
+                values: vec![Arc::new(DataValue::Utf8(Some(line)))],
+            })
+            .collect();
+
+        Ok(tuples)
+    }
+
+    /// Execute the plan and discard its output, the plan is annotated with the actual figures.
+    async fn analyze(
+        transaction: &S::TransactionType,
+        plan: LogicalPlan
+    ) -> Result<AnalyzedPlan, DatabaseError> {
+        let (mut stream, analyzed_plan) = build_analyzed(plan, transaction);
+        let _ = try_collect(&mut stream).await?;
+
+        Ok(analyzed_plan)
+    }
+
+    /// Execute the plan with one snapshot of the transaction.
+    async fn execute(
+        transaction: &S::TransactionType,
+        plan: LogicalPlan
+    ) -> Result<QueryResult, DatabaseError> {
+        let command_tag = CommandTag::from_operator(&plan.operator);
+
+        let mut stream = build(plan, transaction);
+        let tuples = try_collect(&mut stream).await?;
+
+        let result = match command_tag {
+            // the DML executors output the rows they wrote, which are counted as affected
+            Some(tag) => QueryResult::Command { tag, affected_rows: tuples.len() },
+            None => QueryResult::Rows(tuples),
+        };
+
+        Ok(result)
     }
 
     fn default_optimizer(source_plan: LogicalPlan) -> HepOptimizer {
@@ -85,6 +656,8 @@ impl<S: Storage> Database<S> {
                 HepBatchStrategy::fix_point_topdown(10),
                 vec![
                     RuleImpl::PushPredicateThroughJoin,
+                    RuleImpl::PushPredicateThroughProject,
+                    RuleImpl::CombineFilter,
                     RuleImpl::PushPredicateIntoScan
                 ]
             )
@@ -92,6 +665,7 @@ impl<S: Storage> Database<S> {
                 "Column Pruning".to_string(),
                 HepBatchStrategy::fix_point_topdown(10),
                 vec![
+                    RuleImpl::CollapseProject,
                     RuleImpl::PushProjectThroughChild,
                     RuleImpl::PushProjectIntoScan
                 ]
@@ -114,6 +688,166 @@ impl<S: Storage> Database<S> {
                     RuleImpl::CombineFilter
                 ]
             )
+            .batch(
+                "Join Selection".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![
+                    RuleImpl::ChooseIndexLookupJoin,
+                    RuleImpl::ChooseSortMergeJoin
+                ]
+            )
+    }
+}
+
+/// A statement bound once to infer the types of its parameters.
+struct Prepared {
+    statement: Statement,
+    /// `SqlNull` for the parameters whose types can not be inferred
+    parameter_types: Vec<LogicalType>,
+    /// `None` if the type of any parameter is unknown
+    cache_key: Option<PlanCacheKey>,
+}
+
+impl Prepared {
+    /// The values are cast to the types of the parameters.
+    fn parameters(&self, values: Vec<DataValue>) -> Result<Parameters, DatabaseError> {
+        if values.len() != self.parameter_types.len() {
+            return Err(BindError::InvalidParameter(
+                format!("expected {} parameters, got {}", self.parameter_types.len(), values.len())
+            ).into());
+        }
+        let values = values.into_iter()
+            .zip(self.parameter_types.iter())
+            .map(|(value, ty)| match ty {
+                LogicalType::SqlNull => Ok(Arc::new(value)),
+                ty => value.cast(ty).map(Arc::new),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(BindError::from)?;
+
+        Ok(Parameters::Values(values))
+    }
+}
+
+/// The handle of a statement prepared by `Database::prepare`.
+pub struct PreparedStatement<'a, S: Storage> {
+    database: &'a Database<S>,
+    prepared: Prepared,
+}
+
+impl<S: Storage> PreparedStatement<'_, S> {
+    /// The types of `$1, $2, ...`, `SqlNull` if the type can not be inferred.
+    pub fn parameter_types(&self) -> &[LogicalType] {
+        &self.prepared.parameter_types
+    }
+
+    /// Execute the statement with the values of its parameters,
+    /// the writes are discarded if it fails while the transaction started by `BEGIN` is kept.
+    pub async fn execute(&self, params: &[DataValue]) -> Result<QueryResult, DatabaseError> {
+        let parameters = self.prepared.parameters(params.to_vec())?;
+        let mut session = self.database.transaction.lock().await;
+
+        self.database
+            .run_sql(&mut session, &self.prepared.statement, parameters, self.prepared.cache_key.clone())
+            .await
+    }
+}
+
+/// The result of a SQL statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryResult {
+    /// The rows output by a query, e.g. `SELECT` or DML with `RETURNING`
+    Rows(Vec<Tuple>),
+    /// The completion of a statement without output
+    Command {
+        tag: CommandTag,
+        affected_rows: usize,
+    },
+}
+
+impl QueryResult {
+    /// The output rows, which are empty for a command.
+    pub fn tuples(&self) -> &[Tuple] {
+        match self {
+            QueryResult::Rows(tuples) => tuples,
+            QueryResult::Command { .. } => &[],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandTag {
+    Insert,
+    Update,
+    Delete,
+    CreateTable,
+    DropTable,
+    Truncate,
+    CreateSchema,
+    DropSchema,
+    CreateView,
+    DropView,
+    CreateMaterializedView,
+    DropMaterializedView,
+    RefreshMaterializedView,
+    Set,
+    Begin,
+    Commit,
+    Rollback,
+    Savepoint,
+    Release,
+    Prepare,
+    Deallocate,
+}
+
+impl CommandTag {
+    fn from_operator(operator: &Operator) -> Option<Self> {
+        match operator {
+            Operator::Insert(_) => Some(CommandTag::Insert),
+            Operator::Update(_) => Some(CommandTag::Update),
+            Operator::Delete(_) => Some(CommandTag::Delete),
+            Operator::CreateTable(_) => Some(CommandTag::CreateTable),
+            Operator::DropTable(_) => Some(CommandTag::DropTable),
+            Operator::Truncate(_) => Some(CommandTag::Truncate),
+            Operator::CreateSchema(_) => Some(CommandTag::CreateSchema),
+            Operator::DropSchema(_) => Some(CommandTag::DropSchema),
+            Operator::CreateView(op) if op.view.materialized => Some(CommandTag::CreateMaterializedView),
+            Operator::CreateView(_) => Some(CommandTag::CreateView),
+            Operator::DropView(op) if op.materialized => Some(CommandTag::DropMaterializedView),
+            Operator::DropView(_) => Some(CommandTag::DropView),
+            Operator::RefreshMaterializedView(_) => Some(CommandTag::RefreshMaterializedView),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CommandTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag = match self {
+            CommandTag::Insert => "INSERT",
+            CommandTag::Update => "UPDATE",
+            CommandTag::Delete => "DELETE",
+            CommandTag::CreateTable => "CREATE TABLE",
+            CommandTag::DropTable => "DROP TABLE",
+            CommandTag::Truncate => "TRUNCATE",
+            CommandTag::CreateSchema => "CREATE SCHEMA",
+            CommandTag::DropSchema => "DROP SCHEMA",
+            CommandTag::CreateView => "CREATE VIEW",
+            CommandTag::DropView => "DROP VIEW",
+            CommandTag::CreateMaterializedView => "CREATE MATERIALIZED VIEW",
+            CommandTag::DropMaterializedView => "DROP MATERIALIZED VIEW",
+            CommandTag::RefreshMaterializedView => "REFRESH MATERIALIZED VIEW",
+            CommandTag::Set => "SET",
+            CommandTag::Begin => "BEGIN",
+            CommandTag::Commit => "COMMIT",
+            CommandTag::Rollback => "ROLLBACK",
+            CommandTag::Savepoint => "SAVEPOINT",
+            CommandTag::Release => "RELEASE",
+            CommandTag::Prepare => "PREPARE",
+            CommandTag::Deallocate => "DEALLOCATE",
+        };
+
+        write!(f, "{}", tag)
     }
 }
 
@@ -145,6 +879,12 @@ pub enum DatabaseError {
     ),
     #[error("Internal error: {0}")]
     InternalError(String),
+    #[error("statement {index} failed: {source}")]
+    Statement {
+        index: usize,
+        #[source]
+        source: Box<DatabaseError>,
+    },
     #[error("optimizer error: {0}")]
     OptimizerError(
         #[source]
@@ -153,15 +893,30 @@ pub enum DatabaseError {
     )
 }
 
+impl DatabaseError {
+    /// Whether the transaction failed on the concurrent transactions and may succeed when retried from the start,
+    /// e.g. `TransactionConflict` on commit or `Deadlock` on lock.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            DatabaseError::StorageError(err)
+            | DatabaseError::ExecutorError(ExecutorError::StorageError(err)) => err.is_retryable(),
+            DatabaseError::Statement { source, .. } => source.is_retryable(),
+            _ => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::sync::Arc;
     use tempfile::TempDir;
     use crate::catalog::{ColumnCatalog, ColumnDesc, TableName};
-    use crate::db::{Database, DatabaseError};
-    use crate::storage::{Storage, StorageError};
+    use crate::db::{CommandTag, Database, DatabaseError, QueryResult};
+    use crate::planner::cache::PlanCacheStats;
+    use crate::storage::{Storage, StorageError, Transaction};
     use crate::types::LogicalType;
     use crate::types::tuple::create_table;
+    use crate::types::value::DataValue;
 
     async fn build_table(storage: &impl Storage) -> Result<TableName, StorageError> {
         let columns = vec![
@@ -179,7 +934,11 @@ mod test {
             ),
         ];
 
-        Ok(storage.create_table(Arc::new("t1".to_string()), columns).await?)
+        let transaction = storage.transaction().await?;
+        let table_name = transaction.create_table(Arc::new("t1".to_string()), columns)?;
+        transaction.commit().await?;
+
+        Ok(table_name)
     }
 
     #[tokio::test]
@@ -208,109 +967,109 @@ mod test {
 
         println!("show tables:");
         let tuples_show_tables = kipsql.run("show tables").await?;
-        println!("{}", create_table(&tuples_show_tables));
+        println!("{}", create_table(tuples_show_tables[0].tuples()));
 
         println!("full t1:");
         let tuples_full_fields_t1 = kipsql.run("select * from t1").await?;
-        println!("{}", create_table(&tuples_full_fields_t1));
+        println!("{}", create_table(tuples_full_fields_t1[0].tuples()));
 
         println!("full t2:");
         let tuples_full_fields_t2 = kipsql.run("select * from t2").await?;
-        println!("{}", create_table(&tuples_full_fields_t2));
+        println!("{}", create_table(tuples_full_fields_t2[0].tuples()));
 
         println!("projection_and_filter:");
         let tuples_projection_and_filter = kipsql.run("select a from t1 where b > 1").await?;
-        println!("{}", create_table(&tuples_projection_and_filter));
+        println!("{}", create_table(tuples_projection_and_filter[0].tuples()));
 
         println!("projection_and_sort:");
         let tuples_projection_and_sort = kipsql.run("select * from t1 order by a, b").await?;
-        println!("{}", create_table(&tuples_projection_and_sort));
+        println!("{}", create_table(tuples_projection_and_sort[0].tuples()));
 
         println!("limit:");
         let tuples_limit = kipsql.run("select * from t1 limit 1 offset 1").await?;
-        println!("{}", create_table(&tuples_limit));
+        println!("{}", create_table(tuples_limit[0].tuples()));
 
         println!("inner join:");
         let tuples_inner_join = kipsql.run("select * from t1 inner join t2 on a = c").await?;
-        println!("{}", create_table(&tuples_inner_join));
+        println!("{}", create_table(tuples_inner_join[0].tuples()));
 
         println!("left join:");
         let tuples_left_join = kipsql.run("select * from t1 left join t2 on a = c").await?;
-        println!("{}", create_table(&tuples_left_join));
+        println!("{}", create_table(tuples_left_join[0].tuples()));
 
         println!("right join:");
         let tuples_right_join = kipsql.run("select * from t1 right join t2 on a = c").await?;
-        println!("{}", create_table(&tuples_right_join));
+        println!("{}", create_table(tuples_right_join[0].tuples()));
 
         println!("full join:");
         let tuples_full_join = kipsql.run("select * from t1 full join t2 on a = c").await?;
-        println!("{}", create_table(&tuples_full_join));
+        println!("{}", create_table(tuples_full_join[0].tuples()));
 
         println!("count agg:");
         let tuples_count_agg = kipsql.run("select count(d) from t2").await?;
-        println!("{}", create_table(&tuples_count_agg));
+        println!("{}", create_table(tuples_count_agg[0].tuples()));
 
         println!("count wildcard agg:");
         let tuples_count_wildcard_agg = kipsql.run("select count(*) from t2").await?;
-        println!("{}", create_table(&tuples_count_wildcard_agg));
+        println!("{}", create_table(tuples_count_wildcard_agg[0].tuples()));
 
         println!("count distinct agg:");
         let tuples_count_distinct_agg = kipsql.run("select count(distinct d) from t2").await?;
-        println!("{}", create_table(&tuples_count_distinct_agg));
+        println!("{}", create_table(tuples_count_distinct_agg[0].tuples()));
 
         println!("sum agg:");
         let tuples_sum_agg = kipsql.run("select sum(d) from t2").await?;
-        println!("{}", create_table(&tuples_sum_agg));
+        println!("{}", create_table(tuples_sum_agg[0].tuples()));
 
         println!("sum distinct agg:");
         let tuples_sum_distinct_agg = kipsql.run("select sum(distinct d) from t2").await?;
-        println!("{}", create_table(&tuples_sum_distinct_agg));
+        println!("{}", create_table(tuples_sum_distinct_agg[0].tuples()));
 
         println!("avg agg:");
         let tuples_avg_agg = kipsql.run("select avg(d) from t2").await?;
-        println!("{}", create_table(&tuples_avg_agg));
+        println!("{}", create_table(tuples_avg_agg[0].tuples()));
 
         println!("min_max agg:");
         let tuples_min_max_agg = kipsql.run("select min(d), max(d) from t2").await?;
-        println!("{}", create_table(&tuples_min_max_agg));
+        println!("{}", create_table(tuples_min_max_agg[0].tuples()));
 
         println!("group agg:");
         let tuples_group_agg = kipsql.run("select c, max(d) from t2 group by c having c = 1").await?;
-        println!("{}", create_table(&tuples_group_agg));
+        println!("{}", create_table(tuples_group_agg[0].tuples()));
 
         println!("alias:");
         let tuples_group_agg = kipsql.run("select c as o from t2").await?;
-        println!("{}", create_table(&tuples_group_agg));
+        println!("{}", create_table(tuples_group_agg[0].tuples()));
 
         println!("alias agg:");
         let tuples_group_agg = kipsql.run("select c, max(d) as max_d from t2 group by c having c = 1").await?;
-        println!("{}", create_table(&tuples_group_agg));
+        println!("{}", create_table(tuples_group_agg[0].tuples()));
 
         println!("time max:");
         let tuples_time_max = kipsql.run("select max(e) as max_time from t2").await?;
-        println!("{}", create_table(&tuples_time_max));
+        println!("{}", create_table(tuples_time_max[0].tuples()));
 
         println!("time where:");
         let tuples_time_where_t2 = kipsql.run("select (c + 1) from t2 where e > '2021-05-20'").await?;
-        println!("{}", create_table(&tuples_time_where_t2));
+        println!("{}", create_table(tuples_time_where_t2[0].tuples()));
 
         assert!(kipsql.run("select max(d) from t2 group by c").await.is_err());
 
         println!("distinct t1:");
         let tuples_distinct_t1 = kipsql.run("select distinct b, k from t1").await?;
-        println!("{}", create_table(&tuples_distinct_t1));
+        println!("{}", create_table(tuples_distinct_t1[0].tuples()));
 
         println!("update t1 with filter:");
         let _ = kipsql.run("update t1 set b = 0 where b = 1").await?;
         println!("after t1:");
         let update_after_full_t1 = kipsql.run("select * from t1").await?;
-        println!("{}", create_table(&update_after_full_t1));
+        println!("{}", create_table(update_after_full_t1[0].tuples()));
 
         println!("insert overwrite t1:");
         let _ = kipsql.run("insert overwrite t1 (a, b, k) values (-99, 1, 0)").await?;
         println!("after t1:");
         let insert_overwrite_after_full_t1 = kipsql.run("select * from t1").await?;
-        println!("{}", create_table(&insert_overwrite_after_full_t1));
+        println!("{}", create_table(insert_overwrite_after_full_t1[0].tuples()));
 
         assert!(kipsql.run("insert overwrite t1 (a, b, k) values (-1, 1, 0)").await.is_err());
 
@@ -318,7 +1077,7 @@ mod test {
         let _ = kipsql.run("delete from t1 where b = 0").await?;
         println!("after t1:");
         let delete_after_full_t1 = kipsql.run("select * from t1").await?;
-        println!("{}", create_table(&delete_after_full_t1));
+        println!("{}", create_table(delete_after_full_t1[0].tuples()));
 
         println!("truncate t1:");
         let _ = kipsql.run("truncate t1").await?;
@@ -328,7 +1087,372 @@ mod test {
 
         println!("decimal:");
         let tuples_decimal = kipsql.run("select * from t3").await?;
-        println!("{}", create_table(&tuples_decimal));
+        println!("{}", create_table(tuples_decimal[0].tuples()));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_affected_rows() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let command = |tag, affected_rows| QueryResult::Command { tag, affected_rows };
+
+        assert_eq!(kipsql.run("create table t1 (a int primary key, b int)").await?, vec![command(CommandTag::CreateTable, 0)]);
+        assert_eq!(kipsql.run("insert into t1 values (0, 0), (1, 1), (2, 2)").await?, vec![command(CommandTag::Insert, 3)]);
+        assert_eq!(kipsql.run("update t1 set b = 10 where a > 0").await?, vec![command(CommandTag::Update, 2)]);
+        assert_eq!(kipsql.run("delete from t1 where b = 0").await?, vec![command(CommandTag::Delete, 1)]);
+        assert_eq!(kipsql.run("create table t2 as select * from t1").await?, vec![command(CommandTag::CreateTable, 2)]);
+        assert_eq!(kipsql.run("truncate t2").await?, vec![command(CommandTag::Truncate, 0)]);
+        assert_eq!(kipsql.run("drop table t2").await?, vec![command(CommandTag::DropTable, 0)]);
+
+        let tuples = kipsql.run("select * from t1").await?;
+        assert!(matches!(tuples[0], QueryResult::Rows(_)));
+        assert_eq!(tuples[0].tuples().len(), 2);
+
+        let tuples = kipsql.run("delete from t1 where a > 0 returning a").await?;
+        assert!(matches!(tuples[0], QueryResult::Rows(_)));
+        assert_eq!(tuples[0].tuples().len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_run_multiple_statements() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let results = kipsql.run(
+            "create table t1 (a int primary key, b int); insert into t1 values (0, 0), (1, 1); select * from t1;"
+        ).await?;
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1], QueryResult::Command { tag: CommandTag::Insert, affected_rows: 2 });
+        assert_eq!(results[2].tuples().len(), 2);
+
+        let err = kipsql.run("insert into t1 values (2, 2); insert into t1 values (0, 0); insert into t1 values (3, 3)")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DatabaseError::Statement { index: 1, .. }));
+        assert_eq!(kipsql.run("select * from t1").await?[0].tuples().len(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_batch_rollback() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b int)").await?;
+        let err = kipsql.run("begin; insert into t1 values (0, 0); insert into t1 values (0, 1); commit")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, DatabaseError::Statement { index: 2, .. }));
+        assert!(kipsql.run("select * from t1").await?[0].tuples().is_empty());
+        assert!(kipsql.run("rollback").await.is_err());
+
+        let results = kipsql.run("begin; insert into t1 values (0, 0); select * from t1; commit").await?;
+        assert_eq!(results[2].tuples().len(), 1);
+        assert_eq!(results[3], QueryResult::Command { tag: CommandTag::Commit, affected_rows: 0 });
+        assert_eq!(kipsql.run("select * from t1").await?[0].tuples().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_multiple_tables() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b int unique)").await?;
+        let _ = kipsql.run("insert into t1 values (0, 0), (1, 1)").await?;
+
+        let _ = kipsql.run("begin; create table t2 (c int primary key, d int); insert into t2 select * from t1").await?;
+        let _ = kipsql.run("delete from t1 where a = 0; insert into t1 values (2, 0)").await?;
+        assert_eq!(kipsql.run("select * from t1 join t2 on b = d").await?[0].tuples().len(), 2);
+        let _ = kipsql.run("rollback").await?;
+
+        assert!(kipsql.run("select * from t2").await.is_err());
+        assert_eq!(kipsql.run("select a from t1 where b = 0").await?[0].tuples().len(), 1);
+
+        let _ = kipsql.run("begin; create table t2 (c int primary key, d int); insert into t2 select * from t1").await?;
+        let _ = kipsql.run("update t1 set b = b + 10; commit").await?;
+        assert_eq!(kipsql.run("select * from t2").await?[0].tuples().len(), 2);
+        assert_eq!(kipsql.run("select * from t1 where b >= 10").await?[0].tuples().len(), 2);
+
+        let results = kipsql.run("insert into t2 select c + 10, d from t2").await?;
+        assert_eq!(results[0], QueryResult::Command { tag: CommandTag::Insert, affected_rows: 2 });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_savepoint() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b int unique)").await?;
+        assert!(kipsql.run("savepoint s1").await.is_err());
+
+        let _ = kipsql.run("begin; insert into t1 values (0, 0); savepoint s1").await?;
+        let _ = kipsql.run("insert into t1 values (1, 1); savepoint s2; update t1 set b = b + 10").await?;
+        let results = kipsql.run("rollback to savepoint s2; select * from t1 where b < 10").await?;
+        assert_eq!(results[0], QueryResult::Command { tag: CommandTag::Rollback, affected_rows: 0 });
+        assert_eq!(results[1].tuples().len(), 2);
+
+        let _ = kipsql.run("delete from t1; rollback to s1; release savepoint s1").await?;
+        assert_eq!(kipsql.run("select * from t1").await?[0].tuples().len(), 1);
+        assert!(kipsql.run("rollback to s2").await.is_err());
+        assert!(kipsql.run("insert into t1 values (1, 1), (0, 0)").await.is_err());
+        assert_eq!(kipsql.run("select * from t1").await?[0].tuples().len(), 1);
+        let _ = kipsql.run("rollback").await?;
+        assert!(kipsql.run("select * from t1").await?[0].tuples().is_empty());
+
+        let _ = kipsql.run("begin; savepoint s1; create table t2 (c int primary key)").await?;
+        let _ = kipsql.run("insert into t2 values (0); rollback to s1; insert into t1 values (1, 1); commit").await?;
+        assert!(kipsql.run("select * from t2").await.is_err());
+        assert_eq!(kipsql.run("select * from t1").await?[0].tuples().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prepared_statement() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b varchar)").await?;
+
+        let insert = kipsql.prepare("insert into t1 values ($1, $2)").await?;
+        assert_eq!(insert.parameter_types(), &[LogicalType::Integer, LogicalType::Varchar(None)]);
+        for (a, b) in [(0, "a"), (1, "'); drop table t1; --")] {
+            let result = insert.execute(&[DataValue::Int32(Some(a)), DataValue::Utf8(Some(b.to_string()))]).await?;
+            assert_eq!(result, QueryResult::Command { tag: CommandTag::Insert, affected_rows: 1 });
+        }
+        assert!(insert.execute(&[DataValue::Int32(Some(2))]).await.is_err());
+
+        let _ = kipsql.run("begin").await?;
+        let _ = insert.execute(&[DataValue::Int32(Some(2)), DataValue::Utf8(Some("b".to_string()))]).await?;
+        assert!(insert.execute(&[DataValue::Int32(Some(0)), DataValue::Utf8(Some("b".to_string()))]).await.is_err());
+        let _ = kipsql.run("commit").await?;
+        assert_eq!(kipsql.run("select * from t1").await?[0].tuples().len(), 3);
+        let _ = kipsql.run("delete from t1 where a = 2").await?;
+
+        let select = kipsql.prepare("select b from t1 where a = ?").await?;
+        let result = select.execute(&[DataValue::Int32(Some(1))]).await?;
+        assert_eq!(result.tuples()[0].values, vec![Arc::new(DataValue::Utf8(Some("'); drop table t1; --".to_string())))]);
+
+        let update = kipsql.prepare("update t1 set b = :b where a = :a or a + 1 = :a").await?;
+        assert_eq!(update.parameter_types(), &[LogicalType::Varchar(None), LogicalType::Integer]);
+        let result = update.execute(&[DataValue::Utf8(Some("c".to_string())), DataValue::Int32(Some(1))]).await?;
+        assert_eq!(result, QueryResult::Command { tag: CommandTag::Update, affected_rows: 2 });
+        assert_eq!(select.execute(&[DataValue::Int32(Some(0))]).await?.tuples()[0].values, vec![Arc::new(DataValue::Utf8(Some("c".to_string())))]);
+
+        assert!(kipsql.run("select * from t1 where a = $1").await.is_err());
+        assert!(kipsql.prepare("create table t2 (a int primary key)").await.is_err());
+
+        let results = kipsql.run("prepare q (int) as select b from t1 where a >= $1; execute q (0); execute q (1)").await?;
+        assert_eq!(results[0], QueryResult::Command { tag: CommandTag::Prepare, affected_rows: 0 });
+        assert_eq!(results[1].tuples().len(), 2);
+        assert_eq!(results[2].tuples().len(), 1);
+        assert!(kipsql.run("prepare q as select 1").await.is_err());
+        let _ = kipsql.run("deallocate q").await?;
+        assert!(kipsql.run("execute q (0)").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_plan_cache() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b varchar)").await?;
+        let _ = kipsql.run("insert into t1 values (0, 'a'), (1, 'a  b')").await?;
+        assert_eq!(kipsql.run("select * from t1 where b = 'a'").await?[0].tuples().len(), 1);
+        assert_eq!(kipsql.run("select *\n  from t1 -- comment\n where b = 'a';").await?[0].tuples().len(), 1);
+        assert_eq!(kipsql.run("select * from t1 where b = 'a  b'").await?[0].tuples().len(), 1);
+        assert_eq!(kipsql.run("SELECT * FROM T1 WHERE B = 'a'").await?[0].tuples().len(), 1);
+        assert_eq!(kipsql.run("select * from t1 where b = 'A'").await?[0].tuples().len(), 0);
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 2, misses: 4 });
+
+        let _ = kipsql.run("drop table t1; create table t1 (a int primary key, b varchar, c int)").await?;
+        let _ = kipsql.run("insert into t1 values (0, 'a', 0)").await?;
+        assert_eq!(kipsql.run("select * from t1 where b = 'a'").await?[0].tuples()[0].values.len(), 3);
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 2, misses: 6 });
+
+        let _ = kipsql.run("begin; create table t2 (a int primary key)").await?;
+        assert!(kipsql.run("select * from t2").await?[0].tuples().is_empty());
+        let _ = kipsql.run("rollback").await?;
+        assert!(matches!(kipsql.run("select * from t2").await, Err(DatabaseError::Bind(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prepared_plan_cache() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b varchar(2))").await?;
+
+        let insert = kipsql.prepare("insert into t1 values ($1, $2)").await?;
+        for a in 0..3 {
+            let _ = insert.execute(&[DataValue::Int32(Some(a)), DataValue::Utf8(Some(a.to_string()))]).await?;
+        }
+        assert!(insert.execute(&[DataValue::Int32(Some(3)), DataValue::Utf8(Some("abc".to_string()))]).await.is_err());
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 2, misses: 1 });
+
+        let select = kipsql.prepare("select b from t1 where a = $1").await?;
+        for a in 0..3 {
+            let result = select.execute(&[DataValue::Int32(Some(a))]).await?;
+            assert_eq!(result.tuples()[0].values, vec![Arc::new(DataValue::Utf8(Some(a.to_string())))]);
+        }
+        let update = kipsql.prepare("update t1 set b = $1 where a = $2").await?;
+        let _ = update.execute(&[DataValue::Utf8(Some("c".to_string())), DataValue::Int32(Some(0))]).await?;
+        let _ = update.execute(&[DataValue::Utf8(Some("d".to_string())), DataValue::Int32(Some(1))]).await?;
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 5, misses: 3 });
+
+        let results = kipsql.run("prepare q (int) as select b from t1 where a >= $1; execute q (0); execute q (1)").await?;
+        assert_eq!(results[1].tuples().len(), 3);
+        assert_eq!(results[2].tuples().len(), 2);
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 6, misses: 4 });
+
+        let _ = kipsql.run("drop table t1; create table t1 (a int primary key, b varchar(3))").await?;
+        let _ = insert.execute(&[DataValue::Int32(Some(0)), DataValue::Utf8(Some("e".to_string()))]).await?;
+        assert_eq!(select.execute(&[DataValue::Int32(Some(0))]).await?.tuples()[0].values, vec![Arc::new(DataValue::Utf8(Some("e".to_string())))]);
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 6, misses: 6 });
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_view_plan_cache() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b int)").await?;
+        let _ = kipsql.run("insert into t1 values (0, 0), (1, 1), (2, 2)").await?;
+        let _ = kipsql.run("create view v1 as select a, b from t1 where b > 0").await?;
+        assert_eq!(kipsql.run("select * from v1").await?[0].tuples().len(), 2);
+        assert_eq!(kipsql.run("select * from v1").await?[0].tuples().len(), 2);
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 1, misses: 2 });
+
+        let _ = kipsql.run("begin; create or replace view v1 as select a, b from t1 where b > 1").await?;
+        assert_eq!(kipsql.run("select * from v1").await?[0].tuples().len(), 1);
+        let _ = kipsql.run("rollback").await?;
+        assert_eq!(kipsql.run("select * from v1").await?[0].tuples().len(), 2);
+        assert_eq!(kipsql.plan_cache_stats(), PlanCacheStats { hits: 1, misses: 4 });
+
+        let _ = kipsql.run("drop view v1").await?;
+        assert!(matches!(kipsql.run("select * from v1").await, Err(DatabaseError::Bind(_))));
+
+        let _ = kipsql.run("create view v1 as select a, b from t1").await?;
+        assert!(kipsql.run("insert into v1 values (5, 5)").await.is_err());
+        assert!(kipsql.run("select * from v1 join t1 on v1.a = t1.a").await.is_err());
+        let _ = kipsql.run("create view v2 as select a from v1").await?;
+        assert!(kipsql.run("create or replace view v1 as select a from v2").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_materialized_view() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b int)").await?;
+        let _ = kipsql.run("insert into t1 values (0, 0), (1, 1), (2, 1)").await?;
+
+        let results = kipsql.run("create materialized view mv1 as select b, count(a) from t1 group by b").await?;
+        assert!(matches!(results[0], QueryResult::Command { tag: CommandTag::CreateMaterializedView, affected_rows: 2 }));
+        let _ = kipsql.run("insert into t1 values (3, 3)").await?;
+        assert_eq!(kipsql.run("select * from mv1").await?[0].tuples().len(), 2);
+
+        let results = kipsql.run("refresh materialized view mv1").await?;
+        assert!(matches!(results[0], QueryResult::Command { tag: CommandTag::RefreshMaterializedView, affected_rows: 3 }));
+        assert_eq!(kipsql.run("select * from mv1").await?[0].tuples().len(), 3);
+        assert_eq!(kipsql.plan_cache_stats().hits, 1);
+
+        let _ = kipsql.run("drop materialized view mv1").await?;
+        assert!(matches!(kipsql.run("select * from mv1").await, Err(DatabaseError::Bind(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mem_primary_key_join() -> Result<(), DatabaseError> {
+        let kipsql = Database::with_mem().await;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b int)").await?;
+        let _ = kipsql.run("create table t2 (c int primary key, d int)").await?;
+        let _ = kipsql.run("insert into t1 values (0, 1), (1, 3), (2, 5)").await?;
+        let _ = kipsql.run("insert into t2 values (1, 10), (3, 30)").await?;
+
+        let sql = "select a, d from t1 join t2 on t1.b = t2.c where t1.a < 2";
+        let plan = kipsql.run(&format!("explain {}", sql)).await?;
+        assert!(plan[0].tuples()[1].values[0].to_string().contains("IndexLookup primary key"));
+        assert_eq!(kipsql.run(sql).await?[0].tuples().len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_analyze() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b int unique)").await?;
+        let _ = kipsql.run("create table t2 (c int primary key, d int)").await?;
+        let _ = kipsql.run("insert into t1 values (0, 0), (1, 1), (2, 2)").await?;
+        let _ = kipsql.run("insert into t2 values (0, 1), (1, 1), (2, 3)").await?;
+
+        let explain = |result: &QueryResult| result.tuples()
+            .iter()
+            .map(|tuple| tuple.values[0].to_string())
+            .collect::<Vec<_>>();
+
+        let lines = explain(&kipsql.run("explain analyze select a from t1 where b = 1").await?[0]);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[2].starts_with("    Scan t1 -> [t1.a, t1.b], Index: uk_b [= 1] (predicted rows=1) (actual rows=1 time="));
+
+        let lines = explain(&kipsql.run("explain analyze select * from t2 limit 2").await?[0]);
+        assert!(lines[0].starts_with("Scan t2 -> [t2.c, t2.d], Limit: 2, Offset: 0 (predicted rows=2) (actual rows=2 time="));
+
+        let lines = explain(&kipsql.run("explain analyze select * from t2 join t1 on d = a where c > 0").await?[0]);
+        assert!(lines.iter().any(|line| line.contains("Strategy: IndexLookup") && line.contains("(actual rows=1 ")));
+        assert!(lines.iter().any(|line| line.trim_start().starts_with("Scan t1") && line.contains("(actual rows=1 ")));
+
+        let lines = explain(&kipsql.run("explain analyze verbose delete from t2 where c > 0").await?[0]);
+        assert_eq!(lines[0], "Source Plan:");
+        let index = lines.iter().position(|line| line == "Analyzed Plan:").unwrap();
+        assert!(lines[index + 1].starts_with("  Delete t2 (actual rows=2 time="));
+        assert_eq!(kipsql.run("select * from t2").await?[0].tuples().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_show_create_table() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql.run("create table t1 (a int primary key, b bigint unique null, c varchar(10), d decimal(5, 2) null)").await?;
+
+        let rows = |result: &QueryResult| result.tuples()
+            .iter()
+            .map(|tuple| tuple.values.iter().map(|value| value.to_string()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let columns = rows(&kipsql.run("describe t1").await?[0]);
+        let indexes = rows(&kipsql.run("show indexes from t1").await?[0]);
+        let ddl = rows(&kipsql.run("show create table t1").await?[0]).remove(0).remove(1);
+
+        let _ = kipsql.run("drop table t1").await?;
+        let _ = kipsql.run(&ddl).await?;
+        assert_eq!(rows(&kipsql.run("describe t1").await?[0]), columns);
+        assert_eq!(rows(&kipsql.run("show indexes from t1").await?[0]), indexes);
+        assert_eq!(rows(&kipsql.run("show create table t1").await?[0])[0][1], ddl);
 
         Ok(())
     }
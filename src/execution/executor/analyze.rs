@@ -0,0 +1,112 @@
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use crate::execution::executor::BoxedExecutor;
+use crate::execution::ExecutorError;
+use crate::planner::LogicalPlan;
+use crate::types::tuple::Tuple;
+
+/// The actual figures of an executor.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExecutorMetrics {
+    pub rows: usize,
+    /// The time spent in polling the executor, including its children
+    pub elapsed: Duration,
+    /// The time spent in polling the executor until its first row
+    pub first_row: Option<Duration>,
+}
+
+/// The plan tree executed by `EXPLAIN ANALYZE`, whose metrics are filled in by the executors.
+pub struct AnalyzedPlan {
+    operator: String,
+    predicted_rows: Option<usize>,
+    /// Shared with the executor, or with the parent if the operator is executed inside it,
+    /// e.g. the inner scan of `IndexLookupJoin`
+    metrics: Arc<Mutex<ExecutorMetrics>>,
+    pub childrens: Vec<AnalyzedPlan>,
+}
+
+impl AnalyzedPlan {
+    pub(crate) fn new(plan: &LogicalPlan) -> Self {
+        AnalyzedPlan {
+            operator: plan.operator.to_string(),
+            predicted_rows: plan.predicted_rows(),
+            metrics: Default::default(),
+            childrens: Vec::new(),
+        }
+    }
+
+    /// Wrap the executor of this operator to measure it.
+    pub(crate) fn measure<'a>(&self, executor: BoxedExecutor<'a>) -> BoxedExecutor<'a> {
+        Measured { input: executor, metrics: self.metrics.clone() }.boxed()
+    }
+
+    /// The metrics filled in by the parent which executes this operator inside it.
+    pub(crate) fn shared_metrics(&self) -> Arc<Mutex<ExecutorMetrics>> {
+        self.metrics.clone()
+    }
+
+    pub fn metrics(&self) -> ExecutorMetrics {
+        *self.metrics.lock()
+    }
+
+    /// The operators annotated with the predicted and actual rows, one per line,
+    /// and each child is indented under its parent.
+    pub fn explain(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        self.explain_with_indent(0, &mut lines);
+
+        lines
+    }
+
+    fn explain_with_indent(&self, indent: usize, lines: &mut Vec<String>) {
+        let mut line = format!("{}{}", "  ".repeat(indent), self.operator);
+
+        if let Some(predicted_rows) = self.predicted_rows {
+            line.push_str(&format!(" (predicted rows={})", predicted_rows));
+        }
+        let metrics = self.metrics();
+        line.push_str(&format!(
+            " (actual rows={} time={:.3}ms first row={})",
+            metrics.rows,
+            metrics.elapsed.as_secs_f64() * 1000.0,
+            metrics.first_row
+                .map(|first_row| format!("{:.3}ms", first_row.as_secs_f64() * 1000.0))
+                .unwrap_or_else(|| "none".to_string())
+        ));
+        lines.push(line);
+
+        for child in &self.childrens {
+            child.explain_with_indent(indent + 1, lines);
+        }
+    }
+}
+
+/// Count the output rows of the executor and the time spent in polling it.
+struct Measured<'a> {
+    input: BoxedExecutor<'a>,
+    metrics: Arc<Mutex<ExecutorMetrics>>,
+}
+
+impl Stream for Measured<'_> {
+    type Item = Result<Tuple, ExecutorError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let start = Instant::now();
+        let poll = self.input.poll_next_unpin(cx);
+        let mut metrics = self.metrics.lock();
+
+        metrics.elapsed += start.elapsed();
+        if let Poll::Ready(Some(Ok(_))) = &poll {
+            metrics.rows += 1;
+            if metrics.first_row.is_none() {
+                metrics.first_row = Some(metrics.elapsed);
+            }
+        }
+
+        poll
+    }
+}
@@ -0,0 +1,40 @@
+use futures_async_stream::try_stream;
+use crate::catalog::CatalogError;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::create_schema::CreateSchemaOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+
+pub struct CreateSchema {
+    op: CreateSchemaOperator
+}
+
+impl From<CreateSchemaOperator> for CreateSchema {
+    fn from(op: CreateSchemaOperator) -> Self {
+        CreateSchema {
+            op
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for CreateSchema {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl CreateSchema {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let CreateSchemaOperator { schema_name, if_not_exists } = self.op;
+
+        if transaction.has_schema(&schema_name)? {
+            if !if_not_exists {
+                Err(CatalogError::Duplicated("schema", schema_name.clone()))?;
+            }
+        } else {
+            transaction.create_schema(&schema_name)?;
+        }
+    }
+}
@@ -1,33 +1,51 @@
 use futures_async_stream::try_stream;
+use crate::catalog::{CatalogError, split_table_name};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::create_table::CreateTableOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
-pub struct CreateTable {
-    op: CreateTableOperator
+pub struct CreateTable<'a> {
+    op: CreateTableOperator,
+    /// Fill the new table, e.g. `CREATE TABLE ... AS SELECT`
+    input: Option<BoxedExecutor<'a>>
 }
 
-impl From<CreateTableOperator> for CreateTable {
-    fn from(op: CreateTableOperator) -> Self {
+impl<'a> From<(CreateTableOperator, Option<BoxedExecutor<'a>>)> for CreateTable<'a> {
+    fn from((op, input): (CreateTableOperator, Option<BoxedExecutor<'a>>)) -> Self {
         CreateTable {
-            op
+            op,
+            input
         }
     }
 }
 
-impl<S: Storage> Executor<S> for CreateTable {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for CreateTable<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
-impl CreateTable {
+impl CreateTable<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
         let CreateTableOperator { table_name, columns } = self.op;
+        let (schema_name, _) = split_table_name(&table_name);
 
-        let _ = storage.create_table(table_name, columns).await?;
+        if !transaction.has_schema(schema_name)? {
+            Err(CatalogError::NotFound("schema", schema_name.to_string()))?;
+        }
+        if transaction.view(&table_name).is_some() {
+            Err(CatalogError::Duplicated("view", table_name.to_string()))?;
+        }
+        let _ = transaction.create_table(table_name, columns)?;
+
+        if let Some(input) = self.input {
+            #[for_await]
+            for tuple in input {
+                yield tuple?;
+            }
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,54 @@
+use futures_async_stream::try_stream;
+use crate::catalog::{CatalogError, split_table_name};
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::create_view::CreateViewOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+
+pub struct CreateView<'a> {
+    op: CreateViewOperator,
+    /// Create and fill the table of a materialized view
+    input: Option<BoxedExecutor<'a>>
+}
+
+impl<'a> From<(CreateViewOperator, Option<BoxedExecutor<'a>>)> for CreateView<'a> {
+    fn from((op, input): (CreateViewOperator, Option<BoxedExecutor<'a>>)) -> Self {
+        CreateView {
+            op,
+            input
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for CreateView<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl CreateView<'_> {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
+        let CreateViewOperator { view, or_replace } = self.op;
+        let (schema_name, _) = split_table_name(&view.name);
+
+        if !transaction.has_schema(schema_name)? {
+            Err(CatalogError::NotFound("schema", schema_name.to_string()))?;
+        }
+        // Tips: a materialized view could not be replaced by a view, as its table would be left behind
+        if transaction.view(&view.name).is_some_and(|old_view| !or_replace || old_view.materialized) {
+            Err(CatalogError::Duplicated("view", view.name.to_string()))?;
+        }
+        if transaction.table(&view.name).is_some() {
+            Err(CatalogError::Duplicated("table", view.name.to_string()))?;
+        }
+        if let Some(input) = self.input {
+            #[for_await]
+            for tuple in input {
+                yield tuple?;
+            }
+        }
+        transaction.create_view(view)?;
+    }
+}
@@ -0,0 +1,63 @@
+use futures_async_stream::try_stream;
+use crate::catalog::{CatalogError, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, split_table_name};
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::drop_schema::DropSchemaOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+
+pub struct DropSchema {
+    op: DropSchemaOperator
+}
+
+impl From<DropSchemaOperator> for DropSchema {
+    fn from(op: DropSchemaOperator) -> Self {
+        DropSchema {
+            op
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for DropSchema {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl DropSchema {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let DropSchemaOperator { schema_name, if_exists, cascade } = self.op;
+
+        if schema_name == DEFAULT_SCHEMA_NAME || schema_name == INFORMATION_SCHEMA_NAME {
+            Err(ExecutorError::InternalError(format!("the schema {} can not be dropped", schema_name)))?;
+        }
+        if !transaction.has_schema(&schema_name)? {
+            if !if_exists {
+                Err(CatalogError::NotFound("schema", schema_name.clone()))?;
+            }
+            return Ok(());
+        }
+        let table_names = transaction.show_tables()?
+            .into_iter()
+            .filter(|table_name| split_table_name(table_name).0 == schema_name)
+            .collect::<Vec<_>>();
+
+        let view_names = transaction.show_views()?
+            .into_iter()
+            .map(|view| view.name)
+            .filter(|view_name| split_table_name(view_name).0 == schema_name)
+            .collect::<Vec<_>>();
+
+        if !cascade && (!table_names.is_empty() || !view_names.is_empty()) {
+            Err(CatalogError::NotEmpty("schema", schema_name.clone()))?;
+        }
+        for view_name in view_names {
+            transaction.drop_view(&view_name)?;
+        }
+        for table_name in table_names {
+            transaction.drop_table(&table_name)?;
+        }
+        transaction.drop_schema(&schema_name)?;
+    }
+}
@@ -2,7 +2,7 @@ use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::drop_table::DropTableOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
 pub struct DropTable {
@@ -17,17 +17,17 @@ impl From<DropTableOperator> for DropTable {
     }
 }
 
-impl<S: Storage> Executor<S> for DropTable {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for DropTable {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
 impl DropTable {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
         let DropTableOperator { table_name } = self.op;
 
-        storage.drop_table(&table_name).await?;
+        transaction.drop_table(&table_name)?;
     }
 }
\ No newline at end of file
@@ -0,0 +1,46 @@
+use futures_async_stream::try_stream;
+use crate::catalog::CatalogError;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::drop_view::DropViewOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+
+pub struct DropView {
+    op: DropViewOperator
+}
+
+impl From<DropViewOperator> for DropView {
+    fn from(op: DropViewOperator) -> Self {
+        DropView {
+            op
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for DropView {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl DropView {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let DropViewOperator { view_name, if_exists, materialized } = self.op;
+
+        if !transaction.view(&view_name).is_some_and(|view| view.materialized == materialized) {
+            if !if_exists {
+                let kind = if materialized { "materialized view" } else { "view" };
+
+                Err(CatalogError::NotFound(kind, view_name.to_string()))?;
+            }
+            return Ok(());
+        }
+        transaction.drop_view(&view_name)?;
+
+        if materialized {
+            transaction.drop_table(&view_name)?;
+        }
+    }
+}
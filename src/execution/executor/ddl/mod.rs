@@ -1,3 +1,8 @@
+pub(crate) mod create_schema;
 pub(crate) mod create_table;
+pub(crate) mod create_view;
+pub(crate) mod drop_schema;
 pub(crate) mod drop_table;
+pub(crate) mod drop_view;
+pub(crate) mod refresh_materialized_view;
 pub(crate) mod truncate;
\ No newline at end of file
@@ -0,0 +1,43 @@
+use futures_async_stream::try_stream;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::refresh_materialized_view::RefreshMaterializedViewOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+
+pub struct RefreshMaterializedView<'a> {
+    op: RefreshMaterializedViewOperator,
+    /// Insert the rows of the query into the table of the view
+    input: BoxedExecutor<'a>
+}
+
+impl<'a> From<(RefreshMaterializedViewOperator, BoxedExecutor<'a>)> for RefreshMaterializedView<'a> {
+    fn from((op, input): (RefreshMaterializedViewOperator, BoxedExecutor<'a>)) -> Self {
+        RefreshMaterializedView {
+            op,
+            input
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for RefreshMaterializedView<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl RefreshMaterializedView<'_> {
+    /// The old rows are deleted in the same transaction,
+    /// so they are seen by the others until the new ones are committed.
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
+        let RefreshMaterializedView { op: RefreshMaterializedViewOperator { view_name }, input } = self;
+
+        transaction.drop_data(&view_name)?;
+
+        #[for_await]
+        for tuple in input {
+            yield tuple?;
+        }
+    }
+}
@@ -2,7 +2,7 @@ use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::truncate::TruncateOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
 pub struct Truncate {
@@ -17,17 +17,17 @@ impl From<TruncateOperator> for Truncate {
     }
 }
 
-impl<S: Storage> Executor<S> for Truncate {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for Truncate {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
 impl Truncate {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
         let TruncateOperator { table_name } = self.op;
 
-        storage.drop_data(&table_name).await?;
+        transaction.drop_data(&table_name)?;
     }
 }
\ No newline at end of file
@@ -1,20 +1,22 @@
+use std::collections::HashSet;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use crate::catalog::TableName;
 use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::executor::dml::target_tuple;
 use crate::execution::ExecutorError;
 use crate::planner::operator::delete::DeleteOperator;
-use crate::storage::{Storage, Transaction};
+use crate::storage::{StorageError, Transaction};
 use crate::types::index::Index;
 use crate::types::tuple::Tuple;
 
-pub struct Delete {
+pub struct Delete<'a> {
     table_name: TableName,
-    input: BoxedExecutor,
+    input: BoxedExecutor<'a>,
 }
 
-impl From<(DeleteOperator, BoxedExecutor)> for Delete {
-    fn from((DeleteOperator { table_name }, input): (DeleteOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(DeleteOperator, BoxedExecutor<'a>)> for Delete<'a> {
+    fn from((DeleteOperator { table_name }, input): (DeleteOperator, BoxedExecutor<'a>)) -> Self {
         Delete {
             table_name,
             input,
@@ -22,55 +24,71 @@ impl From<(DeleteOperator, BoxedExecutor)> for Delete {
     }
 }
 
-impl<S: Storage> Executor<S> for Delete {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for Delete<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
-impl Delete {
+impl Delete<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
         let Delete { table_name, input } = self;
+        let table_catalog = transaction.table(&table_name)
+            .cloned()
+            .ok_or_else(|| StorageError::TableNotFound(table_name.to_string()))?;
 
-        if let Some(mut transaction) = storage.transaction(&table_name).await {
-            let table_catalog = storage.table(&table_name).await.unwrap();
+        let vec = table_catalog
+            .all_columns()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, col)| col.desc.is_unique
+                .then(|| col.id.and_then(|col_id| {
+                    table_catalog.get_unique_index(&col_id)
+                        .map(|index_meta| (i, index_meta))
+                }))
+                .flatten())
+            .collect_vec();
 
-            let vec = table_catalog
-                .all_columns()
-                .into_iter()
-                .enumerate()
-                .filter_map(|(i, col)| col.desc.is_unique
-                    .then(|| col.id.and_then(|col_id| {
-                        table_catalog.get_unique_index(&col_id)
-                            .map(|index_meta| (i, index_meta))
-                    }))
-                    .flatten())
-                .collect_vec();
+        let mut tuples = Vec::new();
 
+        #[for_await]
+        for tuple in input {
+            tuples.push(tuple?);
+        }
 
-            #[for_await]
-            for tuple in input {
-                let tuple: Tuple = tuple?;
+        let mut deleted_ids = HashSet::new();
+        let mut deleted_tuples = Vec::new();
 
-                for (i, index_meta) in vec.iter() {
-                    let value = &tuple.values[*i];
+        for tuple in tuples {
+            let tuple = target_tuple(&table_name, tuple);
 
-                    if !value.is_null() {
-                        let index = Index {
-                            id: index_meta.id,
-                            column_values: vec![value.clone()],
-                        };
+            // the target row may be joined with many rows
+            if !deleted_ids.insert(tuple.id.clone()) {
+                continue;
+            }
 
-                        transaction.del_index(&index)?;
-                    }
-                }
+            for (i, index_meta) in vec.iter() {
+                let value = &tuple.values[*i];
 
-                if let Some(tuple_id) = tuple.id {
-                    transaction.delete(tuple_id)?;
+                if !value.is_null() {
+                    let index = Index {
+                        id: index_meta.id,
+                        column_values: vec![value.clone()],
+                    };
+
+                    transaction.del_index(&table_name, &index)?;
                 }
             }
-            transaction.commit().await?;
+
+            if let Some(tuple_id) = tuple.id.clone() {
+                transaction.delete(&table_name, tuple_id)?;
+            }
+            deleted_tuples.push(tuple);
+        }
+
+        for tuple in deleted_tuples {
+            yield tuple;
         }
     }
-}
\ No newline at end of file
+}
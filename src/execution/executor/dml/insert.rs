@@ -1,109 +1,301 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use futures_async_stream::try_stream;
-use crate::catalog::TableName;
+use itertools::Itertools;
+use crate::catalog::{ColumnRef, TableCatalog, TableName};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
-use crate::planner::operator::insert::InsertOperator;
-use crate::storage::{Storage, Transaction};
+use crate::expression::ScalarExpression;
+use crate::expression::simplify::ConstantBinary;
+use crate::planner::operator::insert::{ConflictAction, excluded_column, InsertOperator, OnConflict};
+use crate::storage::{Iter, StorageError, Transaction};
 use crate::types::index::Index;
 use crate::types::tuple::Tuple;
 use crate::types::value::DataValue;
 
-pub struct Insert {
+pub struct Insert<'a> {
     table_name: TableName,
-    input: BoxedExecutor,
-    is_overwrite: bool
+    columns: Vec<ColumnRef>,
+    input: BoxedExecutor<'a>,
+    is_overwrite: bool,
+    on_conflict: Option<OnConflict>
 }
 
-impl From<(InsertOperator, BoxedExecutor)> for Insert {
-    fn from((InsertOperator { table_name, is_overwrite }, input): (InsertOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(InsertOperator, BoxedExecutor<'a>)> for Insert<'a> {
+    fn from((InsertOperator { table_name, columns, is_overwrite, on_conflict }, input): (InsertOperator, BoxedExecutor<'a>)) -> Self {
         Insert {
             table_name,
+            columns,
             input,
             is_overwrite,
+            on_conflict,
         }
     }
 }
 
-impl<S: Storage> Executor<S> for Insert {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for Insert<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
-impl Insert {
+impl Insert<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
-        let Insert { table_name, input, is_overwrite } = self;
-        let mut primary_key_index = None;
-        let mut unique_values = HashMap::new();
-
-        if let (Some(table_catalog), Some(mut transaction)) =
-            (storage.table(&table_name).await, storage.transaction(&table_name).await)
-        {
-            #[for_await]
-            for tuple in input {
-                let Tuple { columns, values, .. } = tuple?;
-                let mut tuple_map = HashMap::new();
-                for (i, value) in values.into_iter().enumerate() {
-                    let col = &columns[i];
-                    let cast_val = DataValue::clone(&value).cast(&col.datatype())?;
-
-                    if let Some(col_id) = col.id {
-                        tuple_map.insert(col_id, Arc::new(cast_val));
-                    }
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
+        let Insert { table_name, columns, input, is_overwrite, on_conflict } = self;
+        let table_catalog = transaction.table(&table_name)
+            .cloned()
+            .ok_or_else(|| StorageError::TableNotFound(table_name.to_string()))?;
+        let primary_col_id = table_catalog.all_columns()
+            .iter()
+            .find(|col| col.desc.is_primary)
+            .and_then(|col| col.id)
+            .unwrap();
+        // the generated row keys continue from the largest one in the table
+        let mut next_row_key = match table_catalog.row_key() {
+            Some(row_key) if !columns.iter().any(|col| col.id == row_key.id) => {
+                Some(Self::max_row_key(transaction, &table_name, row_key)? + 1)
+            }
+            _ => None,
+        };
+        let mut inserted_tuples = Vec::new();
+        // the rows inserted or updated by the statement, `DO UPDATE` can not affect them a second time
+        let mut affected_ids = HashSet::new();
+
+        // Tips: the input is read up before any write, it may scan the same table
+        let mut input_tuples = Vec::new();
+
+        #[for_await]
+        for tuple in input {
+            input_tuples.push(tuple?);
+        }
+
+        for Tuple { values, .. } in input_tuples {
+            let mut tuple_map = HashMap::new();
+            for (i, value) in values.into_iter().enumerate() {
+                let col = &columns[i];
+                let cast_val = DataValue::clone(&value).cast(&col.datatype())?;
+
+                if let Some(col_id) = col.id {
+                    tuple_map.insert(col_id, Arc::new(cast_val));
                 }
-                let primary_col_id = primary_key_index.get_or_insert_with(|| {
-                    columns.iter()
-                        .find(|col| col.desc.is_primary)
-                        .map(|col| col.id.unwrap())
-                        .unwrap()
-                });
-                let all_columns = table_catalog.all_columns_with_id();
-                let tuple_id = tuple_map.get(primary_col_id)
-                    .cloned()
-                    .unwrap();
-                let mut tuple = Tuple {
-                    id: Some(tuple_id.clone()),
-                    columns: Vec::with_capacity(all_columns.len()),
-                    values: Vec::with_capacity(all_columns.len()),
-                };
-                for (col_id, col) in all_columns {
-                    let value = tuple_map.remove(col_id)
-                        .unwrap_or_else(|| Arc::new(DataValue::none(col.datatype())));
-
-                    if col.desc.is_unique && !value.is_null() {
-                        unique_values
-                            .entry(col.id)
-                            .or_insert_with(|| vec![])
-                            .push((tuple_id.clone(), value.clone()))
-                    }
-                    if value.is_null() && !col.nullable {
-                        return Err(ExecutorError::InternalError(format!("Non-null fields do not allow null values to be passed in: {:?}", col)));
-                    }
+            }
+            if let Some(row_key) = next_row_key.as_mut() {
+                tuple_map.insert(primary_col_id, Arc::new(DataValue::UInt64(Some(*row_key))));
+                *row_key += 1;
+            }
+            let all_columns = table_catalog.all_columns_with_id();
+            let tuple_id = tuple_map.get(&primary_col_id)
+                .cloned()
+                .unwrap();
+            let mut tuple = Tuple {
+                id: Some(tuple_id.clone()),
+                columns: Vec::with_capacity(all_columns.len()),
+                values: Vec::with_capacity(all_columns.len()),
+            };
+            for (col_id, col) in all_columns {
+                let value = tuple_map.remove(col_id)
+                    .unwrap_or_else(|| Arc::new(DataValue::none(col.datatype())));
 
-                    tuple.columns.push(col.clone());
-                    tuple.values.push(value)
+                if value.is_null() && !col.nullable {
+                    return Err(ExecutorError::InternalError(format!("Non-null fields do not allow null values to be passed in: {:?}", col)));
                 }
 
-                transaction.append(tuple, is_overwrite)?;
+                tuple.columns.push(col.clone());
+                tuple.values.push(value)
             }
-            // Unique Index
-            for (col_id, values) in unique_values {
-                if let Some(index_meta) = table_catalog.get_unique_index(&col_id.unwrap()) {
-                    for (tuple_id, value) in values {
-                        let index = Index {
-                            id: index_meta.id,
-                            column_values: vec![value],
-                        };
-
-                        transaction.add_index(index, vec![tuple_id], true)?;
+
+            if let Some(OnConflict { target_columns, action }) = &on_conflict {
+                if let Some(conflict_tuple) = Self::conflict_tuple(transaction, &table_name, &table_catalog, &tuple, target_columns)? {
+                    if let ConflictAction::DoUpdate { value_exprs, filter } = action {
+                        if conflict_tuple.id.as_ref().is_some_and(|id| affected_ids.contains(id)) {
+                            Err(ExecutorError::InternalError(
+                                "ON CONFLICT DO UPDATE command cannot affect row a second time".to_string()
+                            ))?;
+                        }
+                        if let Some(tuple) = Self::update_conflict_tuple(
+                            transaction,
+                            &table_name,
+                            &table_catalog,
+                            conflict_tuple,
+                            tuple,
+                            value_exprs,
+                            filter
+                        )? {
+                            affected_ids.insert(tuple.id.clone().unwrap());
+                            inserted_tuples.push(tuple);
+                        }
                     }
+                    continue;
+                }
+            }
+
+            transaction.append(&table_name, tuple.clone(), is_overwrite)?;
+            // Unique Index
+            for (col, value) in tuple.columns.iter().zip(tuple.values.iter()) {
+                if !col.desc.is_unique || value.is_null() {
+                    continue;
                 }
+                if let Some(index_meta) = table_catalog.get_unique_index(&col.id.unwrap()) {
+                    let index = Index {
+                        id: index_meta.id,
+                        column_values: vec![value.clone()],
+                    };
+
+                    transaction.add_index(&table_name, index, vec![tuple_id.clone()], true)?;
+                }
+            }
+            affected_ids.insert(tuple_id);
+            inserted_tuples.push(tuple);
+        }
+
+        for tuple in inserted_tuples {
+            yield tuple;
+        }
+    }
+
+    /// The largest generated row key of the table, 0 if it is empty.
+    fn max_row_key<T: Transaction>(
+        transaction: &T,
+        table_name: &String,
+        row_key: &ColumnRef
+    ) -> Result<u64, ExecutorError> {
+        let mut iter = transaction.read(table_name, (None, None), vec![ScalarExpression::ColumnRef(row_key.clone())])?;
+        let mut max_row_key = 0;
+
+        while let Some(tuple) = iter.next_tuple()? {
+            if let DataValue::UInt64(Some(row_key)) = tuple.values[0].as_ref() {
+                max_row_key = max_row_key.max(*row_key);
+            }
+        }
+
+        Ok(max_row_key)
+    }
+
+    /// The existing row with the same primary key or unique value as the tuple,
+    /// only the conflict target columns are checked if there are any.
+    fn conflict_tuple<T: Transaction>(
+        transaction: &T,
+        table_name: &String,
+        table_catalog: &TableCatalog,
+        tuple: &Tuple,
+        target_columns: &[ColumnRef]
+    ) -> Result<Option<Tuple>, ExecutorError> {
+        let projection = table_catalog.all_columns()
+            .into_iter()
+            .map(ScalarExpression::ColumnRef)
+            .collect_vec();
+
+        for (col, value) in tuple.columns.iter().zip(tuple.values.iter()) {
+            if value.is_null() || !(col.desc.is_primary || col.desc.is_unique) {
+                continue;
+            }
+            if !target_columns.is_empty() && !target_columns.iter().any(|target| target.id == col.id) {
+                continue;
+            }
+            let conflict_tuple = if col.desc.is_primary {
+                transaction.read_by_tuple_ids(table_name, projection.clone(), vec![value.clone()])?.pop()
+            } else if let Some(index_meta) = table_catalog.get_unique_index(&col.id.unwrap()) {
+                transaction.read_by_index(
+                    table_name,
+                    (None, None),
+                    projection.clone(),
+                    index_meta.clone(),
+                    vec![ConstantBinary::Eq(value.clone())]
+                )?.next_tuple()?
+            } else {
+                continue;
+            };
+
+            if let Some(conflict_tuple) = conflict_tuple {
+                return Ok(Some(conflict_tuple));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// `DO UPDATE` the existing row, the new values are evaluated on the existing row
+    /// and the `EXCLUDED` row, which is the tuple proposed for insertion.
+    /// Returns `None` if the existing row is filtered out by the `WHERE` condition.
+    fn update_conflict_tuple<T: Transaction>(
+        transaction: &T,
+        table_name: &String,
+        table_catalog: &TableCatalog,
+        mut tuple: Tuple,
+        excluded_tuple: Tuple,
+        value_exprs: &[(ColumnRef, ScalarExpression)],
+        filter: &Option<ScalarExpression>
+    ) -> Result<Option<Tuple>, ExecutorError> {
+        let joined_tuple = Tuple {
+            id: None,
+            columns: tuple.columns
+                .iter()
+                .cloned()
+                .chain(excluded_tuple.columns.iter().map(excluded_column))
+                .collect(),
+            values: tuple.values
+                .iter()
+                .chain(excluded_tuple.values.iter())
+                .cloned()
+                .collect(),
+        };
+
+        if let Some(expr) = filter {
+            if !matches!(expr.eval_column(&joined_tuple)?.as_ref(), DataValue::Boolean(Some(true))) {
+                return Ok(None);
+            }
+        }
+        let old_values = tuple.values.clone();
+        let mut is_key_changed = false;
+
+        for (column, expr) in value_exprs {
+            let value = expr.eval_column(&joined_tuple)?;
+            let value = DataValue::clone(&value).cast(column.datatype())?;
+            value.check_len(column.datatype())?;
+
+            if value.is_null() && !column.nullable {
+                return Err(ExecutorError::InternalError(format!("Non-null fields do not allow null values to be passed in: {:?}", column)));
+            }
+            let value = Arc::new(value);
+            let i = tuple.columns
+                .iter()
+                .position(|col| col.id == column.id)
+                .unwrap();
+
+            if column.desc.is_primary && value != tuple.values[i] {
+                let old_key = tuple.id.replace(value.clone()).unwrap();
+
+                transaction.delete(table_name, old_key)?;
+                is_key_changed = true;
+            }
+            tuple.values[i] = value;
+        }
+        let tuple_id = tuple.id.clone().unwrap();
+
+        for (i, column) in tuple.columns.iter().enumerate() {
+            if !column.desc.is_unique || (!is_key_changed && old_values[i] == tuple.values[i]) {
+                continue;
             }
+            if let Some(index_meta) = table_catalog.get_unique_index(&column.id.unwrap()) {
+                if !old_values[i].is_null() {
+                    transaction.del_index(table_name, &Index {
+                        id: index_meta.id,
+                        column_values: vec![old_values[i].clone()],
+                    })?;
+                }
+                if !tuple.values[i].is_null() {
+                    let index = Index {
+                        id: index_meta.id,
+                        column_values: vec![tuple.values[i].clone()],
+                    };
 
-            transaction.commit().await?;
+                    transaction.add_index(table_name, index, vec![tuple_id.clone()], true)?;
+                }
+            }
         }
+        transaction.append(table_name, tuple.clone(), !is_key_changed)?;
+
+        Ok(Some(tuple))
     }
-}
\ No newline at end of file
+}
@@ -1,3 +1,28 @@
+use crate::catalog::TableName;
+use crate::types::tuple::Tuple;
+
 pub(crate) mod insert;
 pub(crate) mod update;
 pub(crate) mod delete;
+
+/// The row of the target table in the input tuple, which is joined with other tables
+/// in `UPDATE ... FROM` and `DELETE ... USING`.
+pub(crate) fn target_tuple(table_name: &TableName, tuple: Tuple) -> Tuple {
+    if tuple.id.is_some() {
+        return tuple;
+    }
+    let mut target = Tuple { id: None, columns: vec![], values: vec![] };
+
+    for (column, value) in tuple.columns.into_iter().zip(tuple.values) {
+        if column.table_name.as_ref() != Some(table_name) {
+            continue;
+        }
+        if column.desc.is_primary {
+            target.id = Some(value.clone());
+        }
+        target.columns.push(column);
+        target.values.push(value);
+    }
+
+    target
+}
@@ -1,88 +1,134 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 use futures_async_stream::try_stream;
-use crate::catalog::TableName;
+use crate::catalog::{ColumnRef, TableName};
 use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::executor::dml::target_tuple;
 use crate::execution::ExecutorError;
+use crate::expression::ScalarExpression;
 use crate::planner::operator::update::UpdateOperator;
-use crate::storage::{Storage, Transaction};
+use crate::storage::{StorageError, Transaction};
 use crate::types::index::Index;
 use crate::types::tuple::Tuple;
+use crate::types::value::DataValue;
 
-pub struct Update {
+pub struct Update<'a> {
     table_name: TableName,
-    input: BoxedExecutor,
-    values: BoxedExecutor
+    value_exprs: Vec<(ColumnRef, ScalarExpression)>,
+    input: BoxedExecutor<'a>
 }
 
-impl From<(UpdateOperator, BoxedExecutor, BoxedExecutor)> for Update {
-    fn from((UpdateOperator { table_name }, input, values): (UpdateOperator, BoxedExecutor, BoxedExecutor)) -> Self {
+impl<'a> From<(UpdateOperator, BoxedExecutor<'a>)> for Update<'a> {
+    fn from((UpdateOperator { table_name, value_exprs }, input): (UpdateOperator, BoxedExecutor<'a>)) -> Self {
         Update {
             table_name,
-            input,
-            values
+            value_exprs,
+            input
         }
     }
 }
 
-impl<S: Storage> Executor<S> for Update {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for Update<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
-impl Update {
+impl Update<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
-        let Update { table_name, input, values } = self;
-
-        if let Some(mut transaction) = storage.transaction(&table_name).await {
-            let table_catalog = storage.table(&table_name).await.unwrap();
-            let mut value_map = HashMap::new();
-
-            // only once
-            #[for_await]
-            for tuple in values {
-                let Tuple { columns, values, .. } = tuple?;
-                for i in 0..columns.len() {
-                    value_map.insert(columns[i].id, values[i].clone());
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
+        let Update { table_name, value_exprs, input } = self;
+        let table_catalog = transaction.table(&table_name)
+            .cloned()
+            .ok_or_else(|| StorageError::TableNotFound(table_name.to_string()))?;
+        let mut updated_tuples = Vec::new();
+        let mut updated_ids = HashSet::new();
+
+        let mut joined_tuples = Vec::new();
+
+        #[for_await]
+        for tuple in input {
+            joined_tuples.push(tuple?);
+        }
+
+        for joined_tuple in joined_tuples {
+            let mut tuple = target_tuple(&table_name, joined_tuple.clone());
+
+            // the target row may be joined with many rows, only the first one is used
+            if !updated_ids.insert(tuple.id.clone().unwrap()) {
+                continue;
+            }
+            let old_values = tuple.values.clone();
+            let mut is_key_changed = false;
+
+            // all the new values are evaluated on the row before update
+            let mut new_values = Vec::with_capacity(value_exprs.len());
+            for (column, expr) in value_exprs.iter() {
+                let value = expr.eval_column(&joined_tuple)?;
+                let value = DataValue::clone(&value).cast(column.datatype())?;
+                value.check_len(column.datatype())?;
+
+                if value.is_null() && !column.nullable {
+                    return Err(ExecutorError::InternalError(format!("Non-null fields do not allow null values to be passed in: {:?}", column)));
+                }
+                new_values.push(Arc::new(value));
+            }
+            for ((column, _), value) in value_exprs.iter().zip(new_values) {
+                let i = tuple.columns
+                    .iter()
+                    .position(|col| col.id == column.id)
+                    .unwrap();
+
+                if column.desc.is_primary && value != tuple.values[i] {
+                    let old_key = tuple.id.replace(value.clone()).unwrap();
+
+                    transaction.delete(&table_name, old_key)?;
+                    is_key_changed = true;
                 }
+                tuple.values[i] = value;
             }
-            #[for_await]
-            for tuple in input {
-                let mut tuple: Tuple = tuple?;
-                let mut is_overwrite = true;
-
-                for (i, column) in tuple.columns.iter().enumerate() {
-                    if let Some(value) = value_map.get(&column.id) {
-                        if column.desc.is_primary {
-                            let old_key = tuple.id.replace(value.clone()).unwrap();
-
-                            transaction.delete(old_key)?;
-                            is_overwrite = false;
-                        }
-                        if column.desc.is_unique && value != &tuple.values[i] {
-                            if let Some(index_meta) = table_catalog.get_unique_index(&column.id.unwrap()) {
-                                let mut index = Index {
-                                    id: index_meta.id,
-                                    column_values: vec![tuple.values[i].clone()],
-                                };
-                                transaction.del_index(&index)?;
-
-                                if !value.is_null() {
-                                    index.column_values[0] = value.clone();
-                                    transaction.add_index(index, vec![tuple.id.clone().unwrap()], true)?;
-                                }
-                            }
-                        }
-
-                        tuple.values[i] = value.clone();
+
+            // the old index entries are removed before any new one is added,
+            // so that the values can be swapped between rows.
+            let mut index_changes = Vec::new();
+            for (i, column) in tuple.columns.iter().enumerate() {
+                if !column.desc.is_unique || (!is_key_changed && old_values[i] == tuple.values[i]) {
+                    continue;
+                }
+                if let Some(index_meta) = table_catalog.get_unique_index(&column.id.unwrap()) {
+                    if !old_values[i].is_null() {
+                        transaction.del_index(&table_name, &Index {
+                            id: index_meta.id,
+                            column_values: vec![old_values[i].clone()],
+                        })?;
+                    }
+                    if !tuple.values[i].is_null() {
+                        index_changes.push(Index {
+                            id: index_meta.id,
+                            column_values: vec![tuple.values[i].clone()],
+                        });
                     }
                 }
+            }
 
-                transaction.append(tuple, is_overwrite)?;
+            updated_tuples.push((tuple, is_key_changed, index_changes));
+        }
+
+        let mut new_tuples = Vec::with_capacity(updated_tuples.len());
+
+        for (tuple, is_key_changed, index_changes) in updated_tuples {
+            let tuple_id = tuple.id.clone().unwrap();
+
+            for index in index_changes {
+                transaction.add_index(&table_name, index, vec![tuple_id.clone()], true)?;
             }
+            // a changed primary key is a delete and an insert
+            transaction.append(&table_name, tuple.clone(), !is_key_changed)?;
+            new_tuples.push(tuple);
+        }
 
-            transaction.commit().await?;
+        for tuple in new_tuples {
+            yield tuple;
         }
     }
-}
\ No newline at end of file
+}
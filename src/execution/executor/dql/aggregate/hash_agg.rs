@@ -1,4 +1,6 @@
-use ahash::{HashMap, HashMapExt};
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use ahash::RandomState;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use crate::execution::executor::{BoxedExecutor, Executor};
@@ -6,18 +8,18 @@ use crate::execution::executor::dql::aggregate::create_accumulators;
 use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::aggregate::AggregateOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 use crate::types::value::ValueRef;
 
-pub struct HashAggExecutor {
+pub struct HashAggExecutor<'a> {
     pub agg_calls: Vec<ScalarExpression>,
     pub groupby_exprs: Vec<ScalarExpression>,
-    pub input: BoxedExecutor,
+    pub input: BoxedExecutor<'a>,
 }
 
-impl From<(AggregateOperator, BoxedExecutor)> for HashAggExecutor {
-    fn from((AggregateOperator { agg_calls, groupby_exprs }, input): (AggregateOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(AggregateOperator, BoxedExecutor<'a>)> for HashAggExecutor<'a> {
+    fn from((AggregateOperator { agg_calls, groupby_exprs }, input): (AggregateOperator, BoxedExecutor<'a>)) -> Self {
         HashAggExecutor {
             agg_calls,
             groupby_exprs,
@@ -26,17 +28,19 @@ impl From<(AggregateOperator, BoxedExecutor)> for HashAggExecutor {
     }
 }
 
-impl<S: Storage> Executor<S> for HashAggExecutor {
-    fn execute(self, _: &S) -> BoxedExecutor {
-        self._execute()
+impl<'a, T: Transaction> Executor<'a, T> for HashAggExecutor<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
+        self._execute(RandomState::new())
     }
 }
 
-impl HashAggExecutor {
+impl HashAggExecutor<'_> {
+    /// The group keys are compared by value, so the hash function only affects performance,
+    /// and all NULL keys are in the same group.
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute(self) {
+    pub async fn _execute<'a, H: BuildHasher + Send + 'static>(self, hash_builder: H) where Self: 'a {
         let mut group_and_agg_columns_option = None;
-        let mut group_hash_accs = HashMap::new();
+        let mut group_hash_accs = HashMap::with_hasher(hash_builder);
 
         #[for_await]
         for tuple in self.input {
@@ -101,19 +105,25 @@ impl HashAggExecutor {
 
 #[cfg(test)]
 mod test {
+    use std::collections::BTreeMap;
+    use std::hash::BuildHasherDefault;
     use std::sync::Arc;
     use itertools::Itertools;
+    use proptest::collection::vec;
+    use proptest::option;
+    use proptest::prelude::*;
     use crate::catalog::{ColumnCatalog, ColumnDesc};
     use crate::execution::executor::dql::aggregate::hash_agg::HashAggExecutor;
     use crate::execution::executor::dql::values::Values;
     use crate::execution::executor::{Executor, try_collect};
-    use crate::execution::executor::dql::test::build_integers;
+    use crate::execution::executor::dql::test::{build_integers, CollisionHasher};
     use crate::execution::ExecutorError;
     use crate::expression::agg::AggKind;
     use crate::expression::ScalarExpression;
     use crate::planner::operator::aggregate::AggregateOperator;
     use crate::planner::operator::values::ValuesOperator;
     use crate::storage::memory::MemStorage;
+    use crate::storage::Storage;
     use crate::types::LogicalType;
     use crate::types::tuple::create_table;
     use crate::types::value::DataValue;
@@ -121,7 +131,7 @@ mod test {
 
     #[tokio::test]
     async fn test_hash_agg() -> Result<(), ExecutorError> {
-        let mem_storage = MemStorage::new();
+        let transaction = MemStorage::new().transaction().await?;
         let desc = ColumnDesc::new(LogicalType::Integer, false, false);
 
         let t1_columns = vec![
@@ -170,9 +180,9 @@ mod test {
                 ]
             ],
             columns: t1_columns,
-        }).execute(&mem_storage);
+        }).execute(&transaction);
 
-        let tuples = try_collect(&mut HashAggExecutor::from((operator, input)).execute(&mem_storage)).await?;
+        let tuples = try_collect(&mut HashAggExecutor::from((operator, input)).execute(&transaction)).await?;
 
         println!("hash_agg_test: \n{}", create_table(&tuples));
 
@@ -188,4 +198,52 @@ mod test {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    proptest! {
+        #[test]
+        fn test_hash_agg_with_hash_collisions(
+            rows in vec((option::of(-4..4i32), -100..100i32), 1..32)
+        ) {
+            let transaction = tokio_test::block_on(MemStorage::new().transaction()).unwrap();
+            let desc = ColumnDesc::new(LogicalType::Integer, false, false);
+            let columns = vec![
+                Arc::new(ColumnCatalog::new("c1".to_string(), true, desc.clone(), None)),
+                Arc::new(ColumnCatalog::new("c2".to_string(), true, desc.clone(), None)),
+            ];
+            let operator = AggregateOperator {
+                groupby_exprs: vec![ScalarExpression::ColumnRef(columns[0].clone())],
+                agg_calls: vec![
+                    ScalarExpression::AggCall {
+                        distinct: false,
+                        kind: AggKind::Sum,
+                        args: vec![ScalarExpression::ColumnRef(columns[1].clone())],
+                        ty: LogicalType::Integer,
+                    }
+                ],
+            };
+            let input = Values::from(ValuesOperator {
+                rows: rows
+                    .iter()
+                    .map(|(key, value)| build_integers(vec![*key, Some(*value)]))
+                    .collect_vec(),
+                columns,
+            }).execute(&transaction);
+
+            let mut executor = HashAggExecutor::from((operator, input))
+                ._execute(BuildHasherDefault::<CollisionHasher>::default());
+            let tuples = tokio_test::block_on(try_collect(&mut executor)).unwrap();
+
+            let mut expected = BTreeMap::new();
+            for (key, value) in rows.iter() {
+                *expected.entry(*key).or_insert(0) += *value;
+            }
+            let actual = tuples
+                .into_iter()
+                .map(|tuple| (tuple.values[1].i32(), tuple.values[0].i32().unwrap()))
+                .sorted()
+                .collect_vec();
+
+            prop_assert_eq!(actual, expected.into_iter().collect_vec());
+        }
+    }
+}
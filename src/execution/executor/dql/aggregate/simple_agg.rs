@@ -5,17 +5,17 @@ use crate::execution::executor::dql::aggregate::create_accumulators;
 use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::aggregate::AggregateOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 use crate::types::value::ValueRef;
 
-pub struct SimpleAggExecutor {
+pub struct SimpleAggExecutor<'a> {
     pub agg_calls: Vec<ScalarExpression>,
-    pub input: BoxedExecutor,
+    pub input: BoxedExecutor<'a>,
 }
 
-impl From<(AggregateOperator, BoxedExecutor)> for SimpleAggExecutor {
-    fn from((AggregateOperator { agg_calls, .. }, input): (AggregateOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(AggregateOperator, BoxedExecutor<'a>)> for SimpleAggExecutor<'a> {
+    fn from((AggregateOperator { agg_calls, .. }, input): (AggregateOperator, BoxedExecutor<'a>)) -> Self {
         SimpleAggExecutor {
             agg_calls,
             input,
@@ -23,15 +23,15 @@ impl From<(AggregateOperator, BoxedExecutor)> for SimpleAggExecutor {
     }
 }
 
-impl<S: Storage> Executor<S> for SimpleAggExecutor {
-    fn execute(self, _: &S) -> BoxedExecutor {
+impl<'a, T: Transaction> Executor<'a, T> for SimpleAggExecutor<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
         self._execute()
     }
 }
 
-impl SimpleAggExecutor {
+impl SimpleAggExecutor<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute(self) {
+    pub async fn _execute<'a>(self) where Self: 'a {
         let mut accs = create_accumulators(&self.agg_calls);
         let mut columns_option = None;
 
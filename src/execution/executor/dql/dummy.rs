@@ -1,13 +1,13 @@
 use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
 pub struct Dummy {}
 
-impl<S: Storage> Executor<S> for Dummy {
-    fn execute(self, _: &S) -> BoxedExecutor {
+impl<'a, T: Transaction> Executor<'a, T> for Dummy {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
         self._execute()
     }
 }
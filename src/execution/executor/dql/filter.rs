@@ -3,17 +3,17 @@ use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::filter::FilterOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 use crate::types::value::DataValue;
 
-pub struct Filter {
+pub struct Filter<'a> {
     predicate: ScalarExpression,
-    input: BoxedExecutor
+    input: BoxedExecutor<'a>
 }
 
-impl From<(FilterOperator, BoxedExecutor)> for Filter {
-    fn from((FilterOperator { predicate, .. }, input): (FilterOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(FilterOperator, BoxedExecutor<'a>)> for Filter<'a> {
+    fn from((FilterOperator { predicate, .. }, input): (FilterOperator, BoxedExecutor<'a>)) -> Self {
         Filter {
             predicate,
             input
@@ -21,15 +21,15 @@ impl From<(FilterOperator, BoxedExecutor)> for Filter {
     }
 }
 
-impl<S: Storage> Executor<S> for Filter {
-    fn execute(self, _: &S) -> BoxedExecutor {
+impl<'a, T: Transaction> Executor<'a, T> for Filter<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
         self._execute()
     }
 }
 
-impl Filter {
+impl Filter<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute(self) {
+    pub async fn _execute<'a>(self) where Self: 'a {
         let Filter { predicate, input } = self;
 
         #[for_await]
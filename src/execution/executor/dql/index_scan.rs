@@ -2,7 +2,7 @@ use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::scan::ScanOperator;
-use crate::storage::{Iter, Storage, Transaction};
+use crate::storage::{Iter, Transaction};
 use crate::types::errors::TypeError;
 use crate::types::tuple::Tuple;
 
@@ -18,29 +18,28 @@ impl From<ScanOperator> for IndexScan {
     }
 }
 
-impl<S: Storage> Executor<S> for IndexScan {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for IndexScan {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
 impl IndexScan {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
         let ScanOperator { table_name, columns, limit, index_by, .. } = self.op;
         let (index_meta, binaries) = index_by.ok_or(TypeError::InvalidType)?;
 
-        if let Some(transaction) = storage.transaction(&table_name).await {
-            let mut iter = transaction.read_by_index(
-                limit,
-                columns,
-                index_meta,
-                binaries
-            )?;
+        let mut iter = transaction.read_by_index(
+            &table_name,
+            limit,
+            columns,
+            index_meta,
+            binaries
+        )?;
 
-            while let Some(tuple) =  iter.next_tuple()? {
-                yield tuple;
-            }
+        while let Some(tuple) =  iter.next_tuple()? {
+            yield tuple;
         }
     }
 }
\ No newline at end of file
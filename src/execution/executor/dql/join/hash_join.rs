@@ -1,5 +1,7 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::BuildHasher;
 use std::sync::Arc;
-use ahash::{HashMap, HashMapExt, HashSet, HashSetExt, RandomState};
+use ahash::RandomState;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use crate::execution::executor::dql::join::joins_nullable;
@@ -8,20 +10,20 @@ use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinType};
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::errors::TypeError;
 use crate::types::tuple::Tuple;
-use crate::types::value::DataValue;
+use crate::types::value::{DataValue, ValueRef};
 
-pub struct HashJoin {
+pub struct HashJoin<'a> {
     on: JoinCondition,
     ty: JoinType,
-    left_input: BoxedExecutor,
-    right_input: BoxedExecutor
+    left_input: BoxedExecutor<'a>,
+    right_input: BoxedExecutor<'a>
 }
 
-impl From<(JoinOperator, BoxedExecutor, BoxedExecutor)> for HashJoin {
-    fn from((JoinOperator { on, join_type }, left_input, right_input): (JoinOperator, BoxedExecutor, BoxedExecutor)) -> Self {
+impl<'a> From<(JoinOperator, BoxedExecutor<'a>, BoxedExecutor<'a>)> for HashJoin<'a> {
+    fn from((JoinOperator { on, join_type, .. }, left_input, right_input): (JoinOperator, BoxedExecutor<'a>, BoxedExecutor<'a>)) -> Self {
         HashJoin {
             on,
             ty: join_type,
@@ -31,15 +33,16 @@ impl From<(JoinOperator, BoxedExecutor, BoxedExecutor)> for HashJoin {
     }
 }
 
-impl<S: Storage> Executor<S> for HashJoin {
-    fn execute(self, _: &S) -> BoxedExecutor {
-        self._execute()
+impl<'a, T: Transaction> Executor<'a, T> for HashJoin<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
+        self._execute(RandomState::with_seeds(0, 0, 0, 0))
     }
 }
 
-impl HashJoin {
+impl HashJoin<'_> {
+    /// The join keys are compared by value, so the hash function only affects performance.
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute(self) {
+    pub async fn _execute<'a, H: BuildHasher + Clone + Send + 'static>(self, hash_builder: H) where Self: 'a {
         let HashJoin { on, ty, left_input, right_input } = self;
 
         if ty == JoinType::Cross {
@@ -51,20 +54,19 @@ impl HashJoin {
         };
 
         let mut join_columns = Vec::new();
-        let mut used_set = HashSet::<u64>::new();
-        let mut left_map = HashMap::new();
+        let mut used_set = HashSet::with_hasher(hash_builder.clone());
+        let mut left_map = HashMap::with_hasher(hash_builder);
 
-        let hash_random_state = RandomState::with_seeds(0, 0, 0, 0);
         let (left_force_nullable, right_force_nullable) = joins_nullable(&ty);
 
         // build phase:
-        // 1.construct hashtable, one hash key may contains multiple rows indices.
+        // 1.construct hashtable, one join key may contains multiple rows indices.
         // 2.merged all left tuples.
         let mut left_init_flag = false;
         #[for_await]
         for tuple in left_input {
             let tuple: Tuple = tuple?;
-            let hash = Self::hash_row(&on_left_keys, &hash_random_state, &tuple)?;
+            let join_keys = Self::eval_keys(&on_left_keys, &tuple)?;
 
             if !left_init_flag {
                 Self::columns_filling(&tuple, &mut join_columns, left_force_nullable);
@@ -72,7 +74,7 @@ impl HashJoin {
             }
 
             left_map
-                .entry(hash)
+                .entry(join_keys)
                 .or_insert(Vec::new())
                 .push(tuple);
         }
@@ -83,15 +85,17 @@ impl HashJoin {
         for tuple in right_input {
             let tuple: Tuple = tuple?;
             let right_cols_len = tuple.columns.len();
-            let hash = Self::hash_row(&on_right_keys, &hash_random_state, &tuple)?;
+            let join_keys = Self::eval_keys(&on_right_keys, &tuple)?;
+            // NULL never equals to anything, include NULL
+            let has_null = join_keys.iter().any(|value| value.is_null());
 
             if !right_init_flag {
                 Self::columns_filling(&tuple, &mut join_columns, right_force_nullable);
                 right_init_flag = true;
             }
 
-            let mut join_tuples = if let Some(tuples) = left_map.get(&hash) {
-                let _ = used_set.insert(hash);
+            let mut join_tuples = if let (false, Some(tuples)) = (has_null, left_map.get(&join_keys)) {
+                let _ = used_set.insert(join_keys);
 
                 tuples
                     .iter()
@@ -164,8 +168,8 @@ impl HashJoin {
         }
 
         if matches!(ty, JoinType::Left | JoinType::Full) {
-            for (hash, tuples) in left_map {
-                if used_set.contains(&hash) {
+            for (join_keys, tuples) in left_map {
+                if used_set.contains(&join_keys) {
                     continue
                 }
 
@@ -183,7 +187,7 @@ impl HashJoin {
         }
     }
 
-    fn columns_filling(tuple: &Tuple, join_columns: &mut Vec<ColumnRef>, force_nullable: bool) {
+    pub(crate) fn columns_filling(tuple: &Tuple, join_columns: &mut Vec<ColumnRef>, force_nullable: bool) {
         let mut new_columns = tuple.columns.iter()
             .cloned()
             .map(|col| {
@@ -197,40 +201,45 @@ impl HashJoin {
         join_columns.append(&mut new_columns);
     }
 
-    fn hash_row(
+    fn eval_keys(
         on_keys: &[ScalarExpression],
-        hash_random_state: &RandomState,
         tuple: &Tuple
-    ) -> Result<u64, TypeError> {
+    ) -> Result<Vec<ValueRef>, TypeError> {
         let mut values = Vec::with_capacity(on_keys.len());
 
         for expr in on_keys {
             values.push(expr.eval_column(tuple)?);
         }
 
-        Ok(hash_random_state.hash_one(values))
+        Ok(values)
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::hash::BuildHasherDefault;
     use std::sync::Arc;
+    use itertools::Itertools;
+    use proptest::collection::vec;
+    use proptest::option;
+    use proptest::prelude::*;
     use crate::catalog::{ColumnCatalog, ColumnDesc};
     use crate::execution::executor::{BoxedExecutor, Executor, try_collect};
     use crate::execution::executor::dql::join::hash_join::HashJoin;
-    use crate::execution::executor::dql::test::build_integers;
+    use crate::execution::executor::dql::test::{build_integers, CollisionHasher};
     use crate::execution::executor::dql::values::Values;
     use crate::execution::ExecutorError;
     use crate::expression::ScalarExpression;
-    use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinType};
+    use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinStrategy, JoinType};
     use crate::planner::operator::values::ValuesOperator;
     use crate::storage::memory::MemStorage;
     use crate::storage::Storage;
+    use crate::storage::Transaction;
     use crate::types::LogicalType;
     use crate::types::tuple::create_table;
     use crate::types::value::DataValue;
 
-    fn build_join_values<S: Storage>(_s: &S) -> (Vec<(ScalarExpression, ScalarExpression)>, BoxedExecutor, BoxedExecutor) {
+    fn build_join_values<T: Transaction>(_t: &T) -> (Vec<(ScalarExpression, ScalarExpression)>, BoxedExecutor<'_>, BoxedExecutor<'_>) {
         let desc = ColumnDesc::new(LogicalType::Integer, false, false);
 
         let t1_columns = vec![
@@ -298,19 +307,20 @@ mod test {
 
 
 
-        (on_keys, values_t1.execute(_s), values_t2.execute(_s))
+        (on_keys, values_t1.execute(_t), values_t2.execute(_t))
     }
 
     #[tokio::test]
     async fn test_inner_join() -> Result<(), ExecutorError> {
-        let mem_storage = MemStorage::new();
-        let (keys, left, right) = build_join_values(&mem_storage);
+        let transaction = MemStorage::new().transaction().await?;
+        let (keys, left, right) = build_join_values(&transaction);
 
         let op = JoinOperator {
             on: JoinCondition::On { on: keys, filter: None },
             join_type: JoinType::Inner,
+            strategy: JoinStrategy::Hash,
         };
-        let mut executor = HashJoin::from((op, left, right)).execute(&mem_storage);
+        let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
 
         println!("inner_test: \n{}", create_table(&tuples));
@@ -326,14 +336,15 @@ mod test {
 
     #[tokio::test]
     async fn test_left_join() -> Result<(), ExecutorError> {
-        let mem_storage = MemStorage::new();
-        let (keys, left, right) = build_join_values(&mem_storage);
+        let transaction = MemStorage::new().transaction().await?;
+        let (keys, left, right) = build_join_values(&transaction);
 
         let op = JoinOperator {
             on: JoinCondition::On { on: keys, filter: None },
             join_type: JoinType::Left,
+            strategy: JoinStrategy::Hash,
         };
-        let mut executor = HashJoin::from((op, left, right)).execute(&mem_storage);
+        let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
 
         println!("left_test: \n{}", create_table(&tuples));
@@ -350,14 +361,15 @@ mod test {
 
     #[tokio::test]
     async fn test_right_join() -> Result<(), ExecutorError> {
-        let mem_storage = MemStorage::new();
-        let (keys, left, right) = build_join_values(&mem_storage);
+        let transaction = MemStorage::new().transaction().await?;
+        let (keys, left, right) = build_join_values(&transaction);
 
         let op = JoinOperator {
             on: JoinCondition::On { on: keys, filter: None },
             join_type: JoinType::Right,
+            strategy: JoinStrategy::Hash,
         };
-        let mut executor = HashJoin::from((op, left, right)).execute(&mem_storage);
+        let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
 
         println!("right_test: \n{}", create_table(&tuples));
@@ -374,14 +386,15 @@ mod test {
 
     #[tokio::test]
     async fn test_full_join() -> Result<(), ExecutorError> {
-        let mem_storage = MemStorage::new();
-        let (keys, left, right) = build_join_values(&mem_storage);
+        let transaction = MemStorage::new().transaction().await?;
+        let (keys, left, right) = build_join_values(&transaction);
 
         let op = JoinOperator {
             on: JoinCondition::On { on: keys, filter: None },
             join_type: JoinType::Full,
+            strategy: JoinStrategy::Hash,
         };
-        let mut executor = HashJoin::from((op, left, right)).execute(&mem_storage);
+        let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
 
         println!("full_test: \n{}", create_table(&tuples));
@@ -396,4 +409,99 @@ mod test {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    fn join_rows(
+        left_rows: &[(Option<i32>, i32)],
+        right_rows: &[(Option<i32>, i32)],
+        join_type: JoinType
+    ) -> Result<Vec<Vec<Option<i32>>>, ExecutorError> {
+        let transaction = tokio_test::block_on(MemStorage::new().transaction()).unwrap();
+        let desc = ColumnDesc::new(LogicalType::Integer, false, false);
+        let build_values = |names: [&str; 2], rows: &[(Option<i32>, i32)]| {
+            let columns = names
+                .iter()
+                .map(|name| Arc::new(ColumnCatalog::new(name.to_string(), true, desc.clone(), None)))
+                .collect_vec();
+            let rows = rows
+                .iter()
+                .map(|(key, value)| build_integers(vec![*key, Some(*value)]))
+                .collect_vec();
+
+            (columns.clone(), Values::from(ValuesOperator { rows, columns }).execute(&transaction))
+        };
+        let (t1_columns, left) = build_values(["c1", "c2"], left_rows);
+        let (t2_columns, right) = build_values(["c3", "c4"], right_rows);
+
+        let op = JoinOperator {
+            on: JoinCondition::On {
+                on: vec![(ScalarExpression::ColumnRef(t1_columns[0].clone()), ScalarExpression::ColumnRef(t2_columns[0].clone()))],
+                filter: None
+            },
+            join_type,
+            strategy: JoinStrategy::Hash,
+        };
+        let mut executor = HashJoin::from((op, left, right))
+            ._execute(BuildHasherDefault::<CollisionHasher>::default());
+        let tuples = tokio_test::block_on(try_collect(&mut executor))?;
+
+        Ok(tuples
+            .into_iter()
+            .map(|tuple| tuple.values.iter().map(|value| value.i32()).collect_vec())
+            .sorted()
+            .collect_vec())
+    }
+
+    /// Nested loop join as the reference result, NULL keys never match.
+    fn nested_loop_join(
+        left_rows: &[(Option<i32>, i32)],
+        right_rows: &[(Option<i32>, i32)],
+        join_type: JoinType
+    ) -> Vec<Vec<Option<i32>>> {
+        let is_match = |l: &Option<i32>, r: &Option<i32>| l.is_some() && l == r;
+        let mut rows = Vec::new();
+
+        for (l_key, l_value) in left_rows {
+            for (r_key, r_value) in right_rows {
+                if is_match(l_key, r_key) {
+                    rows.push(vec![*l_key, Some(*l_value), *r_key, Some(*r_value)]);
+                }
+            }
+        }
+        if matches!(join_type, JoinType::Left | JoinType::Full) {
+            for (l_key, l_value) in left_rows {
+                if !right_rows.iter().any(|(r_key, _)| is_match(l_key, r_key)) {
+                    rows.push(vec![*l_key, Some(*l_value), None, None]);
+                }
+            }
+        }
+        if matches!(join_type, JoinType::Right | JoinType::Full) {
+            for (r_key, r_value) in right_rows {
+                if !left_rows.iter().any(|(l_key, _)| is_match(l_key, r_key)) {
+                    rows.push(vec![None, None, *r_key, Some(*r_value)]);
+                }
+            }
+        }
+        rows.sort();
+
+        rows
+    }
+
+    proptest! {
+        #[test]
+        fn test_join_with_hash_collisions(
+            left_rows in vec((option::of(-4..4i32), any::<i32>()), 1..16),
+            right_rows in vec((option::of(-4..4i32), any::<i32>()), 1..16),
+            join_type in prop_oneof![
+                Just(JoinType::Inner),
+                Just(JoinType::Left),
+                Just(JoinType::Right),
+                Just(JoinType::Full),
+            ]
+        ) {
+            prop_assert_eq!(
+                join_rows(&left_rows, &right_rows, join_type).unwrap(),
+                nested_loop_join(&left_rows, &right_rows, join_type)
+            );
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::sync::Arc;
+use std::time::Instant;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use parking_lot::Mutex;
+use crate::catalog::ColumnCatalog;
+use crate::execution::executor::dql::join::hash_join::HashJoin;
+use crate::execution::executor::dql::join::joins_nullable;
+use crate::execution::executor::dql::join::sort_merge_join::SortMergeJoin;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::executor::analyze::ExecutorMetrics;
+use crate::execution::ExecutorError;
+use crate::expression::ScalarExpression;
+use crate::expression::simplify::ConstantBinary;
+use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinStrategy, JoinType};
+use crate::planner::operator::scan::ScanOperator;
+use crate::storage::{Iter, Transaction};
+use crate::types::index::IndexMetaRef;
+use crate::types::tuple::Tuple;
+use crate::types::value::{DataValue, ValueRef};
+
+/// Index nested-loop join: every left tuple looks up the matching right tuples
+/// by the first equijoin pair, instead of scanning the whole right table.
+pub struct IndexLookupJoin<'a> {
+    on: JoinCondition,
+    ty: JoinType,
+    index_meta: Option<IndexMetaRef>,
+    left_input: BoxedExecutor<'a>,
+    inner_scan: ScanOperator,
+    /// The rows and time of the lookups, which are filled in for `EXPLAIN ANALYZE`
+    inner_metrics: Option<Arc<Mutex<ExecutorMetrics>>>,
+}
+
+impl<'a> From<(JoinOperator, BoxedExecutor<'a>, ScanOperator)> for IndexLookupJoin<'a> {
+    fn from((JoinOperator { on, join_type, strategy }, left_input, inner_scan): (JoinOperator, BoxedExecutor<'a>, ScanOperator)) -> Self {
+        let index_meta = match strategy {
+            JoinStrategy::IndexLookup(index_meta) => index_meta,
+            _ => unreachable!("IndexLookupJoin must has index lookup strategy")
+        };
+
+        IndexLookupJoin {
+            on,
+            ty: join_type,
+            index_meta,
+            left_input,
+            inner_scan,
+            inner_metrics: None,
+        }
+    }
+}
+
+impl IndexLookupJoin<'_> {
+    pub(crate) fn with_inner_metrics(mut self, inner_metrics: Option<Arc<Mutex<ExecutorMetrics>>>) -> Self {
+        self.inner_metrics = inner_metrics;
+        self
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for IndexLookupJoin<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl IndexLookupJoin<'_> {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
+        let IndexLookupJoin { on, ty, index_meta, left_input, inner_scan, inner_metrics } = self;
+
+        if !matches!(ty, JoinType::Inner | JoinType::Left) {
+            unreachable!("{:?} join should not be in IndexLookupJoinExecutor", ty);
+        }
+        let ((on_left_keys, on_right_keys), filter): ((Vec<ScalarExpression>, Vec<ScalarExpression>), _) = match on {
+            JoinCondition::On { on, filter } => (on.into_iter().unzip(), filter),
+            JoinCondition::None => unreachable!("IndexLookupJoin must has on condition")
+        };
+        let (left_force_nullable, right_force_nullable) = joins_nullable(&ty);
+        let ScanOperator { table_name, columns, .. } = inner_scan;
+
+        let right_columns = columns
+            .iter()
+            .map(|expr| match expr {
+                ScalarExpression::ColumnRef(col) => {
+                    let mut new_catalog = ColumnCatalog::clone(col);
+                    new_catalog.nullable = right_force_nullable;
+
+                    Arc::new(new_catalog)
+                }
+                _ => unreachable!("the inner scan of IndexLookupJoin must only has columns")
+            })
+            .collect_vec();
+
+        let mut join_columns = Vec::new();
+        let mut left_init_flag = false;
+
+        #[for_await]
+        for tuple in left_input {
+            let left_tuple: Tuple = tuple?;
+
+            if !left_init_flag {
+                HashJoin::columns_filling(&left_tuple, &mut join_columns, left_force_nullable);
+                join_columns.append(&mut right_columns.clone());
+                left_init_flag = true;
+            }
+
+            let left_keys: Vec<ValueRef> = on_left_keys
+                .iter()
+                .map(|expr| expr.eval_column(&left_tuple))
+                .try_collect()?;
+            let mut right_tuples = Vec::new();
+            let start = Instant::now();
+
+            // NULL never equals to anything, so there is nothing to look up.
+            if !left_keys[0].is_null() {
+                match &index_meta {
+                    Some(index_meta) => {
+                        let mut iter = transaction.read_by_index(
+                            &table_name,
+                            (None, None),
+                            columns.clone(),
+                            index_meta.clone(),
+                            vec![ConstantBinary::Eq(left_keys[0].clone())]
+                        )?;
+
+                        while let Some(tuple) = iter.next_tuple()? {
+                            right_tuples.push(tuple);
+                        }
+                    }
+                    None => {
+                        right_tuples = transaction.read_by_tuple_ids(
+                            &table_name,
+                            columns.clone(),
+                            vec![left_keys[0].clone()]
+                        )?;
+                    }
+                }
+            }
+            if let Some(inner_metrics) = &inner_metrics {
+                let mut inner_metrics = inner_metrics.lock();
+
+                inner_metrics.elapsed += start.elapsed();
+                inner_metrics.rows += right_tuples.len();
+                if inner_metrics.first_row.is_none() && !right_tuples.is_empty() {
+                    inner_metrics.first_row = Some(inner_metrics.elapsed);
+                }
+            }
+
+            let mut left_used = false;
+
+            for right_tuple in right_tuples {
+                if !Self::rest_keys_eq(&left_keys[1..], &on_right_keys[1..], &right_tuple)? {
+                    continue;
+                }
+                let values = left_tuple.values
+                    .iter()
+                    .chain(right_tuple.values.iter())
+                    .cloned()
+                    .collect_vec();
+                let tuple = Tuple { id: None, columns: join_columns.clone(), values };
+
+                if let Some(expr) = &filter {
+                    match expr.eval_column(&tuple)?.as_ref() {
+                        DataValue::Boolean(Some(true)) => (),
+                        DataValue::Boolean(_) => continue,
+                        _ => unreachable!("only bool"),
+                    }
+                }
+                left_used = true;
+
+                yield tuple;
+            }
+
+            if ty == JoinType::Left && !left_used {
+                yield SortMergeJoin::left_padding(left_tuple, &join_columns);
+            }
+        }
+    }
+
+    /// The equijoin pairs not used by the lookup still need to be checked.
+    fn rest_keys_eq(
+        left_keys: &[ValueRef],
+        on_right_keys: &[ScalarExpression],
+        right_tuple: &Tuple
+    ) -> Result<bool, ExecutorError> {
+        for (left_key, right_expr) in left_keys.iter().zip(on_right_keys) {
+            let right_key = right_expr.eval_column(right_tuple)?;
+
+            if left_key.is_null() || left_key != &right_key {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use tempfile::TempDir;
+    use crate::catalog::ColumnRef;
+    use crate::db::{Database, DatabaseError};
+    use crate::execution::executor::{Executor, try_collect};
+    use crate::execution::executor::dql::join::index_lookup_join::IndexLookupJoin;
+    use crate::execution::executor::dql::test::build_integers;
+    use crate::execution::executor::dql::values::Values;
+    use crate::expression::ScalarExpression;
+    use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinStrategy, JoinType};
+    use crate::planner::operator::Operator;
+    use crate::planner::operator::scan::ScanOperator;
+    use crate::planner::operator::values::ValuesOperator;
+    use crate::storage::kip::{KipStorage, KipTransaction};
+    use crate::storage::{Storage, Transaction};
+    use crate::types::tuple::create_table;
+
+    async fn index_lookup_join(
+        storage: &KipStorage,
+        join_type: JoinType,
+        inner_key: &str
+    ) -> Result<Vec<Vec<Option<i32>>>, DatabaseError> {
+        let transaction = storage.transaction().await?;
+        let table_name = Arc::new("t2".to_string());
+        let table = transaction.table(&table_name).unwrap().clone();
+        let inner_scan = match ScanOperator::new(table_name.clone(), &table).operator {
+            Operator::Scan(op) => op,
+            _ => unreachable!()
+        };
+        let inner_col = table.get_column_by_name(&inner_key.to_string()).unwrap().clone();
+        let index_meta = table.indexes
+            .iter()
+            .find(|meta| meta.column_ids == vec![inner_col.id.unwrap()])
+            .cloned();
+
+        let values_t1 = Values::from(ValuesOperator {
+            rows: vec![
+                build_integers(vec![Some(0), Some(2)]),
+                build_integers(vec![Some(1), None]),
+                build_integers(vec![Some(2), Some(3)]),
+                build_integers(vec![Some(3), Some(5)]),
+            ],
+            columns: vec![
+                outer_column(&transaction, "c1"),
+                outer_column(&transaction, "c2"),
+            ],
+        }).execute(&transaction);
+
+        let op = JoinOperator {
+            on: JoinCondition::On {
+                on: vec![(
+                    ScalarExpression::ColumnRef(outer_column(&transaction, "c2")),
+                    ScalarExpression::ColumnRef(inner_col)
+                )],
+                filter: None
+            },
+            join_type,
+            strategy: JoinStrategy::IndexLookup(index_meta),
+        };
+        let mut executor = IndexLookupJoin::from((op, values_t1, inner_scan)).execute(&transaction);
+        let tuples = try_collect(&mut executor).await?;
+
+        println!("{:?}_test: \n{}", join_type, create_table(&tuples));
+
+        Ok(tuples
+            .into_iter()
+            .map(|tuple| tuple.values
+                .iter()
+                .map(|value| value.i32())
+                .collect())
+            .collect())
+    }
+
+    fn outer_column(transaction: &KipTransaction, name: &str) -> ColumnRef {
+        transaction.table(&Arc::new("t1".to_string()))
+            .unwrap()
+            .get_column_by_name(&name.to_string())
+            .unwrap()
+            .clone()
+    }
+
+    async fn build_tables() -> Result<(TempDir, Database<KipStorage>), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let database = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = database.run("create table t1 (c1 int primary key, c2 int)").await?;
+        let _ = database.run("create table t2 (c3 int primary key, c4 int unique)").await?;
+        let _ = database.run("insert into t2 values (1, 10), (2, 20), (3, 30), (4, 2)").await?;
+
+        Ok((temp_dir, database))
+    }
+
+    #[tokio::test]
+    async fn test_primary_key_lookup() -> Result<(), DatabaseError> {
+        let (_temp_dir, database) = build_tables().await?;
+
+        assert_eq!(index_lookup_join(&database.storage, JoinType::Inner, "c3").await?, vec![
+            vec![Some(0), Some(2), Some(2), Some(20)],
+            vec![Some(2), Some(3), Some(3), Some(30)],
+        ]);
+        assert_eq!(index_lookup_join(&database.storage, JoinType::Left, "c3").await?, vec![
+            vec![Some(0), Some(2), Some(2), Some(20)],
+            vec![Some(1), None, None, None],
+            vec![Some(2), Some(3), Some(3), Some(30)],
+            vec![Some(3), Some(5), None, None],
+        ]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unique_index_lookup() -> Result<(), DatabaseError> {
+        let (_temp_dir, database) = build_tables().await?;
+
+        assert_eq!(index_lookup_join(&database.storage, JoinType::Inner, "c4").await?, vec![
+            vec![Some(0), Some(2), Some(4), Some(2)],
+        ]);
+        assert_eq!(index_lookup_join(&database.storage, JoinType::Left, "c4").await?, vec![
+            vec![Some(0), Some(2), Some(4), Some(2)],
+            vec![Some(1), None, None, None],
+            vec![Some(2), Some(3), None, None],
+            vec![Some(3), Some(5), None, None],
+        ]);
+
+        Ok(())
+    }
+}
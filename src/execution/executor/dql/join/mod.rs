@@ -1,6 +1,8 @@
 use crate::planner::operator::join::JoinType;
 
 pub(crate) mod hash_join;
+pub(crate) mod index_lookup_join;
+pub(crate) mod sort_merge_join;
 
 pub fn joins_nullable(join_type: &JoinType) -> (bool, bool) {
     match join_type {
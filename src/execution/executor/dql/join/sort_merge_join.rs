@@ -0,0 +1,370 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+use futures::TryStreamExt;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use crate::catalog::ColumnRef;
+use crate::execution::executor::dql::join::hash_join::HashJoin;
+use crate::execution::executor::dql::join::joins_nullable;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::expression::ScalarExpression;
+use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinType};
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use crate::types::value::{DataValue, ValueRef};
+
+/// Joins two inputs that are both sorted in ascending order on the equijoin keys.
+///
+/// Only the rows sharing the same key on each side are buffered,
+/// instead of the whole left input as in `HashJoin`.
+pub struct SortMergeJoin<'a> {
+    on: JoinCondition,
+    ty: JoinType,
+    left_input: BoxedExecutor<'a>,
+    right_input: BoxedExecutor<'a>
+}
+
+impl<'a> From<(JoinOperator, BoxedExecutor<'a>, BoxedExecutor<'a>)> for SortMergeJoin<'a> {
+    fn from((JoinOperator { on, join_type, .. }, left_input, right_input): (JoinOperator, BoxedExecutor<'a>, BoxedExecutor<'a>)) -> Self {
+        SortMergeJoin {
+            on,
+            ty: join_type,
+            left_input,
+            right_input,
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for SortMergeJoin<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
+        self._execute()
+    }
+}
+
+type KeyedTuple = (Vec<ValueRef>, Tuple);
+
+impl SortMergeJoin<'_> {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<'a>(self) where Self: 'a {
+        let SortMergeJoin { on, ty, mut left_input, mut right_input } = self;
+
+        if ty == JoinType::Cross {
+            unreachable!("Cross join should not be in SortMergeJoinExecutor");
+        }
+        let ((on_left_keys, on_right_keys), filter): ((Vec<ScalarExpression>, Vec<ScalarExpression>), _) = match on {
+            JoinCondition::On { on, filter } => (on.into_iter().unzip(), filter),
+            JoinCondition::None => unreachable!("SortMergeJoin must has on condition")
+        };
+        let (left_force_nullable, right_force_nullable) = joins_nullable(&ty);
+        let (left_outer, right_outer) = (
+            matches!(ty, JoinType::Left | JoinType::Full),
+            matches!(ty, JoinType::Right | JoinType::Full)
+        );
+
+        let mut left_row = Self::next_row(&mut left_input, &on_left_keys).await?;
+        let mut right_row = Self::next_row(&mut right_input, &on_right_keys).await?;
+
+        let mut join_columns = Vec::new();
+        if let Some((_, tuple)) = &left_row {
+            HashJoin::columns_filling(tuple, &mut join_columns, left_force_nullable);
+        }
+        let left_cols_len = join_columns.len();
+        if let Some((_, tuple)) = &right_row {
+            HashJoin::columns_filling(tuple, &mut join_columns, right_force_nullable);
+        }
+
+        loop {
+            let ordering = match (&left_row, &right_row) {
+                (None, None) => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((left_keys, _)), Some((right_keys, _))) => {
+                    // NULL never equals to anything, so the row can only be output as unmatched.
+                    if Self::has_null(left_keys) {
+                        Ordering::Less
+                    } else if Self::has_null(right_keys) {
+                        Ordering::Greater
+                    } else {
+                        Self::compare_keys(left_keys, right_keys)
+                    }
+                }
+            };
+
+            match ordering {
+                Ordering::Less => {
+                    let (_, tuple) = left_row.take().unwrap();
+
+                    if left_outer {
+                        yield Self::left_padding(tuple, &join_columns);
+                    }
+                    left_row = Self::next_row(&mut left_input, &on_left_keys).await?;
+                }
+                Ordering::Greater => {
+                    let (_, tuple) = right_row.take().unwrap();
+
+                    if right_outer {
+                        yield Self::right_padding(tuple, &join_columns, left_cols_len);
+                    }
+                    right_row = Self::next_row(&mut right_input, &on_right_keys).await?;
+                }
+                Ordering::Equal => {
+                    let (keys, left_tuple) = left_row.take().unwrap();
+                    let mut left_group = vec![left_tuple];
+                    let mut right_group = Vec::new();
+
+                    // collect all rows on both sides that share the current key
+                    left_row = Self::next_row(&mut left_input, &on_left_keys).await?;
+                    while let Some((next_keys, _)) = &left_row {
+                        if Self::has_null(next_keys) || Self::compare_keys(&keys, next_keys) != Ordering::Equal {
+                            break;
+                        }
+                        left_group.push(left_row.take().unwrap().1);
+                        left_row = Self::next_row(&mut left_input, &on_left_keys).await?;
+                    }
+                    while let Some((next_keys, _)) = &right_row {
+                        if Self::has_null(next_keys) || Self::compare_keys(&keys, next_keys) != Ordering::Equal {
+                            break;
+                        }
+                        right_group.push(right_row.take().unwrap().1);
+                        right_row = Self::next_row(&mut right_input, &on_right_keys).await?;
+                    }
+
+                    let mut right_used = vec![false; right_group.len()];
+
+                    for left_tuple in left_group {
+                        let mut left_used = false;
+
+                        for (i, right_tuple) in right_group.iter().enumerate() {
+                            let values = left_tuple.values
+                                .iter()
+                                .chain(right_tuple.values.iter())
+                                .cloned()
+                                .collect_vec();
+                            let tuple = Tuple { id: None, columns: join_columns.clone(), values };
+
+                            if let Some(expr) = &filter {
+                                match expr.eval_column(&tuple)?.as_ref() {
+                                    DataValue::Boolean(Some(true)) => (),
+                                    DataValue::Boolean(_) => continue,
+                                    _ => unreachable!("only bool"),
+                                }
+                            }
+                            left_used = true;
+                            right_used[i] = true;
+
+                            yield tuple;
+                        }
+
+                        if left_outer && !left_used {
+                            yield Self::left_padding(left_tuple, &join_columns);
+                        }
+                    }
+
+                    if right_outer {
+                        for (right_tuple, used) in right_group.into_iter().zip(right_used) {
+                            if !used {
+                                yield Self::right_padding(right_tuple, &join_columns, left_cols_len);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn next_row(
+        input: &mut BoxedExecutor<'_>,
+        on_keys: &[ScalarExpression]
+    ) -> Result<Option<KeyedTuple>, ExecutorError> {
+        if let Some(tuple) = input.try_next().await? {
+            let keys = on_keys
+                .iter()
+                .map(|expr| expr.eval_column(&tuple))
+                .try_collect()?;
+
+            Ok(Some((keys, tuple)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn has_null(keys: &[ValueRef]) -> bool {
+        keys.iter().any(|value| value.is_null())
+    }
+
+    fn compare_keys(left: &[ValueRef], right: &[ValueRef]) -> Ordering {
+        left.iter()
+            .zip(right.iter())
+            .map(|(l, r)| l.partial_cmp(r).unwrap_or(Ordering::Equal))
+            .find(|ordering| ordering != &Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+
+    pub(crate) fn left_padding(Tuple { mut values, .. }: Tuple, join_columns: &[ColumnRef]) -> Tuple {
+        let mut right_empties = join_columns[values.len()..]
+            .iter()
+            .map(|col| Arc::new(DataValue::none(col.datatype())))
+            .collect_vec();
+        values.append(&mut right_empties);
+
+        Tuple { id: None, columns: join_columns.to_vec(), values }
+    }
+
+    fn right_padding(Tuple { values, .. }: Tuple, join_columns: &[ColumnRef], left_cols_len: usize) -> Tuple {
+        let values = join_columns[..left_cols_len]
+            .iter()
+            .map(|col| Arc::new(DataValue::none(col.datatype())))
+            .chain(values)
+            .collect_vec();
+
+        Tuple { id: None, columns: join_columns.to_vec(), values }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use crate::catalog::{ColumnCatalog, ColumnDesc};
+    use crate::execution::executor::{BoxedExecutor, Executor, try_collect};
+    use crate::execution::executor::dql::join::sort_merge_join::SortMergeJoin;
+    use crate::execution::executor::dql::test::build_integers;
+    use crate::execution::executor::dql::values::Values;
+    use crate::execution::ExecutorError;
+    use crate::expression::ScalarExpression;
+    use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinStrategy, JoinType};
+    use crate::planner::operator::values::ValuesOperator;
+    use crate::storage::memory::MemStorage;
+    use crate::storage::Storage;
+    use crate::storage::Transaction;
+    use crate::types::LogicalType;
+    use crate::types::tuple::create_table;
+
+    fn build_sorted_values<T: Transaction>(t: &T) -> (Vec<(ScalarExpression, ScalarExpression)>, BoxedExecutor<'_>, BoxedExecutor<'_>) {
+        let desc = ColumnDesc::new(LogicalType::Integer, false, false);
+
+        let t1_columns = vec![
+            Arc::new(ColumnCatalog::new("c1".to_string(), true, desc.clone(), None)),
+            Arc::new(ColumnCatalog::new("c2".to_string(), true, desc.clone(), None)),
+        ];
+        let t2_columns = vec![
+            Arc::new(ColumnCatalog::new("c3".to_string(), true, desc.clone(), None)),
+            Arc::new(ColumnCatalog::new("c4".to_string(), true, desc.clone(), None)),
+        ];
+
+        let on_keys = vec![
+            (ScalarExpression::ColumnRef(t1_columns[0].clone()), ScalarExpression::ColumnRef(t2_columns[0].clone()))
+        ];
+
+        let values_t1 = Values::from(ValuesOperator {
+            rows: vec![
+                build_integers(vec![None, Some(0)]),
+                build_integers(vec![Some(0), Some(1)]),
+                build_integers(vec![Some(1), Some(2)]),
+                build_integers(vec![Some(1), Some(3)]),
+                build_integers(vec![Some(3), Some(4)]),
+            ],
+            columns: t1_columns,
+        });
+        let values_t2 = Values::from(ValuesOperator {
+            rows: vec![
+                build_integers(vec![Some(1), Some(10)]),
+                build_integers(vec![Some(1), Some(11)]),
+                build_integers(vec![Some(2), Some(12)]),
+                build_integers(vec![Some(3), Some(13)]),
+            ],
+            columns: t2_columns,
+        });
+
+        (on_keys, values_t1.execute(t), values_t2.execute(t))
+    }
+
+    async fn sort_merge_join(join_type: JoinType) -> Result<Vec<Vec<Option<i32>>>, ExecutorError> {
+        let transaction = MemStorage::new().transaction().await?;
+        let (keys, left, right) = build_sorted_values(&transaction);
+
+        let op = JoinOperator {
+            on: JoinCondition::On { on: keys, filter: None },
+            join_type,
+            strategy: JoinStrategy::SortMerge,
+        };
+        let mut executor = SortMergeJoin::from((op, left, right)).execute(&transaction);
+        let tuples = try_collect(&mut executor).await?;
+
+        println!("{:?}_test: \n{}", join_type, create_table(&tuples));
+
+        Ok(tuples
+            .into_iter()
+            .map(|tuple| tuple.values
+                .iter()
+                .map(|value| value.i32())
+                .collect())
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn test_inner_join() -> Result<(), ExecutorError> {
+        let rows = sort_merge_join(JoinType::Inner).await?;
+
+        assert_eq!(rows, vec![
+            vec![Some(1), Some(2), Some(1), Some(10)],
+            vec![Some(1), Some(2), Some(1), Some(11)],
+            vec![Some(1), Some(3), Some(1), Some(10)],
+            vec![Some(1), Some(3), Some(1), Some(11)],
+            vec![Some(3), Some(4), Some(3), Some(13)],
+        ]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_left_join() -> Result<(), ExecutorError> {
+        let rows = sort_merge_join(JoinType::Left).await?;
+
+        assert_eq!(rows, vec![
+            vec![None, Some(0), None, None],
+            vec![Some(0), Some(1), None, None],
+            vec![Some(1), Some(2), Some(1), Some(10)],
+            vec![Some(1), Some(2), Some(1), Some(11)],
+            vec![Some(1), Some(3), Some(1), Some(10)],
+            vec![Some(1), Some(3), Some(1), Some(11)],
+            vec![Some(3), Some(4), Some(3), Some(13)],
+        ]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_right_join() -> Result<(), ExecutorError> {
+        let rows = sort_merge_join(JoinType::Right).await?;
+
+        assert_eq!(rows, vec![
+            vec![Some(1), Some(2), Some(1), Some(10)],
+            vec![Some(1), Some(2), Some(1), Some(11)],
+            vec![Some(1), Some(3), Some(1), Some(10)],
+            vec![Some(1), Some(3), Some(1), Some(11)],
+            vec![None, None, Some(2), Some(12)],
+            vec![Some(3), Some(4), Some(3), Some(13)],
+        ]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_full_join() -> Result<(), ExecutorError> {
+        let rows = sort_merge_join(JoinType::Full).await?;
+
+        assert_eq!(rows, vec![
+            vec![None, Some(0), None, None],
+            vec![Some(0), Some(1), None, None],
+            vec![Some(1), Some(2), Some(1), Some(10)],
+            vec![Some(1), Some(2), Some(1), Some(11)],
+            vec![Some(1), Some(3), Some(1), Some(10)],
+            vec![Some(1), Some(3), Some(1), Some(11)],
+            vec![None, None, Some(2), Some(12)],
+            vec![Some(3), Some(4), Some(3), Some(13)],
+        ]);
+
+        Ok(())
+    }
+}
@@ -3,17 +3,17 @@ use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::limit::LimitOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
-pub struct Limit {
+pub struct Limit<'a> {
     offset: Option<usize>,
     limit: Option<usize>,
-    input: BoxedExecutor
+    input: BoxedExecutor<'a>
 }
 
-impl From<(LimitOperator, BoxedExecutor)> for Limit {
-    fn from((LimitOperator { offset, limit }, input): (LimitOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(LimitOperator, BoxedExecutor<'a>)> for Limit<'a> {
+    fn from((LimitOperator { offset, limit }, input): (LimitOperator, BoxedExecutor<'a>)) -> Self {
         Limit {
             offset: Some(offset),
             limit: Some(limit),
@@ -22,15 +22,15 @@ impl From<(LimitOperator, BoxedExecutor)> for Limit {
     }
 }
 
-impl<S: Storage> Executor<S> for Limit {
-    fn execute(self, _: &S) -> BoxedExecutor {
+impl<'a, T: Transaction> Executor<'a, T> for Limit<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
         self._execute()
     }
 }
 
-impl Limit {
+impl Limit<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute(self) {
+    pub async fn _execute<'a>(self) where Self: 'a {
         let Limit { offset, limit, input } = self;
 
         if limit.is_some() && limit.unwrap() == 0 {
@@ -44,11 +44,14 @@ impl Limit {
         for (i, tuple) in input.enumerate() {
             if i < offset_val {
                 continue
-            } else if i > offset_limit {
-                break
             }
 
             yield tuple?;
+
+            // Tips: stop before pulling the next tuple, which would be locked by `FOR UPDATE`
+            if i >= offset_limit {
+                break
+            }
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,45 @@
+use futures_async_stream::try_stream;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::lock::LockOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+
+pub struct Lock<'a> {
+    op: LockOperator,
+    input: BoxedExecutor<'a>
+}
+
+impl<'a> From<(LockOperator, BoxedExecutor<'a>)> for Lock<'a> {
+    fn from((op, input): (LockOperator, BoxedExecutor<'a>)) -> Self {
+        Lock {
+            op,
+            input
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for Lock<'a> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl Lock<'_> {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<'a, T: Transaction>(self, transaction: &'a T) where Self: 'a {
+        let Lock { op: LockOperator { table_name, lock_mode, lock_wait }, input } = self;
+
+        #[for_await]
+        for tuple in input {
+            let tuple = tuple?;
+            let tuple_id = tuple.id
+                .clone()
+                .ok_or_else(|| ExecutorError::InternalError("the locked tuple has no id".to_string()))?;
+
+            if transaction.lock_tuple(&table_name, &tuple_id, lock_mode, lock_wait).await? {
+                yield tuple;
+            }
+        }
+    }
+}
@@ -4,6 +4,7 @@ pub(crate) mod values;
 pub(crate) mod filter;
 pub(crate) mod sort;
 pub(crate) mod limit;
+pub(crate) mod lock;
 pub(crate) mod join;
 pub(crate) mod dummy;
 pub(crate) mod aggregate;
@@ -11,6 +12,7 @@ pub(crate) mod index_scan;
 
 #[cfg(test)]
 pub(crate) mod test {
+    use std::hash::Hasher;
     use std::sync::Arc;
     use itertools::Itertools;
     use crate::types::value::{DataValue, ValueRef};
@@ -20,4 +22,16 @@ pub(crate) mod test {
             .map(|i| Arc::new(DataValue::Int32(i)))
             .collect_vec()
     }
+
+    /// Hashes every key to the same value, to force hash collisions.
+    #[derive(Default)]
+    pub(crate) struct CollisionHasher;
+
+    impl Hasher for CollisionHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
 }
\ No newline at end of file
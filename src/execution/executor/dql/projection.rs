@@ -3,16 +3,16 @@ use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::project::ProjectOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
-pub struct Projection {
+pub struct Projection<'a> {
     exprs: Vec<ScalarExpression>,
-    input: BoxedExecutor
+    input: BoxedExecutor<'a>
 }
 
-impl From<(ProjectOperator, BoxedExecutor)> for Projection {
-    fn from((ProjectOperator { columns }, input): (ProjectOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(ProjectOperator, BoxedExecutor<'a>)> for Projection<'a> {
+    fn from((ProjectOperator { columns }, input): (ProjectOperator, BoxedExecutor<'a>)) -> Self {
         Projection {
             exprs: columns,
             input,
@@ -20,15 +20,15 @@ impl From<(ProjectOperator, BoxedExecutor)> for Projection {
     }
 }
 
-impl<S: Storage> Executor<S> for Projection {
-    fn execute(self, _: &S) -> BoxedExecutor {
+impl<'a, T: Transaction> Executor<'a, T> for Projection<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
         self._execute()
     }
 }
 
-impl Projection {
+impl Projection<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute(self) {
+    pub async fn _execute<'a>(self) where Self: 'a {
         let Projection { exprs, input } = self;
 
         #[for_await]
@@ -2,7 +2,8 @@ use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::scan::ScanOperator;
-use crate::storage::{Transaction, Iter, Storage};
+use crate::storage::{Transaction, Iter};
+use crate::storage::system::SystemTable;
 use crate::types::tuple::Tuple;
 
 pub(crate) struct SeqScan {
@@ -17,26 +18,24 @@ impl From<ScanOperator> for SeqScan {
     }
 }
 
-impl<S: Storage> Executor<S> for SeqScan {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for SeqScan {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
 impl SeqScan {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
         let ScanOperator { table_name,  columns, limit, .. } = self.op;
 
-        if let Some(transaction) = storage.transaction(&table_name).await {
-            let mut iter = transaction.read(
-                limit,
-                columns
-            )?;
+        let mut iter: Box<dyn Iter + '_> = match SystemTable::from_name(&table_name) {
+            Some(system_table) => Box::new(system_table.read(transaction, limit, columns)?),
+            None => Box::new(transaction.read(&table_name, limit, columns)?),
+        };
 
-            while let Some(tuple) =  iter.next_tuple()? {
-                yield tuple;
-            }
+        while let Some(tuple) =  iter.next_tuple()? {
+            yield tuple;
         }
     }
 }
\ No newline at end of file
@@ -3,17 +3,17 @@ use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::sort::{SortField, SortOperator};
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
-pub struct Sort {
+pub struct Sort<'a> {
     sort_fields: Vec<SortField>,
     limit: Option<usize>,
-    input: BoxedExecutor
+    input: BoxedExecutor<'a>
 }
 
-impl From<(SortOperator, BoxedExecutor)> for Sort {
-    fn from((SortOperator { sort_fields, limit }, input): (SortOperator, BoxedExecutor)) -> Self {
+impl<'a> From<(SortOperator, BoxedExecutor<'a>)> for Sort<'a> {
+    fn from((SortOperator { sort_fields, limit }, input): (SortOperator, BoxedExecutor<'a>)) -> Self {
         Sort {
             sort_fields,
             limit,
@@ -22,15 +22,15 @@ impl From<(SortOperator, BoxedExecutor)> for Sort {
     }
 }
 
-impl<S: Storage> Executor<S> for Sort {
-    fn execute(self, _: &S) -> BoxedExecutor {
+impl<'a, T: Transaction> Executor<'a, T> for Sort<'a> {
+    fn execute(self, _: &'a T) -> BoxedExecutor<'a> {
         self._execute()
     }
 }
 
-impl Sort {
+impl Sort<'_> {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute(self) {
+    pub async fn _execute<'a>(self) where Self: 'a {
         let Sort { sort_fields, limit, input } = self;
         let mut tuples: Vec<Tuple> = vec![];
 
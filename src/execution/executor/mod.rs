@@ -1,3 +1,4 @@
+pub(crate) mod analyze;
 pub(crate) mod dql;
 pub(crate)mod ddl;
 pub(crate)mod dml;
@@ -5,8 +6,14 @@ pub(crate) mod show;
 
 use futures::stream::BoxStream;
 use futures::TryStreamExt;
+use crate::execution::executor::analyze::AnalyzedPlan;
+use crate::execution::executor::ddl::create_schema::CreateSchema;
 use crate::execution::executor::ddl::create_table::CreateTable;
+use crate::execution::executor::ddl::create_view::CreateView;
+use crate::execution::executor::ddl::drop_schema::DropSchema;
 use crate::execution::executor::ddl::drop_table::DropTable;
+use crate::execution::executor::ddl::drop_view::DropView;
+use crate::execution::executor::ddl::refresh_materialized_view::RefreshMaterializedView;
 use crate::execution::executor::ddl::truncate::Truncate;
 use crate::execution::executor::dml::delete::Delete;
 use crate::execution::executor::dml::insert::Insert;
@@ -17,106 +24,208 @@ use crate::execution::executor::dql::dummy::Dummy;
 use crate::execution::executor::dql::filter::Filter;
 use crate::execution::executor::dql::index_scan::IndexScan;
 use crate::execution::executor::dql::join::hash_join::HashJoin;
+use crate::execution::executor::dql::join::index_lookup_join::IndexLookupJoin;
+use crate::execution::executor::dql::join::sort_merge_join::SortMergeJoin;
 use crate::execution::executor::dql::limit::Limit;
+use crate::execution::executor::dql::lock::Lock;
 use crate::execution::executor::dql::projection::Projection;
 use crate::execution::executor::dql::seq_scan::SeqScan;
 use crate::execution::executor::dql::sort::Sort;
 use crate::execution::executor::dql::values::Values;
+use crate::execution::executor::show::show_columns::ShowColumns;
+use crate::execution::executor::show::show_create_table::ShowCreateTable;
+use crate::execution::executor::show::show_indexes::ShowIndexes;
 use crate::execution::executor::show::show_table::ShowTables;
 use crate::execution::ExecutorError;
 use crate::planner::LogicalPlan;
 use crate::planner::operator::Operator;
-use crate::storage::Storage;
+use crate::planner::operator::join::JoinStrategy;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 
-pub type BoxedExecutor = BoxStream<'static, Result<Tuple, ExecutorError>>;
+pub type BoxedExecutor<'a> = BoxStream<'a, Result<Tuple, ExecutorError>>;
 
-pub trait Executor<S: Storage> {
-    fn execute(self, storage: &S) -> BoxedExecutor;
+/// The executors share the transaction, which is borrowed until the returned executor is dropped.
+pub trait Executor<'a, T: Transaction> {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a>;
 }
 
-pub fn build<S: Storage>(plan: LogicalPlan, storage: &S) -> BoxedExecutor {
+pub fn build<T: Transaction>(plan: LogicalPlan, transaction: &T) -> BoxedExecutor<'_> {
+    build_executor(plan, transaction, None)
+}
+
+/// Build the executors which count their output rows and measure their time,
+/// the figures are filled into the returned plan tree while the executor runs.
+pub fn build_analyzed<T: Transaction>(plan: LogicalPlan, transaction: &T) -> (BoxedExecutor<'_>, AnalyzedPlan) {
+    let mut analyzed = Vec::with_capacity(1);
+    let executor = build_executor(plan, transaction, Some(&mut analyzed));
+
+    (executor, analyzed.remove(0))
+}
+
+/// Build the executor of the plan, and push its analyzed node into `analyzed` if it is given.
+fn build_executor<'a, T: Transaction>(
+    plan: LogicalPlan,
+    transaction: &'a T,
+    analyzed: Option<&mut Vec<AnalyzedPlan>>
+) -> BoxedExecutor<'a> {
+    let mut node = analyzed.is_some().then(|| AnalyzedPlan::new(&plan));
     let LogicalPlan { operator, mut childrens } = plan;
+    let mut build_child = |plan: LogicalPlan, transaction: &'a T| {
+        build_executor(plan, transaction, node.as_mut().map(|node| &mut node.childrens))
+    };
 
-    match operator {
-        Operator::Dummy => Dummy{ }.execute(storage),
+    let executor = match operator {
+        Operator::Dummy => Dummy{ }.execute(transaction),
         Operator::Aggregate(op) => {
-            let input = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
 
             if op.groupby_exprs.is_empty() {
-                SimpleAggExecutor::from((op, input)).execute(storage)
+                SimpleAggExecutor::from((op, input)).execute(transaction)
             } else {
-                HashAggExecutor::from((op, input)).execute(storage)
+                HashAggExecutor::from((op, input)).execute(transaction)
             }
         }
         Operator::Filter(op) => {
-            let input = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
 
-            Filter::from((op, input)).execute(storage)
+            Filter::from((op, input)).execute(transaction)
         }
         Operator::Join(op) => {
-            let left_input = build(childrens.remove(0), storage);
-            let right_input = build(childrens.remove(0), storage);
-
-            HashJoin::from((op, left_input, right_input)).execute(storage)
+            let left_input = build_child(childrens.remove(0), transaction);
+
+            match op.strategy {
+                JoinStrategy::Hash => {
+                    let right_input = build_child(childrens.remove(0), transaction);
+
+                    HashJoin::from((op, left_input, right_input)).execute(transaction)
+                }
+                JoinStrategy::SortMerge => {
+                    let right_input = build_child(childrens.remove(0), transaction);
+
+                    SortMergeJoin::from((op, left_input, right_input)).execute(transaction)
+                }
+                JoinStrategy::IndexLookup(_) => {
+                    let inner_plan = childrens.remove(0);
+                    let mut inner_metrics = None;
+                    if let Some(node) = node.as_mut() {
+                        let inner_node = AnalyzedPlan::new(&inner_plan);
+
+                        inner_metrics = Some(inner_node.shared_metrics());
+                        node.childrens.push(inner_node);
+                    }
+                    let inner_scan = match inner_plan.operator {
+                        Operator::Scan(scan_op) => scan_op,
+                        _ => unreachable!("the inner side of IndexLookupJoin must be a scan")
+                    };
+
+                    IndexLookupJoin::from((op, left_input, inner_scan))
+                        .with_inner_metrics(inner_metrics)
+                        .execute(transaction)
+                }
+            }
         }
         Operator::Project(op) => {
-            let input = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
 
-            Projection::from((op, input)).execute(storage)
+            Projection::from((op, input)).execute(transaction)
         }
         Operator::Scan(op) => {
             if op.index_by.is_some() {
-                IndexScan::from(op).execute(storage)
+                IndexScan::from(op).execute(transaction)
             } else {
-                SeqScan::from(op).execute(storage)
+                SeqScan::from(op).execute(transaction)
             }
         }
         Operator::Sort(op) => {
-            let input = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
 
-            Sort::from((op, input)).execute(storage)
+            Sort::from((op, input)).execute(transaction)
         }
         Operator::Limit(op) => {
-            let input = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
+
+            Limit::from((op, input)).execute(transaction)
+        }
+        Operator::Lock(op) => {
+            let input = build_child(childrens.remove(0), transaction);
 
-            Limit::from((op, input)).execute(storage)
+            Lock::from((op, input)).execute(transaction)
         }
         Operator::Insert(op) => {
-            let input = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
 
-            Insert::from((op, input)).execute(storage)
+            Insert::from((op, input)).execute(transaction)
         }
         Operator::Update(op) => {
-            let input = build(childrens.remove(0), storage);
-            let values = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
 
-            Update::from((op, input, values)).execute(storage)
+            Update::from((op, input)).execute(transaction)
         }
         Operator::Delete(op) => {
-            let input = build(childrens.remove(0), storage);
+            let input = build_child(childrens.remove(0), transaction);
 
-            Delete::from((op, input)).execute(storage)
+            Delete::from((op, input)).execute(transaction)
         }
         Operator::Values(op) => {
-            Values::from(op).execute(storage)
+            Values::from(op).execute(transaction)
         }
         Operator::CreateTable(op) => {
-            CreateTable::from(op).execute(storage)
+            let input = childrens.pop().map(|plan| build_child(plan, transaction));
+
+            CreateTable::from((op, input)).execute(transaction)
         }
         Operator::DropTable(op) => {
-            DropTable::from(op).execute(storage)
+            DropTable::from(op).execute(transaction)
+        }
+        Operator::CreateSchema(op) => {
+            CreateSchema::from(op).execute(transaction)
+        }
+        Operator::DropSchema(op) => {
+            DropSchema::from(op).execute(transaction)
+        }
+        Operator::CreateView(op) => {
+            let input = childrens.pop().map(|plan| build_child(plan, transaction));
+
+            CreateView::from((op, input)).execute(transaction)
+        }
+        Operator::DropView(op) => {
+            DropView::from(op).execute(transaction)
+        }
+        Operator::RefreshMaterializedView(op) => {
+            let input = build_child(childrens.remove(0), transaction);
+
+            RefreshMaterializedView::from((op, input)).execute(transaction)
         }
         Operator::Truncate(op) => {
-            Truncate::from(op).execute(storage)
+            Truncate::from(op).execute(transaction)
         }
         Operator::Show(op) => {
-            ShowTables::from(op).execute(storage)
+            ShowTables::from(op).execute(transaction)
+        }
+        Operator::ShowColumns(op) => {
+            ShowColumns::from(op).execute(transaction)
+        }
+        Operator::ShowIndexes(op) => {
+            ShowIndexes::from(op).execute(transaction)
+        }
+        Operator::ShowCreateTable(op) => {
+            ShowCreateTable::from(op).execute(transaction)
+        }
+    };
+
+    match (node, analyzed) {
+        (Some(node), Some(analyzed)) => {
+            let executor = node.measure(executor);
+            analyzed.push(node);
+
+            executor
         }
+        _ => executor,
     }
 }
 
-pub async fn try_collect(executor: &mut BoxedExecutor) -> Result<Vec<Tuple>, ExecutorError> {
+pub async fn try_collect(executor: &mut BoxedExecutor<'_>) -> Result<Vec<Tuple>, ExecutorError> {
     let mut output = Vec::new();
 
     while let Some(tuple) = executor.try_next().await? {
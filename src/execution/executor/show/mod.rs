@@ -1,4 +1,4 @@
+pub(crate) mod show_columns;
+pub(crate) mod show_create_table;
+pub(crate) mod show_indexes;
 pub(crate) mod show_table;
-
-
-
@@ -0,0 +1,65 @@
+use std::sync::Arc;
+use futures_async_stream::try_stream;
+use sqlparser::ast::DataType;
+use crate::catalog::{ColumnCatalog, ColumnRef, TableName};
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::show::ShowColumnsOperator;
+use crate::storage::{StorageError, Transaction};
+use crate::types::tuple::Tuple;
+use crate::types::value::{DataValue, ValueRef};
+
+pub struct ShowColumns {
+    table_name: TableName,
+}
+
+impl From<ShowColumnsOperator> for ShowColumns {
+    fn from(ShowColumnsOperator { table_name }: ShowColumnsOperator) -> Self {
+        ShowColumns {
+            table_name
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for ShowColumns {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl ShowColumns {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let table_catalog = transaction.table(&self.table_name)
+            .cloned()
+            .ok_or_else(|| StorageError::TableNotFound(self.table_name.to_string()))?;
+        let columns: Vec<ColumnRef> = ["FIELD", "TYPE", "NULL", "KEY", "DEFAULT"]
+            .into_iter()
+            .map(|name| Arc::new(ColumnCatalog::new_dummy(name.to_string())))
+            .collect();
+
+        for column in table_catalog.visible_columns() {
+            let key = if column.desc.is_primary {
+                Some("PRI")
+            } else if column.desc.is_unique {
+                Some("UNI")
+            } else {
+                None
+            };
+            let nullable = if column.nullable { "YES" } else { "NO" };
+            let values: Vec<ValueRef> = vec![
+                Arc::new(DataValue::Utf8(Some(column.name.clone()))),
+                Arc::new(DataValue::Utf8(Some(DataType::try_from(column.datatype())?.to_string()))),
+                Arc::new(DataValue::Utf8(Some(nullable.to_string()))),
+                Arc::new(DataValue::Utf8(key.map(String::from))),
+                Arc::new(DataValue::Utf8(None)),
+            ];
+
+            yield Tuple {
+                id: None,
+                columns: columns.clone(),
+                values,
+            };
+        }
+    }
+}
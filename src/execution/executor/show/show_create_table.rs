@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use sqlparser::ast::{ColumnDef, ColumnOption, ColumnOptionDef, DataType, Ident};
+use crate::catalog::{ColumnCatalog, ColumnRef, TableName};
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::show::ShowCreateTableOperator;
+use crate::storage::{StorageError, Transaction};
+use crate::types::errors::TypeError;
+use crate::types::tuple::Tuple;
+use crate::types::value::{DataValue, ValueRef};
+
+pub struct ShowCreateTable {
+    table_name: TableName,
+}
+
+impl From<ShowCreateTableOperator> for ShowCreateTable {
+    fn from(ShowCreateTableOperator { table_name }: ShowCreateTableOperator) -> Self {
+        ShowCreateTable {
+            table_name
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for ShowCreateTable {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl ShowCreateTable {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let table_catalog = transaction.table(&self.table_name)
+            .cloned()
+            .ok_or_else(|| StorageError::TableNotFound(self.table_name.to_string()))?;
+        let column_defs = table_catalog.visible_columns()
+            .iter()
+            .map(Self::column_def)
+            .collect::<Result<Vec<_>, _>>()?;
+        let columns: Vec<ColumnRef> = ["TABLE", "CREATE TABLE"]
+            .into_iter()
+            .map(|name| Arc::new(ColumnCatalog::new_dummy(name.to_string())))
+            .collect();
+        let values: Vec<ValueRef> = vec![
+            Arc::new(DataValue::Utf8(Some(self.table_name.to_string()))),
+            Arc::new(DataValue::Utf8(Some(
+                format!("CREATE TABLE {} ({})", self.table_name, column_defs.iter().join(", "))
+            ))),
+        ];
+
+        yield Tuple {
+            id: None,
+            columns,
+            values,
+        };
+    }
+
+    /// The definition of the column which is bound back to the same `ColumnCatalog`.
+    fn column_def(column: &ColumnRef) -> Result<ColumnDef, TypeError> {
+        let options = if column.desc.is_primary {
+            vec![ColumnOption::Unique { is_primary: true }]
+        } else {
+            let nullable = if column.nullable { ColumnOption::Null } else { ColumnOption::NotNull };
+
+            if column.desc.is_unique {
+                vec![ColumnOption::Unique { is_primary: false }, nullable]
+            } else {
+                vec![nullable]
+            }
+        };
+
+        Ok(ColumnDef {
+            name: Ident::new(&column.name),
+            data_type: DataType::try_from(column.datatype())?,
+            collation: None,
+            options: options.into_iter()
+                .map(|option| ColumnOptionDef { name: None, option })
+                .collect(),
+        })
+    }
+}
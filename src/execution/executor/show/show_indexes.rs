@@ -0,0 +1,61 @@
+use std::sync::Arc;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use crate::catalog::{ColumnCatalog, ColumnRef, TableName};
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::show::ShowIndexesOperator;
+use crate::storage::{StorageError, Transaction};
+use crate::types::tuple::Tuple;
+use crate::types::value::{DataValue, ValueRef};
+
+pub struct ShowIndexes {
+    table_name: TableName,
+}
+
+impl From<ShowIndexesOperator> for ShowIndexes {
+    fn from(ShowIndexesOperator { table_name }: ShowIndexesOperator) -> Self {
+        ShowIndexes {
+            table_name
+        }
+    }
+}
+
+impl<'a, T: Transaction> Executor<'a, T> for ShowIndexes {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
+    }
+}
+
+impl ShowIndexes {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let table_catalog = transaction.table(&self.table_name)
+            .cloned()
+            .ok_or_else(|| StorageError::TableNotFound(self.table_name.to_string()))?;
+        let columns: Vec<ColumnRef> = ["TABLE", "NAME", "COLUMNS", "UNIQUE"]
+            .into_iter()
+            .map(|name| Arc::new(ColumnCatalog::new_dummy(name.to_string())))
+            .collect();
+
+        for index_meta in &table_catalog.indexes {
+            let column_names = index_meta.column_ids
+                .iter()
+                .filter_map(|column_id| table_catalog.get_column_by_id(column_id))
+                .map(|column| column.name.as_str())
+                .join(", ");
+            let values: Vec<ValueRef> = vec![
+                Arc::new(DataValue::Utf8(Some(self.table_name.to_string()))),
+                Arc::new(DataValue::Utf8(Some(index_meta.name.clone()))),
+                Arc::new(DataValue::Utf8(Some(column_names))),
+                Arc::new(DataValue::Boolean(Some(index_meta.is_unique))),
+            ];
+
+            yield Tuple {
+                id: None,
+                columns: columns.clone(),
+                values,
+            };
+        }
+    }
+}
@@ -2,7 +2,7 @@ use futures_async_stream::try_stream;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::show::ShowTablesOperator;
-use crate::storage::Storage;
+use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 use crate::catalog::ColumnCatalog;
 use crate::catalog::ColumnRef;
@@ -21,16 +21,24 @@ impl From<ShowTablesOperator> for ShowTables {
     }
 }
 
-impl<S: Storage> Executor<S> for ShowTables {
-    fn execute(self, storage: &S) -> BoxedExecutor {
-        self._execute(storage.clone())
+impl<'a, T: Transaction> Executor<'a, T> for ShowTables {
+    fn execute(self, transaction: &'a T) -> BoxedExecutor<'a> {
+        self._execute(transaction)
     }
 }
 
 impl ShowTables {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
-    pub async fn _execute<S: Storage>(self, storage: S) {
-        let tables = storage.show_tables().await?;
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let mut tables = transaction.show_tables()?;
+        // the materialized views are listed by the tables which store their rows
+        tables.extend(
+            transaction.show_views()?
+                .into_iter()
+                .filter(|view| !view.materialized)
+                .map(|view| view.name.to_string())
+        );
+        tables.sort();
 
         for table in tables {
             let columns: Vec<ColumnRef> = vec![
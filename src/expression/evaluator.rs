@@ -2,6 +2,7 @@ use std::sync::Arc;
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use crate::expression::value_compute::{binary_op, unary_op};
+use crate::catalog::ColumnCatalog;
 use crate::expression::ScalarExpression;
 use crate::types::errors::TypeError;
 use crate::types::tuple::Tuple;
@@ -18,15 +19,17 @@ impl ScalarExpression {
         match &self {
             ScalarExpression::Constant(val) => Ok(val.clone()),
             ScalarExpression::ColumnRef(col) => {
-                let value = Self::eval_with_name(&tuple, &col.name)
+                let value = Self::eval_with_column(&tuple, col)
                     .unwrap_or(&NULL_VALUE)
                     .clone();
 
                 Ok(value)
             },
             ScalarExpression::InputRef{ index, .. } => Ok(tuple.values[*index].clone()),
+            ScalarExpression::Parameter { .. } => unreachable!(),
             ScalarExpression::Alias{ expr, alias } => {
-                if let Some(value) = Self::eval_with_name(&tuple, alias) {
+                // only the columns output by expressions have no table
+                if let Some(value) = Self::eval_with_name(&tuple, alias, true) {
                     return Ok(value.clone());
                 }
 
@@ -53,15 +56,42 @@ impl ScalarExpression {
 
                 Ok(Arc::new(unary_op(&value, op)?))
             },
-            ScalarExpression::AggCall{ .. } => todo!()
+            ScalarExpression::AggCall{ .. } => todo!(),
+            ScalarExpression::Coalesce{ exprs, ty } => {
+                for expr in exprs {
+                    let value = expr.eval_column(tuple)?;
+
+                    if !value.is_null() {
+                        return Ok(value);
+                    }
+                }
+
+                Ok(Arc::new(DataValue::none(ty)))
+            }
+        }
+    }
+
+    /// Columns of the same table are told apart by id first, e.g. `t1.id` and `t2.id` after join.
+    fn eval_with_column<'a>(tuple: &'a Tuple, col: &ColumnCatalog) -> Option<&'a ValueRef> {
+        if col.table_name.is_some() && col.id.is_some() {
+            let position = tuple
+                .columns
+                .iter()
+                .position(|tul_col| tul_col.table_name == col.table_name && tul_col.id == col.id);
+
+            if let Some(i) = position {
+                return Some(&tuple.values[i]);
+            }
         }
+
+        Self::eval_with_name(tuple, &col.name, false)
     }
 
-    fn eval_with_name<'a>(tuple: &'a Tuple, name: &String) -> Option<&'a ValueRef> {
+    fn eval_with_name<'a>(tuple: &'a Tuple, name: &String, only_expr_output: bool) -> Option<&'a ValueRef> {
         tuple
             .columns
             .iter()
-            .find_position(|tul_col| &tul_col.name == name)
+            .find_position(|tul_col| &tul_col.name == name && !(only_expr_output && tul_col.table_name.is_some()))
             .map(|(i, _)| &tuple.values[i])
     }
 }
\ No newline at end of file
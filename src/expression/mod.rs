@@ -9,8 +9,9 @@ use crate::binder::BinderContext;
 
 use self::agg::AggKind;
 use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnRef};
-use crate::storage::Storage;
-use crate::types::value::ValueRef;
+use crate::storage::Transaction;
+use crate::types::errors::TypeError;
+use crate::types::value::{DataValue, ValueRef};
 use crate::types::LogicalType;
 use crate::types::tuple::Tuple;
 
@@ -59,6 +60,16 @@ pub enum ScalarExpression {
         args: Vec<ScalarExpression>,
         ty: LogicalType,
     },
+    /// The first non-NULL value of exprs, e.g. the merged column of `FULL JOIN ... USING`
+    Coalesce {
+        exprs: Vec<ScalarExpression>,
+        ty: LogicalType,
+    },
+    /// The placeholder `$index` of a cached plan, which is replaced by its value before the plan is optimized
+    Parameter {
+        index: usize,
+        ty: LogicalType,
+    },
 }
 
 impl ScalarExpression {
@@ -72,7 +83,7 @@ impl ScalarExpression {
 
     pub fn nullable(&self) -> bool {
         match self {
-            ScalarExpression::Constant(_) => false,
+            ScalarExpression::Constant(_) | ScalarExpression::Parameter { .. } => false,
             ScalarExpression::ColumnRef(col) => col.nullable,
             ScalarExpression::InputRef { .. } => unreachable!(),
             ScalarExpression::Alias { expr, .. } => expr.nullable(),
@@ -81,7 +92,8 @@ impl ScalarExpression {
             ScalarExpression::Unary { expr, .. } => expr.nullable(),
             ScalarExpression::Binary { left_expr, right_expr, .. } =>
                 left_expr.nullable() && right_expr.nullable(),
-            ScalarExpression::AggCall { args, .. } => args[0].nullable()
+            ScalarExpression::AggCall { args, .. } => args[0].nullable(),
+            ScalarExpression::Coalesce { exprs, .. } => exprs.iter().all(|expr| expr.nullable()),
         }
     }
 
@@ -104,6 +116,12 @@ impl ScalarExpression {
             Self::InputRef {
                 ty: return_type, ..
             } => return_type.clone(),
+            Self::Coalesce {
+                ty: return_type, ..
+            } => *return_type,
+            Self::Parameter {
+                ty: return_type, ..
+            } => *return_type,
             Self::IsNull { .. } => LogicalType::Boolean,
             Self::Alias { expr, .. } => expr.return_type(),
         }
@@ -131,7 +149,7 @@ impl ScalarExpression {
                     columns_collect(left_expr, vec);
                     columns_collect(right_expr, vec);
                 }
-                ScalarExpression::AggCall { args, .. } => {
+                ScalarExpression::AggCall { args, .. } | ScalarExpression::Coalesce { exprs: args, .. } => {
                     for expr in args {
                         columns_collect(expr, vec)
                     }
@@ -147,13 +165,44 @@ impl ScalarExpression {
         exprs
     }
 
-    pub fn has_agg_call<S: Storage>(&self, context: &BinderContext<S>) -> bool {
+    /// Replace the parameters with their values, which are checked against the inferred types.
+    pub(crate) fn bind_parameters(&mut self, values: &[ValueRef]) -> Result<(), TypeError> {
+        match self {
+            ScalarExpression::Parameter { index, ty } => {
+                let value = values[*index - 1].clone();
+                value.check_len(ty)?;
+
+                *self = ScalarExpression::Constant(value);
+            }
+            ScalarExpression::Alias { expr, .. }
+            | ScalarExpression::TypeCast { expr, .. }
+            | ScalarExpression::IsNull { expr }
+            | ScalarExpression::Unary { expr, .. } => expr.bind_parameters(values)?,
+            ScalarExpression::Binary { left_expr, right_expr, .. } => {
+                left_expr.bind_parameters(values)?;
+                right_expr.bind_parameters(values)?;
+            }
+            ScalarExpression::AggCall { args, .. } | ScalarExpression::Coalesce { exprs: args, .. } => {
+                for expr in args {
+                    expr.bind_parameters(values)?;
+                }
+            }
+            ScalarExpression::Constant(_)
+            | ScalarExpression::ColumnRef(_)
+            | ScalarExpression::InputRef { .. } => (),
+        }
+
+        Ok(())
+    }
+
+    pub fn has_agg_call<T: Transaction>(&self, context: &BinderContext<'_, T>) -> bool {
         match self {
             ScalarExpression::InputRef { index, .. } => {
                 context.agg_calls.get(*index).is_some()
             },
             ScalarExpression::AggCall { .. } => unreachable!(),
             ScalarExpression::Constant(_) => false,
+            ScalarExpression::Parameter { .. } => false,
             ScalarExpression::ColumnRef(_) => false,
             ScalarExpression::Alias { expr, .. } => expr.has_agg_call(context),
             ScalarExpression::TypeCast { expr, .. } => expr.has_agg_call(context),
@@ -162,6 +211,9 @@ impl ScalarExpression {
             ScalarExpression::Binary { left_expr, right_expr, .. } => {
                 left_expr.has_agg_call(context) || right_expr.has_agg_call(context)
             }
+            ScalarExpression::Coalesce { exprs, .. } => {
+                exprs.iter().any(|expr| expr.has_agg_call(context))
+            }
         }
     }
 
@@ -178,6 +230,14 @@ impl ScalarExpression {
                     Some(self.clone())
                 ))
             }
+            ScalarExpression::Parameter { index, ty } => {
+                Arc::new(ColumnCatalog::new(
+                    format!("${}", index),
+                    true,
+                    ColumnDesc::new(*ty, false, false),
+                    Some(self.clone())
+                ))
+            }
             ScalarExpression::Alias { expr, alias } => {
                 Arc::new(ColumnCatalog::new(
                     alias.to_string(),
@@ -206,7 +266,7 @@ impl ScalarExpression {
 
                 Arc::new(ColumnCatalog::new(
                     column_name,
-                    true,
+                    kind != &AggKind::Count,
                     ColumnDesc::new(ty.clone(), false, false),
                     Some(self.clone())
                 ))
@@ -251,6 +311,20 @@ impl ScalarExpression {
                     Some(self.clone())
                 ))
             },
+            ScalarExpression::Coalesce { exprs, ty } => {
+                let column_name = format!(
+                    "coalesce({})",
+                    exprs.iter()
+                        .map(|expr| expr.output_columns(tuple).name.clone())
+                        .join(", "),
+                );
+                Arc::new(ColumnCatalog::new(
+                    column_name,
+                    true,
+                    ColumnDesc::new(*ty, false, false),
+                    Some(self.clone())
+                ))
+            },
             _ => unreachable!()
         }
     }
@@ -274,6 +348,34 @@ impl From<SqlUnaryOperator> for UnaryOperator {
     }
 }
 
+impl fmt::Display for ScalarExpression {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ScalarExpression::Constant(value) => match value.as_ref() {
+                DataValue::Utf8(Some(value)) => write!(f, "'{}'", value),
+                value => write!(f, "{}", value),
+            },
+            ScalarExpression::ColumnRef(col) => write!(f, "{}", col),
+            ScalarExpression::InputRef { index, .. } => write!(f, "#{}", index),
+            ScalarExpression::Alias { expr, alias } => write!(f, "{} as {}", expr, alias),
+            ScalarExpression::TypeCast { expr, ty } => write!(f, "cast({} as {})", expr, ty),
+            ScalarExpression::IsNull { expr } => write!(f, "{} is null", expr),
+            ScalarExpression::Unary { op: UnaryOperator::Not, expr, .. } => write!(f, "not {}", expr),
+            ScalarExpression::Unary { op, expr, .. } => write!(f, "{}{}", op, expr),
+            ScalarExpression::Binary { op, left_expr, right_expr, .. } => {
+                write!(f, "({} {} {})", left_expr, op, right_expr)
+            }
+            ScalarExpression::AggCall { distinct, kind, args, .. } => {
+                let distinct = if *distinct { "distinct " } else { "" };
+
+                write!(f, "{:?}({}{})", kind, distinct, args.iter().join(", "))
+            }
+            ScalarExpression::Coalesce { exprs, .. } => write!(f, "coalesce({})", exprs.iter().join(", ")),
+            ScalarExpression::Parameter { index, .. } => write!(f, "${}", index),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BinaryOperator {
     Plus,
@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::fmt;
 use std::collections::{Bound, HashSet};
 use std::mem;
 use std::sync::Arc;
@@ -233,6 +234,29 @@ impl ConstantBinary {
     }
 }
 
+impl fmt::Display for ConstantBinary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstantBinary::Scope { min, max } => {
+                match min {
+                    Bound::Included(value) => write!(f, "[{}", value)?,
+                    Bound::Excluded(value) => write!(f, "({}", value)?,
+                    Bound::Unbounded => write!(f, "(-inf")?,
+                }
+                match max {
+                    Bound::Included(value) => write!(f, ", {}]", value),
+                    Bound::Excluded(value) => write!(f, ", {})", value),
+                    Bound::Unbounded => write!(f, ", +inf)"),
+                }
+            }
+            ConstantBinary::Eq(value) => write!(f, "= {}", value),
+            ConstantBinary::NotEq(value) => write!(f, "!= {}", value),
+            ConstantBinary::And(binaries) => write!(f, "({})", binaries.iter().join(" && ")),
+            ConstantBinary::Or(binaries) => write!(f, "({})", binaries.iter().join(" || ")),
+        }
+    }
+}
+
 #[derive(Debug)]
 enum Replace {
     Binary(ReplaceBinary),
@@ -266,6 +290,9 @@ impl ScalarExpression {
             ScalarExpression::Binary { left_expr, right_expr, .. } => {
                 left_expr.exist_column(col_id) || right_expr.exist_column(col_id)
             }
+            ScalarExpression::Coalesce { exprs, .. } => {
+                exprs.iter().any(|expr| expr.exist_column(col_id))
+            }
             _ => false
         }
     }
@@ -702,6 +729,7 @@ mod test {
                 column_datatype: LogicalType::Integer,
                 is_primary: false,
                 is_unique: false,
+                is_hidden: false,
             },
             ref_expr: None,
         });
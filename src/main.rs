@@ -1,7 +1,7 @@
 use std::error::Error;
 use std::io;
 
-use kip_sql::db::Database;
+use kip_sql::db::{Database, QueryResult};
 use kip_sql::types::tuple::create_table;
 
 pub(crate) const BANNER: &str = "
@@ -51,11 +51,20 @@ async fn server_run() -> Result<(), Box<dyn Error>> {
         }
 
         match db.run(&input).await {
-            Ok(tuples) => {
-                if tuples.is_empty() {
-                    println!("\nEmpty\n");
-                } else {
-                    println!("\n{}\n", create_table(&tuples));
+            Ok(results) => {
+                for result in results {
+                    match result {
+                        QueryResult::Rows(tuples) => {
+                            if tuples.is_empty() {
+                                println!("\nEmpty\n");
+                            } else {
+                                println!("\n{}\n", create_table(&tuples));
+                            }
+                        }
+                        QueryResult::Command { tag, affected_rows } => {
+                            println!("\n{} {}\n", tag, affected_rows);
+                        }
+                    }
                 }
             }
             Err(err) => {
@@ -26,6 +26,20 @@ impl HepOptimizer {
     }
 
     pub fn find_best(&mut self) -> Result<LogicalPlan, OptimizerError> {
+        self.apply_batches(|_, _| ())?;
+
+        Ok(self.graph.to_plan())
+    }
+
+    /// The best plan, and the plan after each batch with the name of the batch.
+    pub fn find_best_with_batches(&mut self) -> Result<(LogicalPlan, Vec<(String, LogicalPlan)>), OptimizerError> {
+        let mut plans = Vec::with_capacity(self.batches.len());
+        self.apply_batches(|batch, graph| plans.push((batch.name.clone(), graph.to_plan())))?;
+
+        Ok((self.graph.to_plan(), plans))
+    }
+
+    fn apply_batches(&mut self, mut on_batch: impl FnMut(&HepBatch, &HepGraph)) -> Result<(), OptimizerError> {
         let batches = self.batches.clone();
 
         for batch in batches {
@@ -39,9 +53,10 @@ impl HepOptimizer {
                     batch_over = true
                 }
             }
+            on_batch(&batch, &self.graph);
         }
 
-        Ok(self.graph.to_plan())
+        Ok(())
     }
 
     fn apply_batch(&mut self, HepBatch{ rules, strategy, .. }: &HepBatch) -> Result<bool, OptimizerError> {
@@ -6,6 +6,7 @@ use crate::optimizer::core::opt_expr::OptExprNode;
 use crate::optimizer::core::pattern::{Pattern, PatternChildrenPredicate};
 use crate::optimizer::core::rule::Rule;
 use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
+use crate::optimizer::heuristic::batch::HepMatchOrder;
 use crate::optimizer::OptimizerError;
 use crate::planner::operator::aggregate::AggregateOperator;
 use crate::planner::operator::Operator;
@@ -26,7 +27,12 @@ lazy_static! {
         Pattern {
             predicate: |op| matches!(op, Operator::Project(_)),
             children: PatternChildrenPredicate::Predicate(vec![Pattern {
-                predicate: |op| !matches!(op, Operator::Scan(_) | Operator::Project(_)),
+                // DML writes the whole rows of its input, e.g. `RETURNING` over DML,
+                // and the lock needs the tuple ids which are not kept by the projection
+                predicate: |op| !matches!(
+                    op,
+                    Operator::Scan(_) | Operator::Project(_) | Operator::Insert(_) | Operator::Update(_) | Operator::Delete(_) | Operator::Lock(_)
+                ),
                 children: PatternChildrenPredicate::Predicate(vec![Pattern {
                     predicate: |op| !matches!(op, Operator::Project(_)),
                     children: PatternChildrenPredicate::None,
@@ -46,15 +52,15 @@ impl Rule for PushProjectIntoScan {
 
     fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
         if let Operator::Project(project_op) = graph.operator(node_id) {
+            // the projection computing expressions must be kept, the scan only outputs columns
+            if !project_op.columns.iter().all(|expr| matches!(expr.unpack_alias(), ScalarExpression::ColumnRef(_))) {
+                return Ok(());
+            }
             let child_index = graph.children_at(node_id)[0];
             if let Operator::Scan(scan_op) = graph.operator(child_index) {
                 let mut new_scan_op = scan_op.clone();
 
-                new_scan_op.columns = project_op.columns
-                    .iter()
-                    .filter(|expr| matches!(expr.unpack_alias(),ScalarExpression::ColumnRef(_)))
-                    .cloned()
-                    .collect_vec();
+                new_scan_op.columns = project_op.columns.clone();
 
                 graph.remove_node(node_id, false);
                 graph.replace_node(
@@ -133,11 +139,16 @@ impl Rule for PushProjectThroughChild {
                         if grandson_referenced_column.is_empty() {
                             return Ok(())
                         }
-                        let grandson_table_name = grandson_referenced_column[0]
-                            .table_name
-                            .clone();
+                        let grandson_table_names = graph
+                            .nodes_iter(HepMatchOrder::TopDown, Some(grandson_id))
+                            .filter_map(|id| match graph.operator(id) {
+                                Operator::Scan(scan_op) => Some(Some(scan_op.table_name.clone())),
+                                _ => None,
+                            })
+                            .chain([grandson_referenced_column[0].table_name.clone()])
+                            .collect_vec();
                         let columns = parent_referenced_columns.iter()
-                            .filter(|col| col.table_name == grandson_table_name)
+                            .filter(|col| grandson_table_names.contains(&col.table_name))
                             .cloned()
                             .map(|col| ScalarExpression::ColumnRef(col))
                             .collect_vec();
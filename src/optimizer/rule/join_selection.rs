@@ -0,0 +1,258 @@
+use lazy_static::lazy_static;
+use crate::catalog::ColumnRef;
+use crate::expression::ScalarExpression;
+use crate::optimizer::core::opt_expr::OptExprNode;
+use crate::optimizer::core::pattern::{Pattern, PatternChildrenPredicate};
+use crate::optimizer::core::rule::Rule;
+use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
+use crate::optimizer::OptimizerError;
+use crate::planner::{is_same_column, LogicalPlan};
+use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinStrategy, JoinType};
+use crate::planner::operator::Operator;
+
+lazy_static! {
+    static ref CHOOSE_INDEX_LOOKUP_JOIN_RULE: Pattern = {
+        Pattern {
+            predicate: |op| matches!(op, Operator::Join(_)),
+            children: PatternChildrenPredicate::None,
+        }
+    };
+    static ref CHOOSE_SORT_MERGE_JOIN_RULE: Pattern = {
+        Pattern {
+            predicate: |op| matches!(op, Operator::Join(_)),
+            children: PatternChildrenPredicate::None,
+        }
+    };
+}
+
+/// Use index nested-loop join when the left input is filtered and the right input
+/// is a table scan whose join key is the primary key or has a unique index,
+/// so that only the matching right tuples are read instead of the whole table.
+pub struct ChooseIndexLookupJoin;
+
+impl Rule for ChooseIndexLookupJoin {
+    fn pattern(&self) -> &Pattern {
+        &CHOOSE_INDEX_LOOKUP_JOIN_RULE
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        if let Operator::Join(op) = graph.operator(node_id) {
+            if op.strategy != JoinStrategy::Hash || !matches!(op.join_type, JoinType::Inner | JoinType::Left) {
+                return Ok(());
+            }
+            let (on_keys, filter) = match &op.on {
+                JoinCondition::On { on, filter } if !on.is_empty() => (on, filter),
+                _ => return Ok(()),
+            };
+            let children = graph.children_at(node_id);
+
+            if !is_filtered(&graph.to_plan_with_index(children[0])) {
+                return Ok(());
+            }
+            let scan_op = match graph.operator(children[1]) {
+                Operator::Scan(scan_op) => scan_op,
+                _ => return Ok(()),
+            };
+            if scan_op.index_by.is_some()
+                || scan_op.limit != (None, None)
+                || !scan_op.columns.iter().all(|expr| matches!(expr, ScalarExpression::ColumnRef(_)))
+            {
+                return Ok(());
+            }
+
+            for (i, (left_expr, right_expr)) in on_keys.iter().enumerate() {
+                let right_col = match right_expr {
+                    ScalarExpression::ColumnRef(col) if col.table_name.as_ref() == Some(&scan_op.table_name) => col,
+                    _ => continue,
+                };
+                if &left_expr.return_type() != right_col.datatype() {
+                    continue;
+                }
+                let index_meta = if right_col.desc.is_primary {
+                    None
+                } else if let Some(meta) = scan_op.index_metas
+                    .iter()
+                    .find(|meta| meta.is_unique && meta.column_ids.len() == 1 && right_col.id == Some(meta.column_ids[0]))
+                {
+                    Some(meta.clone())
+                } else {
+                    continue;
+                };
+
+                // the lookup is driven by the first equijoin pair
+                let mut on = on_keys.clone();
+                on.swap(0, i);
+                let new_op = JoinOperator {
+                    on: JoinCondition::On { on, filter: filter.clone() },
+                    join_type: op.join_type,
+                    strategy: JoinStrategy::IndexLookup(index_meta),
+                };
+
+                graph.replace_node(
+                    node_id,
+                    OptExprNode::OperatorRef(Operator::Join(new_op))
+                );
+
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Use sort-merge join instead of hash join when both inputs
+/// are already sorted on the equijoin keys, e.g. primary key to primary key joins.
+pub struct ChooseSortMergeJoin;
+
+impl Rule for ChooseSortMergeJoin {
+    fn pattern(&self) -> &Pattern {
+        &CHOOSE_SORT_MERGE_JOIN_RULE
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        if let Operator::Join(op) = graph.operator(node_id) {
+            if op.strategy != JoinStrategy::Hash || op.join_type == JoinType::Cross {
+                return Ok(());
+            }
+            let on_keys = match &op.on {
+                JoinCondition::On { on, .. } if !on.is_empty() => on,
+                _ => return Ok(()),
+            };
+            let (mut left_keys, mut right_keys) = (Vec::new(), Vec::new());
+
+            for (left_expr, right_expr) in on_keys {
+                match (left_expr, right_expr) {
+                    (ScalarExpression::ColumnRef(left_col), ScalarExpression::ColumnRef(right_col))
+                        if left_col.datatype() == right_col.datatype() =>
+                    {
+                        left_keys.push(left_col);
+                        right_keys.push(right_col);
+                    }
+                    _ => return Ok(()),
+                }
+            }
+            let children = graph.children_at(node_id);
+            let left_ordered = graph.to_plan_with_index(children[0]).ordered_columns();
+            let right_ordered = graph.to_plan_with_index(children[1]).ordered_columns();
+
+            if is_prefix_columns(&left_keys, &left_ordered) && is_prefix_columns(&right_keys, &right_ordered) {
+                let mut new_op = op.clone();
+                new_op.strategy = JoinStrategy::SortMerge;
+
+                graph.replace_node(
+                    node_id,
+                    OptExprNode::OperatorRef(Operator::Join(new_op))
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Return true when the rows of the plan are restricted by a predicate or limit,
+/// which makes it likely to be small.
+fn is_filtered(plan: &LogicalPlan) -> bool {
+    match &plan.operator {
+        Operator::Filter(_) | Operator::Limit(_) => true,
+        Operator::Scan(op) => op.index_by.is_some() || op.limit != (None, None),
+        Operator::Project(_) | Operator::Sort(_) => is_filtered(&plan.childrens[0]),
+        _ => false,
+    }
+}
+
+/// Return true when keys are the leading columns of the ordering.
+fn is_prefix_columns(keys: &[&ColumnRef], ordered: &[ColumnRef]) -> bool {
+    keys.len() <= ordered.len()
+        && keys.iter()
+            .zip(ordered.iter())
+            .all(|(key, col)| is_same_column(key, col))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binder::test::select_sql_run;
+    use crate::db::DatabaseError;
+    use crate::optimizer::heuristic::batch::HepBatchStrategy;
+    use crate::optimizer::heuristic::optimizer::HepOptimizer;
+    use crate::optimizer::rule::RuleImpl;
+    use crate::planner::LogicalPlan;
+    use crate::planner::operator::join::JoinStrategy;
+    use crate::planner::operator::Operator;
+
+    fn join_strategy(plan: LogicalPlan) -> Result<JoinStrategy, DatabaseError> {
+        let mut best_plan = HepOptimizer::new(plan)
+            .batch(
+                "test_predicate_pushdown".to_string(),
+                HepBatchStrategy::fix_point_topdown(10),
+                vec![RuleImpl::PushPredicateThroughJoin]
+            )
+            .batch(
+                "test_join_selection".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![
+                    RuleImpl::ChooseIndexLookupJoin,
+                    RuleImpl::ChooseSortMergeJoin
+                ]
+            )
+            .find_best()?;
+
+        loop {
+            match best_plan.operator {
+                Operator::Join(op) => return Ok(op.strategy),
+                _ => best_plan = best_plan.childrens.remove(0),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_choose_index_lookup_join() -> Result<(), DatabaseError> {
+        let plan = select_sql_run("select * from t1 join t2 on c1 = c3 where c2 > 1").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::IndexLookup(None));
+
+        let plan = select_sql_run("select * from t2 left join t1 on c4 = c2 where c4 > 1").await?;
+        assert!(matches!(join_strategy(plan)?, JoinStrategy::IndexLookup(Some(meta)) if meta.name == "uk_c2"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_lookup_join_not_applicable() -> Result<(), DatabaseError> {
+        // no index on the right join key
+        let plan = select_sql_run("select * from t1 join t2 on c2 = c4 where c1 > 1").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::Hash);
+
+        // the left side is not filtered
+        let plan = select_sql_run("select * from t1 join t2 on c1 = c3").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::SortMerge);
+
+        // the right side must be fully output
+        let plan = select_sql_run("select * from t1 right join t2 on c1 = c3 where c3 > 1").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::SortMerge);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_choose_sort_merge_join() -> Result<(), DatabaseError> {
+        let plan = select_sql_run("select * from t1 join t2 on c1 = c3").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::SortMerge);
+
+        let plan = select_sql_run("select * from t1 left join t2 on c1 = c3").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::SortMerge);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_keep_hash_join_on_unordered_keys() -> Result<(), DatabaseError> {
+        let plan = select_sql_run("select * from t1 join t2 on c1 = c4").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::Hash);
+
+        let plan = select_sql_run("select * from t1 join t2 on c1 + 1 = c3").await?;
+        assert_eq!(join_strategy(plan)?, JoinStrategy::Hash);
+
+        Ok(())
+    }
+}
@@ -5,13 +5,15 @@ use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
 use crate::optimizer::OptimizerError;
 use crate::optimizer::rule::column_pruning::{PushProjectIntoScan, PushProjectThroughChild};
 use crate::optimizer::rule::combine_operators::{CollapseProject, CombineFilter};
+use crate::optimizer::rule::join_selection::{ChooseIndexLookupJoin, ChooseSortMergeJoin};
 use crate::optimizer::rule::pushdown_limit::{LimitProjectTranspose, EliminateLimits, PushLimitThroughJoin, PushLimitIntoScan};
-use crate::optimizer::rule::pushdown_predicates::PushPredicateThroughJoin;
+use crate::optimizer::rule::pushdown_predicates::{PushPredicateThroughJoin, PushPredicateThroughProject};
 use crate::optimizer::rule::pushdown_predicates::PushPredicateIntoScan;
 use crate::optimizer::rule::simplification::SimplifyFilter;
 
 mod column_pruning;
 mod combine_operators;
+mod join_selection;
 mod pushdown_limit;
 mod pushdown_predicates;
 mod simplification;
@@ -24,6 +26,9 @@ pub enum RuleImpl {
     // Combine operators
     CollapseProject,
     CombineFilter,
+    // Join selection
+    ChooseIndexLookupJoin,
+    ChooseSortMergeJoin,
     // PushDown limit
     LimitProjectTranspose,
     EliminateLimits,
@@ -31,6 +36,7 @@ pub enum RuleImpl {
     PushLimitIntoTableScan,
     // PushDown predicates
     PushPredicateThroughJoin,
+    PushPredicateThroughProject,
     // Tips: need to be used with `SimplifyFilter`
     PushPredicateIntoScan,
     // Simplification
@@ -44,11 +50,14 @@ impl Rule for RuleImpl {
             RuleImpl::PushProjectThroughChild => PushProjectThroughChild {}.pattern(),
             RuleImpl::CollapseProject => CollapseProject {}.pattern(),
             RuleImpl::CombineFilter => CombineFilter {}.pattern(),
+            RuleImpl::ChooseIndexLookupJoin => ChooseIndexLookupJoin {}.pattern(),
+            RuleImpl::ChooseSortMergeJoin => ChooseSortMergeJoin {}.pattern(),
             RuleImpl::LimitProjectTranspose => LimitProjectTranspose {}.pattern(),
             RuleImpl::EliminateLimits => EliminateLimits {}.pattern(),
             RuleImpl::PushLimitThroughJoin => PushLimitThroughJoin {}.pattern(),
             RuleImpl::PushLimitIntoTableScan => PushLimitIntoScan {}.pattern(),
             RuleImpl::PushPredicateThroughJoin => PushPredicateThroughJoin {}.pattern(),
+            RuleImpl::PushPredicateThroughProject => PushPredicateThroughProject {}.pattern(),
             RuleImpl::PushPredicateIntoScan => PushPredicateIntoScan {}.pattern(),
             RuleImpl::SimplifyFilter => SimplifyFilter {}.pattern(),
         }
@@ -60,11 +69,14 @@ impl Rule for RuleImpl {
             RuleImpl::PushProjectThroughChild => PushProjectThroughChild {}.apply(node_id, graph),
             RuleImpl::CollapseProject => CollapseProject {}.apply(node_id, graph),
             RuleImpl::CombineFilter => CombineFilter {}.apply(node_id, graph),
+            RuleImpl::ChooseIndexLookupJoin => ChooseIndexLookupJoin {}.apply(node_id, graph),
+            RuleImpl::ChooseSortMergeJoin => ChooseSortMergeJoin {}.apply(node_id, graph),
             RuleImpl::LimitProjectTranspose => LimitProjectTranspose {}.apply(node_id, graph),
             RuleImpl::EliminateLimits => EliminateLimits {}.apply(node_id, graph),
             RuleImpl::PushLimitThroughJoin => PushLimitThroughJoin {}.apply(node_id, graph),
             RuleImpl::PushLimitIntoTableScan => PushLimitIntoScan {}.apply(node_id, graph),
             RuleImpl::PushPredicateThroughJoin => PushPredicateThroughJoin {}.apply(node_id, graph),
+            RuleImpl::PushPredicateThroughProject => PushPredicateThroughProject {}.apply(node_id, graph),
             RuleImpl::SimplifyFilter => SimplifyFilter {}.apply(node_id, graph),
             RuleImpl::PushPredicateIntoScan => PushPredicateIntoScan {}.apply(node_id, graph)
         }
@@ -34,8 +34,7 @@ lazy_static! {
         }
     };
 
-    // TODO
-    static ref PUSH_PREDICATE_THROUGH_NON_JOIN: Pattern = {
+    static ref PUSH_PREDICATE_THROUGH_PROJECT: Pattern = {
         Pattern {
             predicate: |op| matches!(op, Operator::Filter(_)),
             children: PatternChildrenPredicate::Predicate(vec![Pattern {
@@ -210,6 +209,39 @@ impl Rule for PushPredicateThroughJoin {
     }
 }
 
+/// Pushes down the `Filter` below the `Project`, e.g. the predicates on a view,
+/// when the columns of the `condition` are output by the `Project` as they are.
+pub struct PushPredicateThroughProject;
+
+impl Rule for PushPredicateThroughProject {
+    fn pattern(&self) -> &Pattern {
+        &PUSH_PREDICATE_THROUGH_PROJECT
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        let child_id = graph.children_at(node_id)[0];
+
+        if let (Operator::Filter(op), Operator::Project(child_op)) = (graph.operator(node_id), graph.operator(child_id)) {
+            if op.having {
+                return Ok(());
+            }
+            let output_columns = child_op.columns
+                .iter()
+                .filter_map(|expr| match expr {
+                    ScalarExpression::ColumnRef(col) => Some(col.clone()),
+                    _ => None,
+                })
+                .collect_vec();
+
+            if is_subset_cols(&op.predicate.referenced_columns(), &output_columns) {
+                graph.swap_node(node_id, child_id);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 pub struct PushPredicateIntoScan {
 
 }
@@ -146,6 +146,7 @@ mod test {
                     column_datatype: LogicalType::Integer,
                     is_primary: true,
                     is_unique: false,
+                    is_hidden: false,
                 },
                 ref_expr: None,
             };
@@ -162,6 +163,7 @@ mod test {
                     column_datatype: LogicalType::Integer,
                     is_primary: false,
                     is_unique: true,
+                    is_hidden: false,
                 },
                 ref_expr: None,
             };
@@ -1,5 +1,8 @@
 use sqlparser::{ast::Statement, dialect::PostgreSqlDialect, parser::Parser};
+use sqlparser::ast::{Ident, ObjectName};
+use sqlparser::keywords::Keyword;
 use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::{Token, Tokenizer};
 
 /// Parse a string to a collection of statements.
 ///
@@ -17,3 +20,214 @@ pub fn parse_sql(sql: &str) -> Result<Vec<Statement>, ParserError> {
     let dialect = PostgreSqlDialect {};
     Parser::parse_sql(&dialect, sql)
 }
+
+/// A statement of KipSQL, including the ones which are not supported by sqlparser yet.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParsedStatement {
+    Sql(Statement),
+    /// `ROLLBACK [WORK | TRANSACTION] TO [SAVEPOINT] name`
+    RollbackToSavepoint(Ident),
+    /// `RELEASE [SAVEPOINT] name`
+    ReleaseSavepoint(Ident),
+    /// `SHOW {INDEX | INDEXES | KEYS} {FROM | IN} table_name`
+    ShowIndexes(ObjectName),
+    /// `REFRESH MATERIALIZED VIEW name`
+    RefreshMaterializedView(ObjectName),
+    /// `DROP MATERIALIZED VIEW [IF EXISTS] name`
+    DropMaterializedView { name: ObjectName, if_exists: bool },
+}
+
+/// Parse a string to a collection of statements, which are separated by semicolons.
+///
+/// The placeholders `?` and `:name` are numbered as `$1, $2, ...` in each statement.
+///
+/// # Example
+/// ```rust
+/// use kip_sql::parser::{parse_statements, ParsedStatement};
+/// let stmts = parse_statements("BEGIN; SAVEPOINT a; ROLLBACK TO SAVEPOINT a; COMMIT").unwrap();
+/// assert!(matches!(stmts[2], ParsedStatement::RollbackToSavepoint(_)));
+/// ```
+pub fn parse_statements(sql: &str) -> Result<Vec<ParsedStatement>, ParserError> {
+    let dialect = PostgreSqlDialect {};
+    let tokens = number_placeholders(Tokenizer::new(&dialect, sql).tokenize()?)?;
+    let mut parser = Parser::new(&dialect).with_tokens(tokens);
+    let mut stmts = Vec::new();
+    let mut expecting_statement_delimiter = false;
+
+    loop {
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+        if parser.peek_token() == Token::EOF {
+            break;
+        }
+        if expecting_statement_delimiter {
+            return parser.expected("end of statement", parser.peek_token());
+        }
+        stmts.push(parse_statement(&mut parser)?);
+        expecting_statement_delimiter = true;
+    }
+
+    Ok(stmts)
+}
+
+/// The tokens of the SQL without whitespace, comments and the trailing semicolons,
+/// which are the same for the SQL that only differs in formatting or the case of unquoted words.
+pub fn normalize_sql(sql: &str) -> Result<Vec<Token>, ParserError> {
+    let dialect = PostgreSqlDialect {};
+    let mut tokens = Tokenizer::new(&dialect, sql).tokenize()?;
+
+    tokens.retain(|token| !matches!(token, Token::Whitespace(_)));
+    for token in tokens.iter_mut() {
+        if let Token::Word(word) = token {
+            if word.quote_style.is_none() {
+                word.value = word.value.to_lowercase();
+            }
+        }
+    }
+    while tokens.last() == Some(&Token::SemiColon) {
+        let _ = tokens.pop();
+    }
+
+    Ok(tokens)
+}
+
+fn parse_statement(parser: &mut Parser) -> Result<ParsedStatement, ParserError> {
+    if parser.parse_keyword(Keyword::ROLLBACK) {
+        let _ = parser.parse_one_of_keywords(&[Keyword::WORK, Keyword::TRANSACTION]);
+
+        if parser.parse_keyword(Keyword::TO) {
+            let _ = parser.parse_keyword(Keyword::SAVEPOINT);
+
+            return Ok(ParsedStatement::RollbackToSavepoint(parser.parse_identifier()?));
+        }
+        let chain = parser.parse_commit_rollback_chain()?;
+
+        return Ok(ParsedStatement::Sql(Statement::Rollback { chain }));
+    }
+    if parser.parse_keyword(Keyword::RELEASE) {
+        let _ = parser.parse_keyword(Keyword::SAVEPOINT);
+
+        return Ok(ParsedStatement::ReleaseSavepoint(parser.parse_identifier()?));
+    }
+    if parser.parse_keyword(Keyword::SHOW) {
+        let is_indexes = matches!(
+            parser.peek_token().token,
+            Token::Word(word) if ["INDEX", "INDEXES", "KEYS"].contains(&word.value.to_uppercase().as_str())
+        );
+        if is_indexes {
+            let _ = parser.next_token();
+            let _ = parser.expect_one_of_keywords(&[Keyword::FROM, Keyword::IN])?;
+
+            return Ok(ParsedStatement::ShowIndexes(parser.parse_object_name()?));
+        }
+        parser.prev_token();
+    }
+
+    if matches!(parser.peek_token().token, Token::Word(word) if word.value.eq_ignore_ascii_case("REFRESH")) {
+        let _ = parser.next_token();
+        parser.expect_keywords(&[Keyword::MATERIALIZED, Keyword::VIEW])?;
+
+        return Ok(ParsedStatement::RefreshMaterializedView(parser.parse_object_name()?));
+    }
+    if parser.parse_keywords(&[Keyword::DROP, Keyword::MATERIALIZED, Keyword::VIEW]) {
+        let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+
+        return Ok(ParsedStatement::DropMaterializedView { name: parser.parse_object_name()?, if_exists });
+    }
+
+    parser.parse_statement().map(ParsedStatement::Sql)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PlaceholderStyle {
+    Dollar,
+    QuestionMark,
+    Named,
+}
+
+/// `?` are numbered by their order, and `:name` by the first appearance of each name,
+/// the styles can not be mixed in a statement.
+fn number_placeholders(tokens: Vec<Token>) -> Result<Vec<Token>, ParserError> {
+    let mut numbered = Vec::with_capacity(tokens.len());
+    let mut style = None;
+    let mut question_marks = 0;
+    let mut names: Vec<String> = Vec::new();
+    let mut tokens = tokens.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        let (token_style, token) = match token {
+            Token::SemiColon => {
+                style = None;
+                question_marks = 0;
+                names.clear();
+
+                numbered.push(token);
+                continue;
+            }
+            Token::Placeholder(placeholder) if placeholder == "?" => {
+                question_marks += 1;
+
+                (PlaceholderStyle::QuestionMark, Token::Placeholder(format!("${}", question_marks)))
+            }
+            Token::Placeholder(placeholder) if placeholder.starts_with('?') => {
+                (PlaceholderStyle::QuestionMark, Token::Placeholder(placeholder.replacen('?', "$", 1)))
+            }
+            Token::Placeholder(placeholder) => (PlaceholderStyle::Dollar, Token::Placeholder(placeholder)),
+            Token::Colon if matches!(tokens.peek(), Some(Token::Word(_))) => {
+                let Some(Token::Word(word)) = tokens.next() else { unreachable!() };
+                let index = match names.iter().position(|name| name == &word.value) {
+                    Some(i) => i + 1,
+                    None => {
+                        names.push(word.value);
+                        names.len()
+                    }
+                };
+
+                (PlaceholderStyle::Named, Token::Placeholder(format!("${}", index)))
+            }
+            token => {
+                numbered.push(token);
+                continue;
+            }
+        };
+        if style.get_or_insert(token_style) != &token_style {
+            return Err(ParserError::ParserError("the placeholder styles can not be mixed".to_string()));
+        }
+        numbered.push(token);
+    }
+
+    Ok(numbered)
+}
+
+#[cfg(test)]
+mod test {
+    use sqlparser::ast::{Expr, SetExpr, Statement, Value};
+    use sqlparser::parser::ParserError;
+    use crate::parser::{parse_statements, ParsedStatement};
+
+    fn placeholders(sql: &str) -> Result<Vec<String>, ParserError> {
+        let mut stmts = parse_statements(sql)?;
+        let ParsedStatement::Sql(Statement::Insert { source, .. }) = stmts.remove(0) else { unreachable!() };
+        let SetExpr::Values(values) = *source.body else { unreachable!() };
+
+        Ok(values.rows[0]
+            .iter()
+            .map(|expr| match expr {
+                Expr::Value(Value::Placeholder(placeholder)) => placeholder.clone(),
+                _ => unreachable!(),
+            })
+            .collect())
+    }
+
+    #[test]
+    fn test_number_placeholders() -> Result<(), ParserError> {
+        assert_eq!(placeholders("insert into t values ($2, $1)")?, vec!["$2", "$1"]);
+        assert_eq!(placeholders("insert into t values (?, ?, ?1)")?, vec!["$1", "$2", "$1"]);
+        assert_eq!(placeholders("insert into t values (:b, :a, :b)")?, vec!["$1", "$2", "$1"]);
+        assert!(placeholders("insert into t values (?, $1)").is_err());
+        assert!(placeholders("insert into t values (?, :a)").is_err());
+
+        Ok(())
+    }
+}
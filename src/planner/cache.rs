@@ -0,0 +1,163 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use kip_db::kernel::utils::lru_cache::LruCache;
+use parking_lot::Mutex;
+use sqlparser::tokenizer::Token;
+use crate::binder::{BindError, ValuesSlot};
+use crate::catalog::{TableCatalog, ViewCatalog};
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use crate::types::LogicalType;
+use crate::types::value::ValueRef;
+
+const PLAN_CACHE_CAPACITY: usize = 256;
+
+/// The plans of the statements run by `Database::run` and the prepared statements,
+/// by the tokens of their SQL and the types of their parameters.
+///
+/// The plan of a statement with parameters is cached before it is optimized,
+/// whose placeholders are replaced by the values of each execution, which are used by the optimizer,
+/// e.g. for the index ranges and the limit of the scans.
+/// So a hit of a parameterized statement saves only the parse and the bind, the plan is optimized every time,
+/// and every hit still compares the catalogs the plan was bound to with the ones of the transaction.
+pub(crate) struct PlanCache {
+    plans: Mutex<LruCache<PlanCacheKey, Arc<CachedPlan>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// The tokens of the SQL normalized by `normalize_sql` and the types of its parameters.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PlanCacheKey {
+    tokens: Vec<Token>,
+    parameter_types: Vec<LogicalType>,
+}
+
+impl PlanCacheKey {
+    pub(crate) fn new(tokens: Vec<Token>, parameter_types: Vec<LogicalType>) -> Self {
+        PlanCacheKey { tokens, parameter_types }
+    }
+
+    pub(crate) fn parameter_types(&self) -> &[LogicalType] {
+        &self.parameter_types
+    }
+
+    /// The plan is bound with placeholders, which is optimized after the values are given.
+    pub(crate) fn is_parameterized(&self) -> bool {
+        !self.parameter_types.is_empty()
+    }
+}
+
+pub(crate) struct CachedPlan {
+    plan: LogicalPlan,
+    /// The slots of the `Values` rows given by the parameters
+    values_slots: Vec<ValuesSlot>,
+    /// The catalogs of the tables referenced by the plan when it was bound
+    tables: Vec<TableCatalog>,
+    /// The views expanded into the plan
+    views: Vec<ViewCatalog>,
+}
+
+impl CachedPlan {
+    /// The plan is stale once the catalog of any referenced table or view changes,
+    /// e.g. the table is dropped, recreated or its indexes change.
+    fn is_valid<T: Transaction>(&self, transaction: &T) -> bool {
+        self.tables
+            .iter()
+            .all(|table| transaction.table(&table.name) == Some(table))
+            && self.views
+                .iter()
+                .all(|view| transaction.view(&view.name).as_ref() == Some(view))
+    }
+
+    /// The plan with the values of the parameters if there are any.
+    pub(crate) fn plan(&self, values: &[ValueRef]) -> Result<LogicalPlan, BindError> {
+        let mut plan = self.plan.clone();
+
+        if !values.is_empty() {
+            plan.bind_parameters(values, &self.values_slots)?;
+        }
+
+        Ok(plan)
+    }
+}
+
+/// The counters of the plan cache, a miss is counted when a cacheable statement is planned.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PlanCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Default for PlanCache {
+    fn default() -> Self {
+        PlanCache {
+            plans: Mutex::new(LruCache::new(PLAN_CACHE_CAPACITY).unwrap()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+}
+
+impl PlanCache {
+    pub(crate) fn get(&self, key: &PlanCacheKey) -> Option<Arc<CachedPlan>> {
+        self.plans.lock().get(key).cloned()
+    }
+
+    /// Whether the catalogs the plan was bound to are the ones seen by the transaction,
+    /// otherwise the stale plan is evicted.
+    ///
+    /// Tips: the catalogs are checked with the transaction which runs the plan,
+    /// so the DDL of other sessions and the rolled back one are taken into account.
+    pub(crate) fn check<T: Transaction>(
+        &self,
+        key: &PlanCacheKey,
+        cached: &Arc<CachedPlan>,
+        transaction: &T
+    ) -> bool {
+        if !cached.is_valid(transaction) {
+            let mut plans = self.plans.lock();
+
+            if plans.get(key).is_some_and(|plan| Arc::ptr_eq(plan, cached)) {
+                let _ = plans.remove(key);
+            }
+            return false;
+        }
+        let _ = self.hits.fetch_add(1, Ordering::Relaxed);
+
+        true
+    }
+
+    pub(crate) fn put<T: Transaction>(
+        &self,
+        key: PlanCacheKey,
+        plan: &LogicalPlan,
+        values_slots: Vec<ValuesSlot>,
+        views: Vec<ViewCatalog>,
+        transaction: &T
+    ) {
+        let _ = self.misses.fetch_add(1, Ordering::Relaxed);
+        let tables = plan.referenced_tables()
+            .iter()
+            .map(|table_name| transaction.table(table_name).cloned())
+            .collect::<Option<Vec<_>>>();
+
+        if let Some(tables) = tables {
+            let cached = CachedPlan { plan: plan.clone(), values_slots, tables, views };
+
+            let _ = self.plans.lock().put(key, Arc::new(cached));
+        }
+    }
+
+    /// Evict all the plans, e.g. the unqualified table names may refer to other tables.
+    pub(crate) fn clear(&self) {
+        *self.plans.lock() = LruCache::new(PLAN_CACHE_CAPACITY).unwrap();
+    }
+
+    pub(crate) fn stats(&self) -> PlanCacheStats {
+        PlanCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
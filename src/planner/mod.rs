@@ -1,6 +1,16 @@
+pub mod cache;
 pub mod operator;
 
+use std::sync::Arc;
+use crate::binder::ValuesSlot;
+use crate::catalog::{ColumnRef, TableName};
+use crate::expression::ScalarExpression;
+use crate::expression::simplify::ConstantBinary;
+use crate::planner::operator::insert::{ConflictAction, InsertOperator, OnConflict};
+use crate::planner::operator::join::{JoinCondition, JoinStrategy, JoinType};
 use crate::planner::operator::Operator;
+use crate::types::errors::TypeError;
+use crate::types::value::{DataValue, ValueRef};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct LogicalPlan {
@@ -13,4 +23,180 @@ impl LogicalPlan {
         self.childrens
             .get(index)
     }
+
+    /// The operators of the plan tree from the root, one per line,
+    /// and each child is indented under its parent.
+    pub fn explain(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        self.explain_with_indent(0, &mut lines);
+
+        lines
+    }
+
+    fn explain_with_indent(&self, indent: usize, lines: &mut Vec<String>) {
+        lines.push(format!("{}{}", "  ".repeat(indent), self.operator));
+
+        for child in &self.childrens {
+            child.explain_with_indent(indent + 1, lines);
+        }
+    }
+
+    /// The most rows this plan outputs, as predicted by the pushed down limits
+    /// and the lookups of unique indexes, `None` if it is unknown.
+    pub fn predicted_rows(&self) -> Option<usize> {
+        let child_rows = || self.childrens.get(0).and_then(LogicalPlan::predicted_rows);
+
+        match &self.operator {
+            Operator::Scan(op) => {
+                let lookups = op.index_by
+                    .as_ref()
+                    .filter(|(index_meta, binaries)| {
+                        index_meta.is_unique && binaries.iter().all(|binary| matches!(binary, ConstantBinary::Eq(_)))
+                    })
+                    .map(|(_, binaries)| binaries.len());
+
+                [op.limit.1, lookups].into_iter().flatten().min()
+            }
+            Operator::Limit(op) => [Some(op.limit), child_rows()].into_iter().flatten().min(),
+            Operator::Sort(op) => [op.limit, child_rows()].into_iter().flatten().min(),
+            Operator::Filter(_) | Operator::Project(_) | Operator::Lock(_) => child_rows(),
+            Operator::Aggregate(op) if op.groupby_exprs.is_empty() => Some(1),
+            Operator::Values(op) => Some(op.rows.len()),
+            _ => None,
+        }
+    }
+
+    /// The tables read or written by this plan, without duplicates.
+    pub fn referenced_tables(&self) -> Vec<TableName> {
+        let mut tables = Vec::new();
+        self.collect_tables(&mut tables);
+
+        tables
+    }
+
+    fn collect_tables(&self, tables: &mut Vec<TableName>) {
+        let table_name = match &self.operator {
+            Operator::Scan(op) => Some(&op.table_name),
+            Operator::Lock(op) => Some(&op.table_name),
+            Operator::Insert(op) => Some(&op.table_name),
+            Operator::Update(op) => Some(&op.table_name),
+            Operator::Delete(op) => Some(&op.table_name),
+            Operator::CreateTable(op) => Some(&op.table_name),
+            Operator::DropTable(op) => Some(&op.table_name),
+            Operator::Truncate(op) => Some(&op.table_name),
+            Operator::ShowColumns(op) => Some(&op.table_name),
+            Operator::ShowIndexes(op) => Some(&op.table_name),
+            Operator::ShowCreateTable(op) => Some(&op.table_name),
+            _ => None,
+        };
+        if let Some(table_name) = table_name {
+            if !tables.contains(table_name) {
+                tables.push(table_name.clone());
+            }
+        }
+        for child in &self.childrens {
+            child.collect_tables(tables);
+        }
+    }
+
+    /// Replace the parameters of the plan bound by `Parameters::Template` with their values,
+    /// the values of the slots are cast to the columns of `Values` as the binder does.
+    pub(crate) fn bind_parameters(
+        &mut self,
+        values: &[ValueRef],
+        values_slots: &[ValuesSlot]
+    ) -> Result<(), TypeError> {
+        let exprs: Vec<&mut ScalarExpression> = match &mut self.operator {
+            Operator::Aggregate(op) => op.agg_calls.iter_mut().chain(op.groupby_exprs.iter_mut()).collect(),
+            Operator::Filter(op) => vec![&mut op.predicate],
+            Operator::Join(op) => match &mut op.on {
+                JoinCondition::On { on, filter } => on.iter_mut()
+                    .flat_map(|(left_expr, right_expr)| [left_expr, right_expr])
+                    .chain(filter.as_mut())
+                    .collect(),
+                JoinCondition::None => vec![],
+            },
+            Operator::Project(op) => op.columns.iter_mut().collect(),
+            Operator::Scan(op) => op.columns.iter_mut().collect(),
+            Operator::Sort(op) => op.sort_fields.iter_mut().map(|field| &mut field.expr).collect(),
+            Operator::Update(op) => op.value_exprs.iter_mut().map(|(_, expr)| expr).collect(),
+            Operator::Insert(InsertOperator {
+                on_conflict: Some(OnConflict { action: ConflictAction::DoUpdate { value_exprs, filter }, .. }),
+                ..
+            }) => value_exprs.iter_mut()
+                .map(|(_, expr)| expr)
+                .chain(filter.as_mut())
+                .collect(),
+            Operator::Values(op) => {
+                for slot in values_slots {
+                    let value = &values[slot.index - 1];
+                    let ty = op.columns[slot.column].datatype();
+                    value.check_len(ty)?;
+
+                    op.rows[slot.row][slot.column] = Arc::new(DataValue::clone(value).cast(ty)?);
+                }
+                vec![]
+            }
+            _ => vec![],
+        };
+        for expr in exprs {
+            expr.bind_parameters(values)?;
+        }
+        for child in &mut self.childrens {
+            child.bind_parameters(values, values_slots)?;
+        }
+
+        Ok(())
+    }
+
+    /// The columns on which the output of this plan is known to be sorted in ascending order.
+    ///
+    /// Rows whose values are NULL may be placed anywhere,
+    /// only the order of non-NULL values is guaranteed.
+    pub fn ordered_columns(&self) -> Vec<ColumnRef> {
+        match &self.operator {
+            Operator::Scan(op) => op.ordered_columns(),
+            Operator::Filter(_) | Operator::Limit(_) => {
+                self.childrens[0].ordered_columns()
+            }
+            Operator::Project(op) => {
+                self.childrens[0]
+                    .ordered_columns()
+                    .into_iter()
+                    .take_while(|col| {
+                        op.columns.iter().any(|expr| {
+                            matches!(expr, ScalarExpression::ColumnRef(c) if is_same_column(c, col))
+                        })
+                    })
+                    .collect()
+            }
+            Operator::Sort(op) => {
+                op.sort_fields
+                    .iter()
+                    .map_while(|field| match &field.expr {
+                        ScalarExpression::ColumnRef(col) if field.asc => Some(col.clone()),
+                        _ => None,
+                    })
+                    .collect()
+            }
+            Operator::Join(op) => {
+                // Only the merge of sorted inputs and the lookups driven by the left side
+                // keep the order of the left side.
+                if matches!(op.strategy, JoinStrategy::SortMerge | JoinStrategy::IndexLookup(_))
+                    && matches!(op.join_type, JoinType::Inner | JoinType::Left)
+                {
+                    self.childrens[0].ordered_columns()
+                } else {
+                    vec![]
+                }
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Compare columns by the table and column id they come from,
+/// ignoring attributes such as nullable that joins may change.
+pub(crate) fn is_same_column(left: &ColumnRef, right: &ColumnRef) -> bool {
+    left.table_name == right.table_name && left.id == right.id
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+use itertools::Itertools;
 use crate::{
     expression::ScalarExpression,
     planner::operator::Operator,
@@ -25,3 +27,14 @@ impl AggregateOperator {
         }
     }
 }
+
+impl fmt::Display for AggregateOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Aggregate [{}]", self.agg_calls.iter().join(", "))?;
+        if !self.groupby_exprs.is_empty() {
+            write!(f, ", Group By: [{}]", self.groupby_exprs.iter().join(", "))?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,13 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CreateSchemaOperator {
+    pub schema_name: String,
+    pub if_not_exists: bool,
+}
+
+impl fmt::Display for CreateSchemaOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Create Schema {}", self.schema_name)
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt;
+use itertools::Itertools;
 use crate::catalog::{ColumnCatalog, TableName};
 
 #[derive(Debug, PartialEq, Clone)]
@@ -7,3 +9,14 @@ pub struct CreateTableOperator {
     /// List of columns of the table
     pub columns: Vec<ColumnCatalog>,
 }
+
+impl fmt::Display for CreateTableOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let columns = self.columns
+            .iter()
+            .map(|column| format!("{} {}", column.name, column.datatype()))
+            .join(", ");
+
+        write!(f, "Create Table {} [{}]", self.table_name, columns)
+    }
+}
@@ -0,0 +1,16 @@
+use std::fmt;
+use crate::catalog::ViewCatalog;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CreateViewOperator {
+    pub view: ViewCatalog,
+    pub or_replace: bool,
+}
+
+impl fmt::Display for CreateViewOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = if self.view.materialized { "Materialized View" } else { "View" };
+
+        write!(f, "Create {} {} [{}]", kind, self.view.name, self.view.columns.join(", "))
+    }
+}
@@ -1,6 +1,13 @@
+use std::fmt;
 use crate::catalog::TableName;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct DeleteOperator {
     pub table_name: TableName,
-}
\ No newline at end of file
+}
+
+impl fmt::Display for DeleteOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Delete {}", self.table_name)
+    }
+}
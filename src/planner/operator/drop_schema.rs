@@ -0,0 +1,21 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DropSchemaOperator {
+    pub schema_name: String,
+    pub if_exists: bool,
+    /// Drop the tables and views in the schema, otherwise the schema must be empty
+    pub cascade: bool,
+}
+
+impl fmt::Display for DropSchemaOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Drop Schema {}", self.schema_name)?;
+
+        if self.cascade {
+            write!(f, ", Cascade")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,14 @@
+use std::fmt;
 use crate::catalog::TableName;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct DropTableOperator {
     /// Table name to insert to
     pub table_name: TableName,
-}
\ No newline at end of file
+}
+
+impl fmt::Display for DropTableOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Drop Table {}", self.table_name)
+    }
+}
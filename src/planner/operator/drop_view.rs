@@ -0,0 +1,18 @@
+use std::fmt;
+use crate::catalog::TableName;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct DropViewOperator {
+    pub view_name: TableName,
+    pub if_exists: bool,
+    /// `DROP MATERIALIZED VIEW`, which drops the table of the view too
+    pub materialized: bool,
+}
+
+impl fmt::Display for DropViewOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = if self.materialized { "Materialized View" } else { "View" };
+
+        write!(f, "Drop {} {}", kind, self.view_name)
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt;
 use std::vec;
 
 use crate::expression::ScalarExpression;
@@ -23,3 +24,14 @@ impl FilterOperator {
         }
     }
 }
+
+impl fmt::Display for FilterOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Filter {}", self.predicate)?;
+        if self.having {
+            write!(f, ", Having")?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,7 +1,64 @@
-use crate::catalog::TableName;
+use std::fmt;
+use std::sync::Arc;
+use itertools::Itertools;
+use crate::catalog::{ColumnCatalog, ColumnRef, TableName};
+use crate::expression::ScalarExpression;
+
+/// The pseudo-table of the row proposed for insertion in `ON CONFLICT DO UPDATE`
+pub const EXCLUDED_TABLE_NAME: &str = "excluded";
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct InsertOperator {
     pub table_name: TableName,
+    /// The columns to insert the input values by position
+    pub columns: Vec<ColumnRef>,
     pub is_overwrite: bool,
-}
\ No newline at end of file
+    pub on_conflict: Option<OnConflict>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct OnConflict {
+    /// The primary key or unique columns checked for conflicts, all of them if empty
+    pub target_columns: Vec<ColumnRef>,
+    pub action: ConflictAction,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ConflictAction {
+    DoNothing,
+    /// Update the existing row, the new values are evaluated on the existing row and `EXCLUDED`
+    DoUpdate {
+        value_exprs: Vec<(ColumnRef, ScalarExpression)>,
+        filter: Option<ScalarExpression>,
+    },
+}
+
+/// The column of the target table in the `EXCLUDED` pseudo-table.
+pub(crate) fn excluded_column(column: &ColumnRef) -> ColumnRef {
+    let mut column = ColumnCatalog::clone(column);
+    column.table_name = Some(Arc::new(EXCLUDED_TABLE_NAME.to_string()));
+
+    Arc::new(column)
+}
+
+impl fmt::Display for InsertOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let overwrite = if self.is_overwrite { " Overwrite" } else { "" };
+        write!(f, "Insert{} {} [{}]", overwrite, self.table_name, self.columns.iter().join(", "))?;
+        match &self.on_conflict {
+            Some(OnConflict { action: ConflictAction::DoNothing, .. }) => write!(f, ", On Conflict: Do Nothing"),
+            Some(OnConflict { action: ConflictAction::DoUpdate { value_exprs, filter }, .. }) => {
+                let value_exprs = value_exprs.iter()
+                    .map(|(column, expr)| format!("{} = {}", column, expr))
+                    .join(", ");
+                write!(f, ", On Conflict: Do Update [{}]", value_exprs)?;
+                if let Some(filter) = filter {
+                    write!(f, ", Filter: {}", filter)?;
+                }
+
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
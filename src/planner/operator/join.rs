@@ -1,5 +1,8 @@
+use std::fmt;
+use itertools::Itertools;
 use crate::expression::ScalarExpression;
 use crate::planner::LogicalPlan;
+use crate::types::index::IndexMetaRef;
 
 use super::Operator;
 
@@ -22,10 +25,22 @@ pub enum JoinCondition {
     None,
 }
 
+/// The physical algorithm used to execute the join.
+#[derive(Debug, PartialEq, Clone)]
+pub enum JoinStrategy {
+    Hash,
+    /// Both inputs are already sorted on the equijoin keys.
+    SortMerge,
+    /// For each left row, look up the right table scan by the first equijoin pair,
+    /// through the unique index if any, otherwise through the primary key.
+    IndexLookup(Option<IndexMetaRef>),
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct JoinOperator {
     pub on: JoinCondition,
     pub join_type: JoinType,
+    pub strategy: JoinStrategy,
 }
 
 impl JoinOperator {
@@ -36,8 +51,31 @@ impl JoinOperator {
         join_type: JoinType,
     ) -> LogicalPlan {
         LogicalPlan {
-            operator: Operator::Join(JoinOperator { on, join_type }),
+            operator: Operator::Join(JoinOperator { on, join_type, strategy: JoinStrategy::Hash }),
             childrens: vec![left, right],
         }
     }
 }
+
+impl fmt::Display for JoinOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} Join", self.join_type)?;
+        if let JoinCondition::On { on, filter } = &self.on {
+            if !on.is_empty() {
+                let on = on.iter()
+                    .map(|(left, right)| format!("{} = {}", left, right))
+                    .join(", ");
+                write!(f, ", On: [{}]", on)?;
+            }
+            if let Some(filter) = filter {
+                write!(f, ", Filter: {}", filter)?;
+            }
+        }
+        match &self.strategy {
+            JoinStrategy::Hash => write!(f, ", Strategy: Hash"),
+            JoinStrategy::SortMerge => write!(f, ", Strategy: SortMerge"),
+            JoinStrategy::IndexLookup(Some(index_meta)) => write!(f, ", Strategy: IndexLookup {}", index_meta.name),
+            JoinStrategy::IndexLookup(None) => write!(f, ", Strategy: IndexLookup primary key"),
+        }
+    }
+}
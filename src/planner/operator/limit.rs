@@ -1,3 +1,4 @@
+use std::fmt;
 use crate::planner::LogicalPlan;
 
 use super::Operator;
@@ -16,3 +17,14 @@ pub fn new(offset: usize, limit: usize, children: LogicalPlan) -> LogicalPlan {
         }
     }
 }
+
+impl fmt::Display for LimitOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Limit {}", self.limit)?;
+        if self.offset > 0 {
+            write!(f, ", Offset: {}", self.offset)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+use std::fmt;
+use crate::catalog::TableName;
+use crate::planner::LogicalPlan;
+use crate::storage::lock::{LockMode, LockWait};
+
+use super::Operator;
+
+/// `FOR UPDATE` / `FOR SHARE`, locks the tuples of the table passed through it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct LockOperator {
+    pub table_name: TableName,
+    pub lock_mode: LockMode,
+    pub lock_wait: LockWait,
+}
+
+impl LockOperator {
+    pub fn new(
+        table_name: TableName,
+        lock_mode: LockMode,
+        lock_wait: LockWait,
+        children: LogicalPlan
+    ) -> LogicalPlan {
+        LogicalPlan {
+            operator: Operator::Lock(LockOperator { table_name, lock_mode, lock_wait }),
+            childrens: vec![children],
+        }
+    }
+}
+
+impl fmt::Display for LockOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Lock {}, Mode: {:?}, Wait: {:?}", self.table_name, self.lock_mode, self.lock_wait)
+    }
+}
@@ -1,8 +1,11 @@
 pub mod aggregate;
+pub mod create_schema;
 pub mod create_table;
+pub mod create_view;
 pub mod filter;
 pub mod join;
 pub mod limit;
+pub mod lock;
 pub mod project;
 pub mod scan;
 pub mod sort;
@@ -10,19 +13,29 @@ pub mod insert;
 pub mod values;
 pub mod update;
 pub mod delete;
+pub mod drop_schema;
 pub mod drop_table;
+pub mod drop_view;
 pub mod truncate;
+pub mod refresh_materialized_view;
 pub mod show;
 
+use std::fmt;
 use itertools::Itertools;
 use crate::catalog::ColumnRef;
 use crate::expression::ScalarExpression;
+use crate::planner::operator::create_schema::CreateSchemaOperator;
 use crate::planner::operator::create_table::CreateTableOperator;
+use crate::planner::operator::create_view::CreateViewOperator;
 use crate::planner::operator::delete::DeleteOperator;
+use crate::planner::operator::drop_schema::DropSchemaOperator;
 use crate::planner::operator::drop_table::DropTableOperator;
+use crate::planner::operator::drop_view::DropViewOperator;
 use crate::planner::operator::insert::InsertOperator;
 use crate::planner::operator::join::JoinCondition;
-use crate::planner::operator::show::ShowTablesOperator;
+use crate::planner::operator::lock::LockOperator;
+use crate::planner::operator::refresh_materialized_view::RefreshMaterializedViewOperator;
+use crate::planner::operator::show::{ShowColumnsOperator, ShowCreateTableOperator, ShowIndexesOperator, ShowTablesOperator};
 use crate::planner::operator::truncate::TruncateOperator;
 use crate::planner::operator::update::UpdateOperator;
 use crate::planner::operator::values::ValuesOperator;
@@ -43,6 +56,7 @@ pub enum Operator {
     Scan(ScanOperator),
     Sort(SortOperator),
     Limit(LimitOperator),
+    Lock(LockOperator),
     Values(ValuesOperator),
     // DML
     Insert(InsertOperator),
@@ -52,8 +66,16 @@ pub enum Operator {
     CreateTable(CreateTableOperator),
     DropTable(DropTableOperator),
     Truncate(TruncateOperator),
+    CreateSchema(CreateSchemaOperator),
+    DropSchema(DropSchemaOperator),
+    CreateView(CreateViewOperator),
+    DropView(DropViewOperator),
+    RefreshMaterializedView(RefreshMaterializedViewOperator),
     // Show
     Show(ShowTablesOperator),
+    ShowColumns(ShowColumnsOperator),
+    ShowIndexes(ShowIndexesOperator),
+    ShowCreateTable(ShowCreateTableOperator),
 }
 
 impl Operator {
@@ -147,3 +169,35 @@ impl Operator {
         }
     }
 }
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operator::Dummy => write!(f, "Dummy"),
+            Operator::Aggregate(op) => write!(f, "{}", op),
+            Operator::Filter(op) => write!(f, "{}", op),
+            Operator::Join(op) => write!(f, "{}", op),
+            Operator::Project(op) => write!(f, "{}", op),
+            Operator::Scan(op) => write!(f, "{}", op),
+            Operator::Sort(op) => write!(f, "{}", op),
+            Operator::Limit(op) => write!(f, "{}", op),
+            Operator::Lock(op) => write!(f, "{}", op),
+            Operator::Values(op) => write!(f, "{}", op),
+            Operator::Insert(op) => write!(f, "{}", op),
+            Operator::Update(op) => write!(f, "{}", op),
+            Operator::Delete(op) => write!(f, "{}", op),
+            Operator::CreateTable(op) => write!(f, "{}", op),
+            Operator::DropTable(op) => write!(f, "{}", op),
+            Operator::Truncate(op) => write!(f, "{}", op),
+            Operator::CreateSchema(op) => write!(f, "{}", op),
+            Operator::DropSchema(op) => write!(f, "{}", op),
+            Operator::CreateView(op) => write!(f, "{}", op),
+            Operator::DropView(op) => write!(f, "{}", op),
+            Operator::RefreshMaterializedView(op) => write!(f, "{}", op),
+            Operator::Show(op) => write!(f, "{}", op),
+            Operator::ShowColumns(op) => write!(f, "{}", op),
+            Operator::ShowIndexes(op) => write!(f, "{}", op),
+            Operator::ShowCreateTable(op) => write!(f, "{}", op),
+        }
+    }
+}
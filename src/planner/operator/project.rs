@@ -1,6 +1,14 @@
+use std::fmt;
+use itertools::Itertools;
 use crate::expression::ScalarExpression;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ProjectOperator {
     pub columns: Vec<ScalarExpression>,
 }
+
+impl fmt::Display for ProjectOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Projection [{}]", self.columns.iter().join(", "))
+    }
+}
@@ -0,0 +1,14 @@
+use std::fmt;
+use crate::catalog::TableName;
+
+/// Replace the rows of the materialized view with the ones inserted by its child.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RefreshMaterializedViewOperator {
+    pub view_name: TableName,
+}
+
+impl fmt::Display for RefreshMaterializedViewOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Refresh Materialized View {}", self.view_name)
+    }
+}
@@ -1,5 +1,6 @@
+use std::fmt;
 use itertools::Itertools;
-use crate::catalog::{TableCatalog, TableName};
+use crate::catalog::{ColumnRef, TableCatalog, TableName};
 use crate::expression::ScalarExpression;
 use crate::expression::simplify::ConstantBinary;
 use crate::planner::LogicalPlan;
@@ -43,4 +44,44 @@ impl ScanOperator {
             childrens: vec![],
         }
     }
+
+    /// Tuples are stored ordered by primary key, and a single range read on a unique index
+    /// comes back ordered by the index column.
+    pub fn ordered_columns(&self) -> Vec<ColumnRef> {
+        let find_column = |predicate: &dyn Fn(&ColumnRef) -> bool| {
+            self.columns
+                .iter()
+                .find_map(|expr| match expr {
+                    ScalarExpression::ColumnRef(col) if predicate(col) => Some(col.clone()),
+                    _ => None,
+                })
+        };
+
+        match &self.index_by {
+            None => find_column(&|col| col.desc.is_primary),
+            Some((meta, binaries)) if meta.is_unique && binaries.len() == 1 => {
+                find_column(&|col| col.id == Some(meta.column_ids[0]))
+            }
+            Some(_) => None,
+        }
+        .into_iter()
+        .collect_vec()
+    }
+}
+
+impl fmt::Display for ScanOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Scan {} -> [{}]", self.table_name, self.columns.iter().join(", "))?;
+        if let (_, Some(limit)) = self.limit {
+            write!(f, ", Limit: {}", limit)?;
+        }
+        if let (Some(offset), _) = self.limit {
+            write!(f, ", Offset: {}", offset)?;
+        }
+        if let Some((index_meta, binaries)) = &self.index_by {
+            write!(f, ", Index: {} [{}]", index_meta.name, binaries.iter().join(", "))?;
+        }
+
+        Ok(())
+    }
 }
@@ -1,2 +1,47 @@
+use std::fmt;
+use crate::catalog::TableName;
+
 #[derive(Debug, PartialEq, Clone)]
-pub struct ShowTablesOperator {}
\ No newline at end of file
+pub struct ShowTablesOperator {}
+
+/// `DESCRIBE t` or `SHOW COLUMNS FROM t`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShowColumnsOperator {
+    pub table_name: TableName,
+}
+
+/// `SHOW INDEXES FROM t`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShowIndexesOperator {
+    pub table_name: TableName,
+}
+
+/// `SHOW CREATE TABLE t`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShowCreateTableOperator {
+    pub table_name: TableName,
+}
+
+impl fmt::Display for ShowTablesOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Show Tables")
+    }
+}
+
+impl fmt::Display for ShowColumnsOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Show Columns {}", self.table_name)
+    }
+}
+
+impl fmt::Display for ShowIndexesOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Show Indexes {}", self.table_name)
+    }
+}
+
+impl fmt::Display for ShowCreateTableOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Show Create Table {}", self.table_name)
+    }
+}
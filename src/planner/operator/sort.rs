@@ -1,3 +1,5 @@
+use std::fmt;
+use itertools::Itertools;
 use crate::expression::ScalarExpression;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -23,3 +25,23 @@ pub struct SortOperator {
     /// Support push down limit to sort plan.
     pub limit: Option<usize>,
 }
+
+impl fmt::Display for SortOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sort_fields = self.sort_fields
+            .iter()
+            .map(|field| {
+                let order = if field.asc { "asc" } else { "desc" };
+                let nulls = if field.nulls_first { "nulls first" } else { "nulls last" };
+
+                format!("{} {} {}", field.expr, order, nulls)
+            })
+            .join(", ");
+        write!(f, "Sort By [{}]", sort_fields)?;
+        if let Some(limit) = self.limit {
+            write!(f, ", Limit: {}", limit)?;
+        }
+
+        Ok(())
+    }
+}
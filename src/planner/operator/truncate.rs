@@ -1,7 +1,14 @@
+use std::fmt;
 use crate::catalog::TableName;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct TruncateOperator {
     /// Table name to insert to
     pub table_name: TableName,
-}
\ No newline at end of file
+}
+
+impl fmt::Display for TruncateOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Truncate {}", self.table_name)
+    }
+}
@@ -1,6 +1,22 @@
-use crate::catalog::TableName;
+use std::fmt;
+use itertools::Itertools;
+use crate::catalog::{ColumnRef, TableName};
+use crate::expression::ScalarExpression;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct UpdateOperator {
     pub table_name: TableName,
-}
\ No newline at end of file
+    /// The new value of each column, evaluated on the row to be updated
+    pub value_exprs: Vec<(ColumnRef, ScalarExpression)>,
+}
+
+impl fmt::Display for UpdateOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let value_exprs = self.value_exprs
+            .iter()
+            .map(|(column, expr)| format!("{} = {}", column, expr))
+            .join(", ");
+
+        write!(f, "Update {} Set [{}]", self.table_name, value_exprs)
+    }
+}
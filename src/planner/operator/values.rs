@@ -1,3 +1,5 @@
+use std::fmt;
+use itertools::Itertools;
 use crate::catalog::ColumnRef;
 use crate::types::value::ValueRef;
 
@@ -5,4 +7,10 @@ use crate::types::value::ValueRef;
 pub struct ValuesOperator {
     pub rows: Vec<Vec<ValueRef>>,
     pub columns: Vec<ColumnRef>
-}
\ No newline at end of file
+}
+
+impl fmt::Display for ValuesOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Values [{}], Rows: {}", self.columns.iter().join(", "), self.rows.len())
+    }
+}
@@ -1,29 +1,52 @@
-use std::collections::{Bound, VecDeque};
-use std::collections::hash_map::RandomState;
-use std::mem;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
+use std::iter::Peekable;
+use std::{mem, vec};
 use std::ops::SubAssign;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use async_trait::async_trait;
+use bytes::Bytes;
 use kip_db::kernel::lsm::mvcc::TransactionIter;
 use kip_db::kernel::lsm::{mvcc, storage};
 use kip_db::kernel::lsm::iterator::Iter as KipDBIter;
 use kip_db::kernel::lsm::storage::Config;
-use kip_db::kernel::Storage as KipDBStorage;
-use kip_db::kernel::utils::lru_cache::ShardingLruCache;
-use crate::catalog::{ColumnCatalog, TableCatalog, TableName};
+use kip_db::KernelError;
+use parking_lot::Mutex;
+use crate::catalog::{ColumnCatalog, TableCatalog, TableName, ViewCatalog};
 use crate::expression::simplify::ConstantBinary;
 use crate::storage::{Bounds, Projections, Storage, StorageError, Transaction, Iter, tuple_projection, IndexIter};
+use crate::storage::system::SystemTable;
+use crate::storage::lock::{LockManager, LockMode, LockOwner, LockWait};
 use crate::storage::table_codec::TableCodec;
 use crate::types::errors::TypeError;
 use crate::types::index::{Index, IndexMeta, IndexMetaRef};
 use crate::types::tuple::{Tuple, TupleId};
 use crate::types::value::ValueRef;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IsolationLevel {
+    /// The commit fails if a key written by the transaction has been written
+    /// by another transaction committed after it started.
+    #[default]
+    SnapshotIsolation,
+    /// Also fails if a key read by the transaction has been written in the meantime.
+    ///
+    /// Tips: the keys missing from a range scan are not tracked, so phantoms are not detected
+    Serializable,
+}
+
 #[derive(Clone)]
 pub struct KipStorage {
-    cache: Arc<ShardingLruCache<String, TableCatalog>>,
-    pub inner: Arc<storage::KipStorage>
+    pub inner: Arc<storage::KipStorage>,
+    isolation_level: IsolationLevel,
+    commit_log: Arc<Mutex<CommitLog>>,
+    /// Serializes the snapshots and the commits,
+    /// so a transaction sees all the commits before its start timestamp.
+    commit_lock: Arc<tokio::sync::Mutex<()>>,
+    lock_manager: Arc<LockManager>,
+    lock_timeout: Duration,
 }
 
 impl KipStorage {
@@ -32,96 +55,24 @@ impl KipStorage {
         let storage = storage::KipStorage::open_with_config(config).await?;
 
         Ok(KipStorage {
-            cache: Arc::new(ShardingLruCache::new(
-                32,
-                16,
-                RandomState::default(),
-            )?),
             inner: Arc::new(storage),
+            isolation_level: IsolationLevel::default(),
+            commit_log: Arc::new(Mutex::new(CommitLog::default())),
+            commit_lock: Arc::new(tokio::sync::Mutex::new(())),
+            lock_manager: Arc::new(LockManager::default()),
+            lock_timeout: Duration::from_secs(10),
         })
     }
 
-    fn column_collect(name: &String, tx: &mvcc::Transaction) -> Result<(Vec<ColumnCatalog>, Option<TableName>), StorageError> {
-        let (column_min, column_max) = TableCodec::columns_bound(name);
-        let mut column_iter = tx.iter(Bound::Included(&column_min), Bound::Included(&column_max))?;
-
-        let mut columns = vec![];
-        let mut name_option = None;
-
-        while let Some((_, value_option)) = column_iter.try_next().ok().flatten() {
-            if let Some(value) = value_option {
-                let (table_name, column) = TableCodec::decode_column(&value)?;
-
-                if name != table_name.as_str() {
-                    return Ok((vec![], None));
-                }
-                let _ = name_option.insert(table_name);
-
-                columns.push(column);
-            }
-        }
-
-        Ok((columns, name_option))
+    pub fn with_isolation_level(mut self, isolation_level: IsolationLevel) -> Self {
+        self.isolation_level = isolation_level;
+        self
     }
 
-    fn index_meta_collect(name: &String, tx: &mvcc::Transaction) -> Option<Vec<IndexMetaRef>> {
-        let (index_min, index_max) = TableCodec::index_meta_bound(name);
-        let mut index_metas = vec![];
-        let mut index_iter = tx.iter(Bound::Included(&index_min), Bound::Included(&index_max)).ok()?;
-
-        while let Some((_, value_option)) = index_iter.try_next().ok().flatten() {
-            if let Some(value) = value_option {
-                if let Some(index_meta) = TableCodec::decode_index_meta(&value).ok() {
-                    index_metas.push(Arc::new(index_meta));
-                }
-            }
-        }
-
-        Some(index_metas)
-    }
-
-    fn _drop_data(table: &mut KipTransaction, min: &[u8], max: &[u8]) -> Result<(), StorageError> {
-        let mut iter = table.tx.iter(Bound::Included(&min), Bound::Included(&max))?;
-        let mut data_keys = vec![];
-
-        while let Some((key, value_option)) = iter.try_next()? {
-            if value_option.is_some() {
-                data_keys.push(key);
-            }
-        }
-        drop(iter);
-
-        for key in data_keys {
-            table.tx.remove(&key)?
-        }
-
-        Ok(())
-    }
-
-    fn create_index_meta_for_table(
-        tx: &mut mvcc::Transaction,
-        table: &mut TableCatalog
-    ) -> Result<(), StorageError> {
-        let table_name = table.name.clone();
-
-        for col in table.all_columns()
-            .into_iter()
-            .filter(|col| col.desc.is_unique)
-        {
-            if let Some(col_id) = col.id {
-                let meta = IndexMeta {
-                    id: 0,
-                    column_ids: vec![col_id],
-                    name: format!("uk_{}", col.name),
-                    is_unique: true,
-                };
-                let meta_ref = table.add_index_meta(meta);
-                let (key, value) = TableCodec::encode_index_meta(&table_name, meta_ref)?;
-
-                tx.set(key, value);
-            }
-        }
-        Ok(())
+    /// The longest time to wait for a row lock, 10 seconds by default.
+    pub fn with_lock_timeout(mut self, lock_timeout: Duration) -> Self {
+        self.lock_timeout = lock_timeout;
+        self
     }
 }
 
@@ -129,143 +80,214 @@ impl KipStorage {
 impl Storage for KipStorage {
     type TransactionType = KipTransaction;
 
-    async fn create_table(&self, table_name: TableName, columns: Vec<ColumnCatalog>) -> Result<TableName, StorageError> {
-        let mut tx = self.inner.new_transaction().await;
-        let mut table_catalog = TableCatalog::new(table_name.clone(), columns)?;
-
-        Self::create_index_meta_for_table(&mut tx, &mut table_catalog)?;
-
-        for (_, column) in &table_catalog.columns {
-            let (key, value) = TableCodec::encode_column(column)?;
-            tx.set(key, value);
-        }
+    async fn transaction(&self) -> Result<Self::TransactionType, StorageError> {
+        let _guard = self.commit_lock.lock().await;
+        let tx = self.inner.new_transaction().await;
+        let start_ts = self.commit_log.lock().begin();
+
+        Ok(KipTransaction {
+            tx,
+            writes: Mutex::new(WriteBuffer::default()),
+            cache: Mutex::new(TableCache::default()),
+            read_keys: (self.isolation_level == IsolationLevel::Serializable)
+                .then(|| Mutex::new(HashSet::new())),
+            ticket: CommitTicket {
+                start_ts,
+                commit_log: Arc::clone(&self.commit_log),
+                commit_lock: Arc::clone(&self.commit_lock),
+            },
+            lock_owner: self.lock_manager.owner(),
+            lock_timeout: self.lock_timeout,
+        })
+    }
+}
 
-        let (k, v)= TableCodec::encode_root_table(&table_name)?;
-        self.inner.set(k, v).await?;
+/// The write sets of the recent commits, which are checked by the commits
+/// of the transactions started before them.
+#[derive(Default)]
+struct CommitLog {
+    last_commit_ts: u64,
+    commits: VecDeque<(u64, HashSet<Bytes>)>,
+    /// The start timestamps of the running transactions with their counts
+    running: BTreeMap<u64, usize>,
+}
 
-        tx.commit().await?;
-        self.cache.put(table_name.to_string(), table_catalog);
+impl CommitLog {
+    fn begin(&mut self) -> u64 {
+        let start_ts = self.last_commit_ts;
+        *self.running.entry(start_ts).or_default() += 1;
 
-        Ok(table_name)
+        start_ts
     }
 
-    async fn drop_table(&self, name: &String) -> Result<(), StorageError> {
-        self.drop_data(name).await?;
+    fn end(&mut self, start_ts: u64) {
+        if let Some(count) = self.running.get_mut(&start_ts) {
+            *count -= 1;
 
-        let (min, max) = TableCodec::columns_bound(name);
-        let mut tx = self.inner.new_transaction().await;
-        let mut iter = tx.iter(Bound::Included(&min), Bound::Included(&max))?;
-        let mut col_keys = vec![];
-
-        while let Some((key, value_option))  = iter.try_next()? {
-            if value_option.is_some() {
-                col_keys.push(key);
+            if *count == 0 {
+                let _ = self.running.remove(&start_ts);
             }
         }
-        drop(iter);
-
-        for col_key in col_keys {
-            tx.remove(&col_key)?
+        // the commits seen by all the running transactions are no longer checked
+        let oldest_ts = self.running
+            .keys()
+            .next()
+            .copied()
+            .unwrap_or(self.last_commit_ts);
+
+        while matches!(self.commits.front(), Some((commit_ts, _)) if *commit_ts <= oldest_ts) {
+            let _ = self.commits.pop_front();
         }
-        tx.remove(&TableCodec::encode_root_table_key(name))?;
-        tx.commit().await?;
-
-        let _ = self.cache.remove(name);
-
-        Ok(())
     }
 
-    async fn drop_data(&self, name: &String) -> Result<(), StorageError> {
-        if let Some(mut transaction) = self.transaction(name).await {
+    fn is_conflict(
+        &self,
+        start_ts: u64,
+        write_keys: &HashSet<Bytes>,
+        read_keys: Option<&HashSet<Bytes>>
+    ) -> bool {
+        self.commits
+            .iter()
+            .filter(|(commit_ts, _)| *commit_ts > start_ts)
+            .any(|(_, keys)| {
+                !keys.is_disjoint(write_keys)
+                    || read_keys.map_or(false, |read_keys| !keys.is_disjoint(read_keys))
+            })
+    }
 
-            let (tuple_min, tuple_max) = transaction.table_codec.tuple_bound();
-            Self::_drop_data(&mut transaction, &tuple_min, &tuple_max)?;
+    fn is_written_since(&self, start_ts: u64, key: &Bytes) -> bool {
+        self.commits
+            .iter()
+            .any(|(commit_ts, keys)| *commit_ts > start_ts && keys.contains(key))
+    }
 
-            let (index_min, index_max) = transaction.table_codec.all_index_bound();
-            Self::_drop_data(&mut transaction, &index_min, &index_max)?;
+    fn commit(&mut self, write_keys: HashSet<Bytes>) {
+        self.last_commit_ts += 1;
+        self.commits.push_back((self.last_commit_ts, write_keys));
+    }
+}
 
-            transaction.tx.commit().await?;
-        }
+/// The registration of a running transaction in the `CommitLog`, removed on drop.
+struct CommitTicket {
+    start_ts: u64,
+    commit_log: Arc<Mutex<CommitLog>>,
+    commit_lock: Arc<tokio::sync::Mutex<()>>,
+}
 
-        Ok(())
+impl Drop for CommitTicket {
+    fn drop(&mut self) {
+        self.commit_log.lock().end(self.start_ts);
     }
+}
 
-    async fn transaction(&self, name: &String) -> Option<Self::TransactionType> {
-        let table_codec = self.table(name)
-            .await
-            .map(|catalog| TableCodec { table: catalog.clone() })?;
-        let tx = self.inner.new_transaction().await;
+struct Savepoint {
+    name: String,
+    /// The length of the undo log when the savepoint is created
+    undo_len: usize,
+}
 
-        Some(KipTransaction { table_codec, tx, })
-    }
+/// The writes of the transaction, which are applied to its `mvcc::Transaction` on commit,
+/// as the executors of a statement may write while the others are scanning.
+#[derive(Default)]
+struct WriteBuffer {
+    /// `None` for the removed keys
+    buf: BTreeMap<Bytes, Option<Bytes>>,
+    savepoints: Vec<Savepoint>,
+    /// The keys written after the first savepoint or the statement mark with their previous entries in `buf`
+    undo_log: Vec<(Bytes, Option<Option<Bytes>>)>,
+    /// The length of the undo log before the running statement of the transaction block
+    statement: Option<usize>,
+}
 
-    async fn table(&self, name: &String) -> Option<&TableCatalog> {
-        let mut option = self.cache.get(name);
+impl WriteBuffer {
+    fn savepoint_position(&self, name: &str) -> Result<usize, StorageError> {
+        self.savepoints
+            .iter()
+            .rposition(|savepoint| savepoint.name == name)
+            .ok_or(StorageError::TransactionError("the savepoint does not exist"))
+    }
 
-        if option.is_none() {
-            let tx = self.inner.new_transaction().await;
-            // TODO: unify the data into a `Meta` prefix and use one iteration to collect all data
-            let (columns, name_option) = Self::column_collect(name, &tx).ok()?;
-            let indexes = Self::index_meta_collect(name, &tx)?;
+    fn rollback_undo_log(&mut self, undo_len: usize) {
+        let undo_log = self.undo_log.split_off(undo_len);
 
-            if let Some(catalog) = name_option
-                .and_then(|table_name| TableCatalog::new_with_indexes(table_name, columns, indexes).ok())
-            {
-                option = self.cache.get_or_insert(name.to_string(), |_| Ok(catalog)).ok();
-            }
+        // Tips: the keys only written after the savepoint are removed from the buffer, so they do not conflict on commit
+        for (key, value) in undo_log.into_iter().rev() {
+            let _ = match value {
+                Some(value) => self.buf.insert(key, value),
+                None => self.buf.remove(&key),
+            };
         }
-
-        option
     }
 
-    async fn show_tables(&self) -> Result<Vec<String>, StorageError> {
-        let mut tables = vec![];
-        let (min, max) = TableCodec::root_table_bound();
-
-        let tx = self.inner.new_transaction().await;
-        let mut iter = tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+    fn is_undo_logged(&self) -> bool {
+        !self.savepoints.is_empty() || self.statement.is_some()
+    }
+}
 
-        while let Some((_, value_option))  = iter.try_next().ok().flatten() {
-            if let Some(value) = value_option {
-                let table_name = TableCodec::decode_root_table(&value)?;
+/// The codecs of the tables used in the transaction,
+/// so the catalog changes are only visible to the transaction until commit.
+///
+/// Tips: the replaced codecs are kept until the transaction ends, as the executors may still refer to them
+#[derive(Default)]
+struct TableCache {
+    codecs: HashMap<String, Arc<TableCodec>>,
+    replaced: Vec<Arc<TableCodec>>,
+}
 
-                tables.push(table_name);
-            }
-        }
+impl TableCache {
+    fn insert(&mut self, table_name: String, table_codec: Arc<TableCodec>) {
+        self.replaced.extend(self.codecs.insert(table_name, table_codec));
+    }
 
-        Ok(tables)
+    fn remove(&mut self, table_name: &String) {
+        self.replaced.extend(self.codecs.remove(table_name));
     }
 }
 
 pub struct KipTransaction {
-    table_codec: TableCodec,
-    tx: mvcc::Transaction
+    tx: mvcc::Transaction,
+    writes: Mutex<WriteBuffer>,
+    cache: Mutex<TableCache>,
+    /// Only tracked with `IsolationLevel::Serializable`
+    read_keys: Option<Mutex<HashSet<Bytes>>>,
+    ticket: CommitTicket,
+    lock_owner: LockOwner,
+    lock_timeout: Duration,
 }
 
 #[async_trait]
 impl Transaction for KipTransaction {
     type IterType<'a> = KipIter<'a>;
 
-    fn read(&self, bounds: Bounds, projections: Projections) -> Result<Self::IterType<'_>, StorageError> {
-        let (min, max) = self.table_codec.tuple_bound();
-        let iter = self.tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+    fn read(
+        &self,
+        table_name: &String,
+        bounds: Bounds,
+        projections: Projections
+    ) -> Result<Self::IterType<'_>, StorageError> {
+        let table_codec = self.table_codec(table_name)?;
+        let (min, max) = table_codec.tuple_bound();
+        let iter = self.iter(Bound::Included(&min), Bound::Included(&max))?;
 
         Ok(KipIter {
             offset: bounds.0.unwrap_or(0),
             limit: bounds.1,
             projections,
-            table_codec: &self.table_codec,
+            table_codec,
             iter,
+            read_keys: self.read_keys.as_ref(),
         })
     }
 
     fn read_by_index(
         &self,
+        table_name: &String,
         (offset_option, mut limit_option): Bounds,
         projections: Projections,
         index_meta: IndexMetaRef,
         binaries: Vec<ConstantBinary>
     ) -> Result<IndexIter<'_>, StorageError> {
+        let table_codec = self.table_codec(table_name)?;
         let mut tuple_ids = Vec::new();
         let mut offset = offset_option.unwrap_or(0);
 
@@ -276,9 +298,11 @@ impl Transaction for KipTransaction {
 
             match binary {
                 ConstantBinary::Scope { min, max } => {
-                    let mut iter = self.scope_to_iter(&index_meta, min, max)?;
+                    let mut iter = self.scope_to_iter(table_codec, &index_meta, min, max)?;
+
+                    while let Some((key, value_option)) = iter.try_next()? {
+                        record_read(self.read_keys.as_ref(), &key);
 
-                    while let Some((_, value_option)) = iter.try_next()? {
                         if let Some(value) = value_option {
                             for id in TableCodec::decode_index(&value)? {
                                 if Self::offset_move(&mut offset) { continue; }
@@ -297,9 +321,10 @@ impl Transaction for KipTransaction {
                 ConstantBinary::Eq(val) => {
                     if Self::offset_move(&mut offset) { continue; }
 
-                    let key = self.val_to_key(&index_meta, val)?;
+                    let key = Self::val_to_key(table_codec, &index_meta, val)?;
+                    record_read(self.read_keys.as_ref(), &key);
 
-                    if let Some(bytes) = self.tx.get(&key)? {
+                    if let Some(bytes) = self.get(&key)? {
                         tuple_ids.append(&mut TableCodec::decode_index(&bytes)?)
                     }
 
@@ -311,16 +336,46 @@ impl Transaction for KipTransaction {
 
         Ok(IndexIter {
             projections,
-            table_codec: &self.table_codec,
+            table_codec,
             tuple_ids: VecDeque::from(tuple_ids),
-            tx: &self.tx,
+            transaction: self,
+            read_keys: self.read_keys.as_ref(),
         })
     }
 
-    fn add_index(&mut self, index: Index, tuple_ids: Vec<TupleId>, is_unique: bool) -> Result<(), StorageError> {
-        let (key, value) = self.table_codec.encode_index(&index, &tuple_ids)?;
+    fn read_by_tuple_ids(
+        &self,
+        table_name: &String,
+        projections: Projections,
+        tuple_ids: Vec<TupleId>
+    ) -> Result<Vec<Tuple>, StorageError> {
+        let mut iter = IndexIter {
+            projections,
+            table_codec: self.table_codec(table_name)?,
+            tuple_ids: VecDeque::from(tuple_ids),
+            transaction: self,
+            read_keys: self.read_keys.as_ref(),
+        };
+        let mut tuples = Vec::new();
+
+        while let Some(tuple) = iter.next_tuple()? {
+            tuples.push(tuple);
+        }
+
+        Ok(tuples)
+    }
+
+    fn add_index(
+        &self,
+        table_name: &String,
+        index: Index,
+        tuple_ids: Vec<TupleId>,
+        is_unique: bool
+    ) -> Result<(), StorageError> {
+        let (key, value) = self.table_codec(table_name)?
+            .encode_index(&index, &tuple_ids)?;
 
-        if let Some(bytes) = self.tx.get(&key)? {
+        if let Some(bytes) = self.get(&key)? {
             if is_unique {
                 let old_tuple_ids = TableCodec::decode_index(&bytes)?;
 
@@ -334,64 +389,471 @@ impl Transaction for KipTransaction {
             }
         }
 
-        self.tx.set(key, value);
+        self.set(key, value)?;
 
         Ok(())
     }
 
-    fn del_index(&mut self, index: &Index) -> Result<(), StorageError> {
-        let key = self.table_codec.encode_index_key(&index)?;
+    fn del_index(&self, table_name: &String, index: &Index) -> Result<(), StorageError> {
+        let key = self.table_codec(table_name)?
+            .encode_index_key(&index)?;
 
-        self.tx.remove(&key)?;
+        self.remove(&key)?;
 
         Ok(())
     }
 
-    fn append(&mut self, tuple: Tuple, is_overwrite: bool) -> Result<(), StorageError> {
-        let (key, value) = self.table_codec.encode_tuple(&tuple)?;
+    fn append(&self, table_name: &String, tuple: Tuple, is_overwrite: bool) -> Result<(), StorageError> {
+        let (key, value) = self.table_codec(table_name)?
+            .encode_tuple(&tuple)?;
 
-        if !is_overwrite && self.tx.get(&key)?.is_some() {
+        if !is_overwrite && self.get(&key)?.is_some() {
             return Err(StorageError::DuplicatePrimaryKey);
         }
-        self.tx.set(key, value);
+        self.set(key, value)?;
+
+        Ok(())
+    }
+
+    fn delete(&self, table_name: &String, tuple_id: TupleId) -> Result<(), StorageError> {
+        let key = self.table_codec(table_name)?
+            .encode_tuple_key(&tuple_id)?;
+
+        self.remove(&key)?;
+
+        Ok(())
+    }
+
+    fn create_table(&self, table_name: TableName, columns: Vec<ColumnCatalog>) -> Result<TableName, StorageError> {
+        let mut table_catalog = TableCatalog::new(table_name.clone(), columns)?;
+
+        self.create_index_meta_for_table(&mut table_catalog)?;
+
+        for (_, column) in &table_catalog.columns {
+            let (key, value) = TableCodec::encode_column(column)?;
+            self.set(key, value)?;
+        }
+
+        let (k, v)= TableCodec::encode_root_table(&table_name)?;
+        self.set(k, v)?;
+
+        self.cache.lock().insert(table_name.to_string(), Arc::new(TableCodec { table: table_catalog }));
+
+        Ok(table_name)
+    }
+
+    fn drop_table(&self, table_name: &String) -> Result<(), StorageError> {
+        self.drop_data(table_name)?;
+
+        let (column_min, column_max) = TableCodec::columns_bound(table_name);
+        self._drop_data(&column_min, &column_max)?;
+
+        let (index_meta_min, index_meta_max) = TableCodec::index_meta_bound(table_name);
+        self._drop_data(&index_meta_min, &index_meta_max)?;
+
+        self.remove(&TableCodec::encode_root_table_key(table_name))?;
+
+        self.cache.lock().remove(table_name);
+
+        Ok(())
+    }
+
+    fn drop_data(&self, table_name: &String) -> Result<(), StorageError> {
+        let table_codec = self.table_codec(table_name)?;
+        let (tuple_min, tuple_max) = table_codec.tuple_bound();
+        let (index_min, index_max) = table_codec.all_index_bound();
+
+        self._drop_data(&tuple_min, &tuple_max)?;
+        self._drop_data(&index_min, &index_max)?;
+
+        Ok(())
+    }
+
+    fn table(&self, table_name: &String) -> Option<&TableCatalog> {
+        self.table_codec(table_name)
+            .ok()
+            .map(|table_codec| &table_codec.table)
+            .or_else(|| SystemTable::from_name(table_name).map(|system_table| system_table.catalog()))
+    }
+
+    fn show_tables(&self) -> Result<Vec<String>, StorageError> {
+        let mut tables = vec![];
+        let (min, max) = TableCodec::root_table_bound();
+
+        let mut iter = self.iter(Bound::Included(&min), Bound::Included(&max))?;
+
+        while let Some((_, value_option)) = iter.try_next().ok().flatten() {
+            if let Some(value) = value_option {
+                let table_name = TableCodec::decode_root_table(&value)?;
+
+                tables.push(table_name);
+            }
+        }
+
+        Ok(tables)
+    }
+
+    fn create_schema(&self, schema_name: &str) -> Result<(), StorageError> {
+        let (key, value) = TableCodec::encode_schema(schema_name);
+
+        self.set(key, value)
+    }
+
+    fn drop_schema(&self, schema_name: &str) -> Result<(), StorageError> {
+        self.remove(&TableCodec::encode_schema_key(schema_name))
+    }
+
+    fn show_schemas(&self) -> Result<Vec<String>, StorageError> {
+        let mut schemas = vec![];
+        let (min, max) = TableCodec::schema_bound();
+        let mut iter = self.iter(Bound::Included(&min), Bound::Included(&max))?;
+
+        while let Some((_, value_option)) = iter.try_next()? {
+            if let Some(value) = value_option {
+                schemas.push(TableCodec::decode_schema(&value)?);
+            }
+        }
+
+        Ok(schemas)
+    }
+
+    fn create_view(&self, view: ViewCatalog) -> Result<(), StorageError> {
+        let (key, value) = TableCodec::encode_view(&view)?;
+
+        self.set(key, value)
+    }
+
+    fn drop_view(&self, view_name: &String) -> Result<(), StorageError> {
+        self.remove(&TableCodec::encode_view_key(view_name))
+    }
+
+    fn view(&self, view_name: &String) -> Option<ViewCatalog> {
+        self.get(&TableCodec::encode_view_key(view_name))
+            .ok()
+            .flatten()
+            .and_then(|bytes| TableCodec::decode_view(&bytes).ok())
+    }
+
+    fn show_views(&self) -> Result<Vec<ViewCatalog>, StorageError> {
+        let mut views = vec![];
+        let (min, max) = TableCodec::view_bound();
+        let mut iter = self.iter(Bound::Included(&min), Bound::Included(&max))?;
+
+        while let Some((_, value_option)) = iter.try_next()? {
+            if let Some(value) = value_option {
+                views.push(TableCodec::decode_view(&value)?);
+            }
+        }
+
+        Ok(views)
+    }
+
+    fn savepoint(&mut self, name: String) -> Result<(), StorageError> {
+        let writes = self.writes.get_mut();
+        let undo_len = writes.undo_log.len();
+
+        writes.savepoints.push(Savepoint { name, undo_len });
 
         Ok(())
     }
 
-    fn delete(&mut self, tuple_id: TupleId) -> Result<(), StorageError> {
-        let key = self.table_codec.encode_tuple_key(&tuple_id)?;
-        self.tx.remove(&key)?;
+    fn rollback_to_savepoint(&mut self, name: &str) -> Result<(), StorageError> {
+        let writes = self.writes.get_mut();
+        let i = writes.savepoint_position(name)?;
+        let undo_len = writes.savepoints[i].undo_len;
+
+        writes.savepoints.truncate(i + 1);
+        writes.rollback_undo_log(undo_len);
+        // the catalog may be changed after the savepoint
+        *self.cache.get_mut() = TableCache::default();
 
         Ok(())
     }
 
+    fn release_savepoint(&mut self, name: &str) -> Result<(), StorageError> {
+        let writes = self.writes.get_mut();
+        let i = writes.savepoint_position(name)?;
+
+        writes.savepoints.truncate(i);
+        if !writes.is_undo_logged() {
+            writes.undo_log.clear();
+        }
+
+        Ok(())
+    }
+
+    fn mark_statement(&mut self) -> Result<(), StorageError> {
+        let writes = self.writes.get_mut();
+
+        writes.statement = Some(writes.undo_log.len());
+
+        Ok(())
+    }
+
+    fn rollback_statement(&mut self) -> Result<(), StorageError> {
+        let writes = self.writes.get_mut();
+        let undo_len = writes.statement
+            .take()
+            .ok_or(StorageError::TransactionError("the statement is not marked"))?;
+
+        writes.savepoints.retain(|savepoint| savepoint.undo_len <= undo_len);
+        writes.rollback_undo_log(undo_len);
+        if !writes.is_undo_logged() {
+            writes.undo_log.clear();
+        }
+        // the failed statement may have changed the catalog
+        *self.cache.get_mut() = TableCache::default();
+
+        Ok(())
+    }
+
+    fn release_statement(&mut self) -> Result<(), StorageError> {
+        let writes = self.writes.get_mut();
+
+        writes.statement = None;
+        if !writes.is_undo_logged() {
+            writes.undo_log.clear();
+        }
+
+        Ok(())
+    }
+
+    async fn lock_tuple(
+        &self,
+        table_name: &String,
+        tuple_id: &TupleId,
+        lock_mode: LockMode,
+        lock_wait: LockWait
+    ) -> Result<bool, StorageError> {
+        let key = Bytes::from(self.table_codec(table_name)?.encode_tuple_key(tuple_id)?);
+
+        if !self.lock_owner.lock(key.clone(), lock_mode, lock_wait, self.lock_timeout).await? {
+            return Ok(false);
+        }
+        // Tips: the tuple may have been changed by the lock holder after the snapshot
+        if self.ticket.commit_log.lock().is_written_since(self.ticket.start_ts, &key) {
+            return Err(StorageError::TransactionConflict);
+        }
+
+        Ok(true)
+    }
+
     async fn commit(self) -> Result<(), StorageError> {
-        self.tx.commit().await?;
+        let KipTransaction { mut tx, writes, read_keys, ticket, .. } = self;
+        let WriteBuffer { buf, .. } = writes.into_inner();
+
+        if buf.is_empty() {
+            return Ok(());
+        }
+        let _guard = ticket.commit_lock.lock().await;
+        let read_keys = read_keys.map(Mutex::into_inner);
+        let write_keys = buf.keys().cloned().collect();
+
+        if ticket.commit_log.lock().is_conflict(ticket.start_ts, &write_keys, read_keys.as_ref()) {
+            return Err(StorageError::TransactionConflict);
+        }
+        for (key, value) in buf {
+            match value {
+                Some(value) => tx.set(key, value),
+                // the key may be written and removed by the transaction, which is not in the snapshot
+                None => if tx.get(&key)?.is_some() {
+                    tx.remove(&key)?;
+                }
+            }
+        }
+        tx.commit().await?;
+        ticket.commit_log.lock().commit(write_keys);
 
         Ok(())
     }
 }
 
 impl KipTransaction {
-    fn val_to_key(&self, index_meta: &IndexMetaRef, val: ValueRef) -> Result<Vec<u8>, TypeError> {
+    /// The value of the key written by the transaction, or in its snapshot.
+    pub(crate) fn get(&self, key: &[u8]) -> Result<Option<Bytes>, StorageError> {
+        if let Some(value) = self.writes.lock().buf.get(key) {
+            return Ok(value.clone());
+        }
+
+        Ok(self.tx.get(key)?)
+    }
+
+    /// The keys in the range with the writes of the transaction,
+    /// which are copied so the later writes are not seen by the iterator.
+    fn iter(&self, min: Bound<&[u8]>, max: Bound<&[u8]>) -> Result<BufferedIter<'_>, StorageError> {
+        let is_empty = match (min, max) {
+            (Bound::Included(min_key) | Bound::Excluded(min_key), Bound::Included(max_key) | Bound::Excluded(max_key)) => {
+                min_key > max_key || (min_key == max_key && !matches!((min, max), (Bound::Included(_), Bound::Included(_))))
+            }
+            _ => false,
+        };
+        let writes = if is_empty {
+            vec![]
+        } else {
+            self.writes.lock()
+                .buf
+                .range::<[u8], _>((min, max))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        };
+
+        Ok(BufferedIter {
+            iter: self.tx.iter(min, max)?,
+            next: None,
+            writes: writes.into_iter().peekable(),
+        })
+    }
+
+    fn set(&self, key: Bytes, value: Bytes) -> Result<(), StorageError> {
+        self.write(key, Some(value));
+
+        Ok(())
+    }
+
+    fn remove(&self, key: &[u8]) -> Result<(), StorageError> {
+        if self.get(key)?.is_none() {
+            return Err(KernelError::KeyNotFound.into());
+        }
+        self.write(Bytes::copy_from_slice(key), None);
+
+        Ok(())
+    }
+
+    fn write(&self, key: Bytes, value: Option<Bytes>) {
+        let mut writes = self.writes.lock();
+        let old_value = writes.buf.insert(key.clone(), value);
+
+        if writes.is_undo_logged() {
+            writes.undo_log.push((key, old_value));
+        }
+    }
+
+    /// The codec of the table, the catalog is loaded on the first use in the transaction.
+    fn table_codec(&self, table_name: &String) -> Result<&TableCodec, StorageError> {
+        let mut cache = self.cache.lock();
+        let table_codec = match cache.codecs.get(table_name) {
+            Some(table_codec) => Arc::clone(table_codec),
+            None => {
+                // TODO: unify the data into a `Meta` prefix and use one iteration to collect all data
+                let (columns, name_option) = self.column_collect(table_name)?;
+                let indexes = self.index_meta_collect(table_name)?;
+                let table_name_option = name_option.ok_or_else(|| StorageError::TableNotFound(table_name.to_string()))?;
+                let table_codec = Arc::new(TableCodec {
+                    table: TableCatalog::new_with_indexes(table_name_option, columns, indexes)?
+                });
+
+                cache.insert(table_name.to_string(), Arc::clone(&table_codec));
+                table_codec
+            }
+        };
+
+        // Safety: the codecs in the cache are only dropped with the transaction or by `&mut self`, see `TableCache`
+        Ok(unsafe { &*Arc::as_ptr(&table_codec) })
+    }
+
+    fn column_collect(&self, name: &String) -> Result<(Vec<ColumnCatalog>, Option<TableName>), StorageError> {
+        let (column_min, column_max) = TableCodec::columns_bound(name);
+        let mut column_iter = self.iter(Bound::Included(&column_min), Bound::Included(&column_max))?;
+
+        let mut columns = vec![];
+        let mut name_option = None;
+
+        while let Some((_, value_option)) = column_iter.try_next().ok().flatten() {
+            if let Some(value) = value_option {
+                let (table_name, column) = TableCodec::decode_column(&value)?;
+
+                if name != table_name.as_str() {
+                    return Ok((vec![], None));
+                }
+                let _ = name_option.insert(table_name);
+
+                columns.push(column);
+            }
+        }
+
+        Ok((columns, name_option))
+    }
+
+    fn index_meta_collect(&self, name: &String) -> Result<Vec<IndexMetaRef>, StorageError> {
+        let (index_min, index_max) = TableCodec::index_meta_bound(name);
+        let mut index_metas = vec![];
+        let mut index_iter = self.iter(Bound::Included(&index_min), Bound::Included(&index_max))?;
+
+        while let Some((_, value_option)) = index_iter.try_next().ok().flatten() {
+            if let Some(value) = value_option {
+                if let Some(index_meta) = TableCodec::decode_index_meta(&value).ok() {
+                    index_metas.push(Arc::new(index_meta));
+                }
+            }
+        }
+
+        Ok(index_metas)
+    }
+
+    fn _drop_data(&self, min: &[u8], max: &[u8]) -> Result<(), StorageError> {
+        let mut iter = self.iter(Bound::Included(&min), Bound::Included(&max))?;
+        let mut data_keys = vec![];
+
+        while let Some((key, value_option)) = iter.try_next()? {
+            if value_option.is_some() {
+                data_keys.push(key);
+            }
+        }
+        drop(iter);
+
+        for key in data_keys {
+            self.write(key, None);
+        }
+
+        Ok(())
+    }
+
+    fn create_index_meta_for_table(
+        &self,
+        table: &mut TableCatalog
+    ) -> Result<(), StorageError> {
+        let table_name = table.name.clone();
+
+        for col in table.all_columns()
+            .into_iter()
+            .filter(|col| col.desc.is_unique)
+        {
+            if let Some(col_id) = col.id {
+                let meta = IndexMeta {
+                    id: 0,
+                    column_ids: vec![col_id],
+                    name: format!("uk_{}", col.name),
+                    is_unique: true,
+                };
+                let meta_ref = table.add_index_meta(meta);
+                let (key, value) = TableCodec::encode_index_meta(&table_name, meta_ref)?;
+
+                self.set(key, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn val_to_key(table_codec: &TableCodec, index_meta: &IndexMetaRef, val: ValueRef) -> Result<Vec<u8>, TypeError> {
         let index = Index::new(index_meta.id, vec![val]);
 
-        self.table_codec.encode_index_key(&index)
+        table_codec.encode_index_key(&index)
     }
 
     fn scope_to_iter(
         &self,
+        table_codec: &TableCodec,
         index_meta: &IndexMetaRef,
         min: Bound<ValueRef>,
         max: Bound<ValueRef>
-    ) -> Result<TransactionIter, StorageError> {
+    ) -> Result<BufferedIter<'_>, StorageError> {
         let bound_encode = |bound: Bound<ValueRef>| -> Result<_, StorageError> {
             match bound {
                 Bound::Included(val) => {
-                    Ok(Bound::Included(self.val_to_key(&index_meta, val)?))
+                    Ok(Bound::Included(Self::val_to_key(table_codec, &index_meta, val)?))
                 },
                 Bound::Excluded(val) => {
-                    Ok(Bound::Excluded(self.val_to_key(&index_meta, val)?))
+                    Ok(Bound::Excluded(Self::val_to_key(table_codec, &index_meta, val)?))
                 }
                 Bound::Unbounded => Ok(Bound::Unbounded)
             }
@@ -401,7 +863,7 @@ impl KipTransaction {
                 let _ = mem::replace(value, Bound::Included(bound));
             }
         };
-        let (bound_min, bound_max) = self.table_codec.index_bound(&index_meta.id);
+        let (bound_min, bound_max) = table_codec.index_bound(&index_meta.id);
 
         let mut encode_min = bound_encode(min)?;
         check_bound(&mut encode_min, bound_min);
@@ -409,10 +871,10 @@ impl KipTransaction {
         let mut encode_max = bound_encode(max)?;
         check_bound(&mut encode_max, bound_max);
 
-        Ok(self.tx.iter(
+        self.iter(
             encode_min.as_ref().map(Vec::as_slice),
             encode_max.as_ref().map(Vec::as_slice),
-        )?)
+        )
     }
 
     fn offset_move(offset: &mut usize) -> bool {
@@ -436,12 +898,43 @@ impl KipTransaction {
     }
 }
 
+/// Merges the writes of the transaction into the keys of its snapshot.
+pub struct BufferedIter<'a> {
+    iter: TransactionIter<'a>,
+    /// The next key of `iter`
+    next: Option<(Bytes, Option<Bytes>)>,
+    writes: Peekable<vec::IntoIter<(Bytes, Option<Bytes>)>>,
+}
+
+impl BufferedIter<'_> {
+    fn try_next(&mut self) -> Result<Option<(Bytes, Option<Bytes>)>, StorageError> {
+        if self.next.is_none() {
+            self.next = self.iter.try_next()?;
+        }
+        let ordering = match (&self.next, self.writes.peek()) {
+            (Some((key, _)), Some((write_key, _))) => write_key.cmp(key),
+            (None, Some(_)) => Ordering::Less,
+            (_, None) => Ordering::Greater,
+        };
+
+        Ok(match ordering {
+            Ordering::Less => self.writes.next(),
+            Ordering::Equal => {
+                self.next = None;
+                self.writes.next()
+            }
+            Ordering::Greater => self.next.take(),
+        })
+    }
+}
+
 pub struct KipIter<'a> {
     offset: usize,
     limit: Option<usize>,
     projections: Projections,
     table_codec: &'a TableCodec,
-    iter: TransactionIter<'a>
+    iter: BufferedIter<'a>,
+    read_keys: Option<&'a Mutex<HashSet<Bytes>>>,
 }
 
 impl Iter for KipIter<'_> {
@@ -457,8 +950,10 @@ impl Iter for KipIter<'_> {
             }
         }
 
-        while let Some(item) = self.iter.try_next()? {
-            if let (_, Some(value)) = item {
+        while let Some((key, value_option)) = self.iter.try_next()? {
+            record_read(self.read_keys, &key);
+
+            if let Some(value) = value_option {
                 let tuple = tuple_projection(
                     &mut self.limit,
                     &self.projections,
@@ -473,17 +968,25 @@ impl Iter for KipIter<'_> {
     }
 }
 
+pub(crate) fn record_read(read_keys: Option<&Mutex<HashSet<Bytes>>>, key: &[u8]) {
+    if let Some(read_keys) = read_keys {
+        let _ = read_keys.lock().insert(Bytes::copy_from_slice(key));
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::collections::{Bound, VecDeque};
     use std::sync::Arc;
+    use std::time::Duration;
     use itertools::Itertools;
     use tempfile::TempDir;
     use crate::catalog::{ColumnCatalog, ColumnDesc};
-    use crate::db::{Database, DatabaseError};
+    use crate::db::{Database, DatabaseError, QueryResult};
+    use crate::execution::ExecutorError;
     use crate::expression::ScalarExpression;
     use crate::expression::simplify::ConstantBinary;
-    use crate::storage::kip::KipStorage;
+    use crate::storage::kip::{IsolationLevel, KipStorage};
     use crate::storage::{Storage, StorageError, Iter, Transaction, IndexIter};
     use crate::storage::memory::test::data_filling;
     use crate::storage::table_codec::TableCodec;
@@ -512,16 +1015,17 @@ mod test {
         let source_columns = columns.iter()
             .map(|col_ref| ColumnCatalog::clone(&col_ref))
             .collect_vec();
-        let table_id = storage.create_table(Arc::new("test".to_string()), source_columns).await?;
+        let transaction = storage.transaction().await?;
+        let table_id = transaction.create_table(Arc::new("test".to_string()), source_columns)?;
 
-        let table_catalog = storage.table(&"test".to_string()).await;
+        let table_catalog = transaction.table(&"test".to_string());
         assert!(table_catalog.is_some());
         assert!(table_catalog.unwrap().get_column_id_by_name(&"c1".to_string()).is_some());
 
-        let mut transaction = storage.transaction(&table_id).await.unwrap();
-        data_filling(columns, &mut transaction)?;
+        data_filling(&table_id, columns, &transaction)?;
 
         let mut iter = transaction.read(
+            &table_id,
             (Some(1), Some(1)),
             vec![ScalarExpression::InputRef { index: 0, ty: LogicalType::Integer }]
         )?;
@@ -543,7 +1047,8 @@ mod test {
         let _ = kipsql.run("create table t1 (a int primary key)").await?;
         let _ = kipsql.run("insert into t1 (a) values (0), (1), (2)").await?;
 
-        let table = kipsql.storage.table(&"t1".to_string()).await.unwrap().clone();
+        let transaction = kipsql.storage.transaction().await?;
+        let table = transaction.table(&"t1".to_string()).unwrap().clone();
         let projections = table.all_columns()
             .into_iter()
             .map(|col| ScalarExpression::ColumnRef(col))
@@ -551,7 +1056,6 @@ mod test {
         let codec = TableCodec {
             table,
         };
-        let tx = kipsql.storage.transaction(&"t1".to_string()).await.unwrap();
         let tuple_ids = vec![
             Arc::new(DataValue::Int32(Some(0))),
             Arc::new(DataValue::Int32(Some(1))),
@@ -561,7 +1065,8 @@ mod test {
             projections,
             table_codec: &codec,
             tuple_ids: VecDeque::from(tuple_ids.clone()),
-            tx: &tx.tx,
+            transaction: &transaction,
+            read_keys: None,
         };
         let mut result = Vec::new();
 
@@ -582,13 +1087,14 @@ mod test {
         let _ = kipsql.run("create table t1 (a int primary key, b int unique)").await?;
         let _ = kipsql.run("insert into t1 (a, b) values (0, 0), (1, 1), (2, 2)").await?;
 
-        let table = kipsql.storage.table(&"t1".to_string()).await.unwrap().clone();
+        let transaction = kipsql.storage.transaction().await?;
+        let table = transaction.table(&"t1".to_string()).unwrap().clone();
         let projections = table.all_columns()
             .into_iter()
             .map(|col| ScalarExpression::ColumnRef(col))
             .collect_vec();
-        let transaction = kipsql.storage.transaction(&"t1".to_string()).await.unwrap();
         let mut iter = transaction.read_by_index(
+            &"t1".to_string(),
             (Some(0), Some(1)),
             projections,
             table.indexes[0].clone(),
@@ -610,4 +1116,86 @@ mod test {
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_transaction_conflict() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = KipStorage::new(temp_dir.path()).await?;
+        let kipsql_1 = Database::new(storage.clone())?;
+        let kipsql_2 = Database::new(storage.clone())?;
+
+        let _ = kipsql_1.run("create table t1 (a int primary key, b int)").await?;
+        let _ = kipsql_1.run("insert into t1 values (0, 0), (1, 1)").await?;
+
+        let _ = kipsql_1.run("begin; update t1 set b = 10 where a = 0").await?;
+        let _ = kipsql_2.run("begin; update t1 set b = 20 where a = 0").await?;
+        let _ = kipsql_1.run("commit").await?;
+        let err = kipsql_2.run("commit").await.unwrap_err();
+        assert!(matches!(err, DatabaseError::StorageError(StorageError::TransactionConflict)));
+        assert!(err.is_retryable());
+
+        let _ = kipsql_1.run("begin; update t1 set b = 10 where a = 1; savepoint s1; update t1 set b = 10 where a = 0").await?;
+        let _ = kipsql_1.run("rollback to savepoint s1").await?;
+        let _ = kipsql_2.run("begin; update t1 set b = 20 where a = 0; commit").await?;
+        let _ = kipsql_1.run("commit").await?;
+
+        let _ = kipsql_1.run("begin; update t1 set b = 0 where a = 1").await?;
+        let _ = kipsql_2.run("begin; update t1 set b = 1 where a = 0; commit").await?;
+        let _ = kipsql_1.run("commit").await?;
+
+        let storage = storage.with_isolation_level(IsolationLevel::Serializable);
+        let kipsql_1 = Database::new(storage.clone())?;
+        let kipsql_2 = Database::new(storage)?;
+
+        let _ = kipsql_1.run("begin; select * from t1 where a = 0; update t1 set b = 0 where a = 1").await?;
+        let _ = kipsql_2.run("begin; select * from t1 where a = 1; update t1 set b = 1 where a = 0; commit").await?;
+        assert!(matches!(
+            kipsql_1.run("commit").await,
+            Err(DatabaseError::StorageError(StorageError::TransactionConflict))
+        ));
+        let tuples = kipsql_1.run("select b from t1 order by a").await?;
+        assert_eq!(tuples[0].tuples()[0].values, vec![Arc::new(DataValue::Int32(Some(1)))]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_row_lock() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = KipStorage::new(temp_dir.path())
+            .await?
+            .with_lock_timeout(Duration::from_millis(50));
+        let kipsql_1 = Database::new(storage.clone())?;
+        let kipsql_2 = Database::new(storage)?;
+        let first_id = |results: Vec<QueryResult>| results[1].tuples()[0].values[0].clone();
+
+        let _ = kipsql_1.run("create table t1 (a int primary key, b int)").await?;
+        let _ = kipsql_1.run("insert into t1 values (0, 0), (1, 1), (2, 2)").await?;
+
+        let sql = "begin; select a from t1 order by a limit 1 for update skip locked";
+        assert_eq!(first_id(kipsql_1.run(sql).await?), Arc::new(DataValue::Int32(Some(0))));
+        assert_eq!(first_id(kipsql_2.run(sql).await?), Arc::new(DataValue::Int32(Some(1))));
+
+        assert!(matches!(
+            kipsql_2.run("select * from t1 where a = 0 for update nowait").await,
+            Err(DatabaseError::ExecutorError(ExecutorError::StorageError(StorageError::LockNotAvailable)))
+        ));
+        assert!(kipsql_2.run("select * from t1 where a = 0 for share").await.is_err());
+        assert!(matches!(
+            kipsql_2.run("begin").await,
+            Err(DatabaseError::StorageError(StorageError::TransactionError(_)))
+        ));
+        let _ = kipsql_1.run("commit").await?;
+        let _ = kipsql_2.run("select * from t1 where a = 0 for share").await?;
+
+        let _ = kipsql_1.run("begin; select * from t1 where a = 2 for update").await?;
+        let _ = kipsql_2.run("rollback; begin").await?;
+        let _ = kipsql_1.run("update t1 set b = 20 where a = 2; commit").await?;
+        let err = kipsql_2.run("select * from t1 where a = 2 for update").await.unwrap_err();
+        assert!(matches!(err, DatabaseError::ExecutorError(ExecutorError::StorageError(StorageError::TransactionConflict))));
+        assert!(err.is_retryable());
+        assert!(DatabaseError::Statement { index: 1, source: Box::new(err) }.is_retryable());
+
+        Ok(())
+    }
+}
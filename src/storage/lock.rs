@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use bytes::Bytes;
+use parking_lot::Mutex;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use crate::storage::StorageError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockMode {
+    /// `FOR SHARE`, conflicts with the exclusive locks of the other transactions
+    Share,
+    /// `FOR UPDATE`, conflicts with all the locks of the other transactions
+    Exclusive,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockWait {
+    /// Wait until the lock is released or the timeout is reached
+    Block,
+    /// `NOWAIT`, fail at once if the lock is held by another transaction
+    NoWait,
+    /// `SKIP LOCKED`, skip the row if the lock is held by another transaction
+    SkipLocked,
+}
+
+type OwnerId = u64;
+
+#[derive(Default)]
+struct RowLock {
+    exclusive: Option<OwnerId>,
+    shared: HashSet<OwnerId>,
+}
+
+impl RowLock {
+    /// The other owners which block the lock in the mode
+    fn blockers(&self, owner: OwnerId, lock_mode: LockMode) -> HashSet<OwnerId> {
+        let mut blockers: HashSet<OwnerId> = self.exclusive
+            .iter()
+            .copied()
+            .collect();
+
+        if lock_mode == LockMode::Exclusive {
+            blockers.extend(self.shared.iter().copied());
+        }
+        let _ = blockers.remove(&owner);
+
+        blockers
+    }
+
+    fn grant(&mut self, owner: OwnerId, lock_mode: LockMode) {
+        match lock_mode {
+            LockMode::Share if self.exclusive != Some(owner) => {
+                let _ = self.shared.insert(owner);
+            }
+            LockMode::Exclusive => {
+                let _ = self.shared.remove(&owner);
+                self.exclusive = Some(owner);
+            }
+            _ => (),
+        }
+    }
+
+    fn is_held_by(&self, owner: OwnerId) -> bool {
+        self.exclusive == Some(owner) || self.shared.contains(&owner)
+    }
+}
+
+#[derive(Default)]
+struct LockTable {
+    locks: HashMap<Bytes, RowLock>,
+    /// The keys locked by each owner, which are released together
+    held: HashMap<OwnerId, Vec<Bytes>>,
+    waits_for: HashMap<OwnerId, HashSet<OwnerId>>,
+}
+
+impl LockTable {
+    fn try_lock(&mut self, owner: OwnerId, key: &Bytes, lock_mode: LockMode) -> Result<(), HashSet<OwnerId>> {
+        let row_lock = self.locks.entry(key.clone()).or_default();
+        let blockers = row_lock.blockers(owner, lock_mode);
+
+        if !blockers.is_empty() {
+            return Err(blockers);
+        }
+        if !row_lock.is_held_by(owner) {
+            self.held.entry(owner).or_default().push(key.clone());
+        }
+        row_lock.grant(owner, lock_mode);
+
+        Ok(())
+    }
+
+    /// Whether the owner waits for itself through the other waiters
+    fn is_deadlock(&self, owner: OwnerId) -> bool {
+        let mut visited = HashSet::new();
+        let mut stack = vec![owner];
+
+        while let Some(waiter) = stack.pop() {
+            for blocker in self.waits_for.get(&waiter).into_iter().flatten() {
+                if *blocker == owner {
+                    return true;
+                }
+                if visited.insert(*blocker) {
+                    stack.push(*blocker);
+                }
+            }
+        }
+
+        false
+    }
+
+    fn release(&mut self, owner: OwnerId) {
+        let _ = self.waits_for.remove(&owner);
+
+        for key in self.held.remove(&owner).into_iter().flatten() {
+            if let Some(row_lock) = self.locks.get_mut(&key) {
+                if row_lock.exclusive == Some(owner) {
+                    row_lock.exclusive = None;
+                }
+                let _ = row_lock.shared.remove(&owner);
+
+                if row_lock.exclusive.is_none() && row_lock.shared.is_empty() {
+                    let _ = self.locks.remove(&key);
+                }
+            }
+        }
+    }
+}
+
+/// The row locks shared by the transactions of a storage,
+/// which are held until the transaction commits or rolls back.
+#[derive(Default)]
+pub(crate) struct LockManager {
+    table: Mutex<LockTable>,
+    /// Wakes up the waiters when any lock is released
+    released: Notify,
+    next_owner: AtomicU64,
+}
+
+impl LockManager {
+    pub(crate) fn owner(self: &Arc<Self>) -> LockOwner {
+        LockOwner {
+            id: self.next_owner.fetch_add(1, Ordering::Relaxed),
+            lock_manager: Arc::clone(self),
+        }
+    }
+}
+
+/// The locks of a transaction, released on drop.
+pub(crate) struct LockOwner {
+    id: OwnerId,
+    lock_manager: Arc<LockManager>,
+}
+
+impl LockOwner {
+    /// Lock the key, `false` is returned if it is skipped by `LockWait::SkipLocked`.
+    ///
+    /// The waiter which closes a cycle in the waits-for graph is aborted with `StorageError::Deadlock`.
+    pub(crate) async fn lock(
+        &self,
+        key: Bytes,
+        lock_mode: LockMode,
+        lock_wait: LockWait,
+        timeout: Duration
+    ) -> Result<bool, StorageError> {
+        let LockManager { table, released, .. } = self.lock_manager.as_ref();
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            // Tips: listen before checking, so a release between them is not missed
+            let notified = released.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            {
+                let mut table = table.lock();
+
+                match table.try_lock(self.id, &key, lock_mode) {
+                    Ok(()) => {
+                        let _ = table.waits_for.remove(&self.id);
+
+                        return Ok(true);
+                    }
+                    Err(_) if lock_wait == LockWait::NoWait => return Err(StorageError::LockNotAvailable),
+                    Err(_) if lock_wait == LockWait::SkipLocked => return Ok(false),
+                    Err(blockers) => {
+                        let _ = table.waits_for.insert(self.id, blockers);
+
+                        if table.is_deadlock(self.id) {
+                            let _ = table.waits_for.remove(&self.id);
+
+                            return Err(StorageError::Deadlock);
+                        }
+                    }
+                }
+            }
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                let _ = table.lock().waits_for.remove(&self.id);
+
+                return Err(StorageError::LockTimeout);
+            }
+        }
+    }
+}
+
+impl Drop for LockOwner {
+    fn drop(&mut self) {
+        self.lock_manager.table.lock().release(self.id);
+        self.lock_manager.released.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::time::Duration;
+    use bytes::Bytes;
+    use crate::storage::lock::{LockManager, LockMode, LockWait};
+    use crate::storage::StorageError;
+
+    const TIMEOUT: Duration = Duration::from_secs(1);
+
+    #[tokio::test]
+    async fn test_lock_modes() -> Result<(), StorageError> {
+        let lock_manager = Arc::new(LockManager::default());
+        let (owner_1, owner_2) = (lock_manager.owner(), lock_manager.owner());
+        let key = Bytes::from("k1");
+
+        assert!(owner_1.lock(key.clone(), LockMode::Share, LockWait::NoWait, TIMEOUT).await?);
+        assert!(owner_2.lock(key.clone(), LockMode::Share, LockWait::NoWait, TIMEOUT).await?);
+        assert!(!owner_2.lock(key.clone(), LockMode::Exclusive, LockWait::SkipLocked, TIMEOUT).await?);
+        assert!(matches!(
+            owner_2.lock(key.clone(), LockMode::Exclusive, LockWait::NoWait, TIMEOUT).await,
+            Err(StorageError::LockNotAvailable)
+        ));
+        assert!(matches!(
+            owner_2.lock(key.clone(), LockMode::Exclusive, LockWait::Block, Duration::from_millis(10)).await,
+            Err(StorageError::LockTimeout)
+        ));
+
+        let waiter = tokio::spawn(async move {
+            owner_2.lock(Bytes::from("k1"), LockMode::Exclusive, LockWait::Block, TIMEOUT).await
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(owner_1);
+        assert!(waiter.await.unwrap()?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deadlock() -> Result<(), StorageError> {
+        let lock_manager = Arc::new(LockManager::default());
+        let (owner_1, owner_2) = (lock_manager.owner(), lock_manager.owner());
+
+        assert!(owner_1.lock(Bytes::from("k1"), LockMode::Exclusive, LockWait::Block, TIMEOUT).await?);
+        assert!(owner_2.lock(Bytes::from("k2"), LockMode::Exclusive, LockWait::Block, TIMEOUT).await?);
+
+        let waiter = tokio::spawn(async move {
+            let result = owner_1.lock(Bytes::from("k2"), LockMode::Exclusive, LockWait::Block, TIMEOUT).await;
+
+            (owner_1, result)
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(matches!(
+            owner_2.lock(Bytes::from("k1"), LockMode::Exclusive, LockWait::Block, TIMEOUT).await,
+            Err(StorageError::Deadlock)
+        ));
+        drop(owner_2);
+        let (_owner_1, result) = waiter.await.unwrap();
+        assert!(result?);
+
+        Ok(())
+    }
+}
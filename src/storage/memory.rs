@@ -1,11 +1,13 @@
 use std::cell::Cell;
 use std::fmt::{Debug, Formatter};
-use std::slice;
+use std::vec;
 use std::sync::Arc;
 use async_trait::async_trait;
-use crate::catalog::{ColumnCatalog, RootCatalog, TableCatalog, TableName};
+use crate::catalog::{CatalogError, ColumnCatalog, RootCatalog, TableCatalog, TableName, ViewCatalog};
 use crate::expression::simplify::ConstantBinary;
 use crate::storage::{Bounds, Projections, Storage, StorageError, Transaction, Iter, tuple_projection, IndexIter};
+use crate::storage::system::SystemTable;
+use crate::storage::lock::{LockMode, LockWait};
 use crate::types::index::{Index, IndexMetaRef};
 use crate::types::tuple::{Tuple, TupleId};
 
@@ -48,178 +50,267 @@ impl MemStorage {
 #[derive(Debug)]
 struct StorageInner {
     root: RootCatalog,
-    tables: Vec<(TableName, MemTable)>
+    tables: Vec<(TableName, Vec<Tuple>)>
 }
 
 #[async_trait]
 impl Storage for MemStorage {
-    type TransactionType = MemTable;
+    type TransactionType = MemTransaction;
 
-    async fn create_table(&self, table_name: TableName, columns: Vec<ColumnCatalog>) -> Result<TableName, StorageError> {
-        let new_table = MemTable {
-            tuples: Arc::new(Cell::new(vec![])),
-        };
-        let inner = unsafe { self.inner.as_ptr().as_mut() }.unwrap();
-
-        let table_id = inner.root.add_table(table_name.clone(), columns)?;
-        inner.tables.push((table_name, new_table));
-
-        Ok(table_id)
+    async fn transaction(&self) -> Result<Self::TransactionType, StorageError> {
+        Ok(MemTransaction {
+            inner: self.inner.clone(),
+        })
     }
+}
 
-    async fn drop_table(&self, name: &String) -> Result<(), StorageError> {
-        let inner = unsafe {
-            self.inner
-                .as_ptr()
-                .as_mut()
-                .unwrap()
-        };
-
-        inner.root.drop_table(&name)?;
+unsafe impl Send for MemTransaction {
 
-        Ok(())
-    }
+}
 
-    async fn drop_data(&self, name: &String) -> Result<(), StorageError> {
-        let inner = unsafe {
-            self.inner
-                .as_ptr()
-                .as_mut()
-                .unwrap()
-        };
+unsafe impl Sync for MemTransaction {
 
-        inner.tables.retain(|(t_name, _)| t_name.as_str() != name);
+}
 
-        Ok(())
-    }
+/// Writes the storage directly, so the writes can not be rolled back
+pub struct MemTransaction {
+    inner: Arc<Cell<StorageInner>>
+}
 
-    async fn transaction(&self, name: &String) -> Option<Self::TransactionType> {
+impl MemTransaction {
+    fn inner(&self) -> &StorageInner {
         unsafe {
             self.inner
                 .as_ptr()
                 .as_ref()
                 .unwrap()
-                .tables
-                .iter()
-                .find(|(tname, _)| tname.as_str() == name)
-                .map(|(_, table)| table.clone())
         }
     }
 
-    async fn table(&self, name: &String) -> Option<&TableCatalog> {
+    /// Tips: the references from `inner` must not be kept across the writes
+    fn with_inner_mut<R>(&self, f: impl FnOnce(&mut StorageInner) -> R) -> R {
         unsafe {
-            self.inner
+            f(self.inner
                 .as_ptr()
-                .as_ref()
-                .unwrap()
-                .root
-                .get_table(name)
+                .as_mut()
+                .unwrap())
         }
     }
 
-    async fn show_tables(&self) -> Result<Vec<String>, StorageError> {
-        todo!()
+    fn tuples(&self, table_name: &String) -> Result<&Vec<Tuple>, StorageError> {
+        self.inner()
+            .tables
+            .iter()
+            .find(|(t_name, _)| t_name.as_str() == table_name)
+            .map(|(_, tuples)| tuples)
+            .ok_or_else(|| StorageError::TableNotFound(table_name.to_string()))
     }
-}
-
-unsafe impl Send for MemTable {
-
-}
-
-unsafe impl Sync for MemTable {
 
+    fn with_tuples_mut<R>(
+        &self,
+        table_name: &String,
+        f: impl FnOnce(&mut Vec<Tuple>) -> Result<R, StorageError>
+    ) -> Result<R, StorageError> {
+        self.with_inner_mut(|inner| {
+            inner.tables
+                .iter_mut()
+                .find(|(t_name, _)| t_name.as_str() == table_name)
+                .map(|(_, tuples)| f(tuples))
+                .ok_or_else(|| StorageError::TableNotFound(table_name.to_string()))?
+        })
+    }
 }
 
-#[derive(Clone)]
-pub struct MemTable {
-    tuples: Arc<Cell<Vec<Tuple>>>
-}
-
-impl Debug for MemTable {
+impl Debug for MemTransaction {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        unsafe {
-            f.debug_struct("MemTable")
-                .field("{:?}", self.tuples.as_ptr().as_ref().unwrap())
-                .finish()
-        }
+        f.debug_struct("MemTransaction")
+            .field("{:?}", self.inner())
+            .finish()
     }
 }
 
 #[async_trait]
-impl Transaction for MemTable {
-    type IterType<'a> = MemTraction<'a>;
-
-    fn read(&self, bounds: Bounds, projection: Projections) -> Result<Self::IterType<'_>, StorageError> {
-        unsafe {
-            Ok(
-                MemTraction {
-                    offset: bounds.0.unwrap_or(0),
-                    limit: bounds.1,
-                    projections: projection,
-                    iter: self.tuples.as_ptr().as_ref().unwrap().iter(),
-                }
-            )
-        }
+impl Transaction for MemTransaction {
+    type IterType<'a> = MemTraction;
+
+    fn read(&self, table_name: &String, bounds: Bounds, projection: Projections) -> Result<Self::IterType<'_>, StorageError> {
+        Ok(
+            MemTraction {
+                offset: bounds.0.unwrap_or(0),
+                limit: bounds.1,
+                projections: projection,
+                // the tuples are copied, as the table may be written during the scan
+                iter: self.tuples(table_name)?.clone().into_iter(),
+            }
+        )
     }
 
     #[allow(unused_variables)]
-    fn read_by_index(&self, bounds: Bounds, projection: Projections, index_meta: IndexMetaRef, binaries: Vec<ConstantBinary>) -> Result<IndexIter<'_>, StorageError> {
+    fn read_by_index(
+        &self,
+        table_name: &String,
+        bounds: Bounds,
+        projection: Projections,
+        index_meta: IndexMetaRef,
+        binaries: Vec<ConstantBinary>
+    ) -> Result<IndexIter<'_>, StorageError> {
         todo!()
     }
 
+    fn read_by_tuple_ids(&self, table_name: &String, projection: Projections, tuple_ids: Vec<TupleId>) -> Result<Vec<Tuple>, StorageError> {
+        let tuples = self.tuples(table_name)?;
+
+        tuple_ids
+            .iter()
+            .filter_map(|tuple_id| tuples.iter().find(|tuple| tuple.id.as_ref() == Some(tuple_id)))
+            .map(|tuple| tuple_projection(&mut None, &projection, tuple.clone()))
+            .collect()
+    }
+
     #[allow(unused_variables)]
-    fn add_index(&mut self, index: Index, tuple_ids: Vec<TupleId>, is_unique: bool) -> Result<(), StorageError> {
+    fn add_index(&self, table_name: &String, index: Index, tuple_ids: Vec<TupleId>, is_unique: bool) -> Result<(), StorageError> {
         todo!()
     }
 
-    fn del_index(&mut self, _index: &Index) -> Result<(), StorageError> {
+    fn del_index(&self, _table_name: &String, _index: &Index) -> Result<(), StorageError> {
         todo!()
     }
 
-    fn append(&mut self, tuple: Tuple, is_overwrite: bool) -> Result<(), StorageError> {
-        let tuples = unsafe {
-            self.tuples
-                .as_ptr()
-                .as_mut()
-        }.unwrap();
-
-        if let Some(original_tuple) = tuples.iter_mut().find(|t| t.id == tuple.id) {
-            if !is_overwrite {
-                return Err(StorageError::DuplicatePrimaryKey);
+    fn append(&self, table_name: &String, tuple: Tuple, is_overwrite: bool) -> Result<(), StorageError> {
+        self.with_tuples_mut(table_name, |tuples| {
+            if let Some(original_tuple) = tuples.iter_mut().find(|t| t.id == tuple.id) {
+                if !is_overwrite {
+                    return Err(StorageError::DuplicatePrimaryKey);
+                }
+                *original_tuple = tuple;
+            } else {
+                tuples.push(tuple);
             }
-            *original_tuple = tuple;
-        } else {
-            tuples.push(tuple);
-        }
 
-        Ok(())
+            Ok(())
+        })
     }
 
-    fn delete(&mut self, tuple_id: TupleId) -> Result<(), StorageError> {
-        let tuples = unsafe {
-            self.tuples
-                .as_ptr()
-                .as_mut()
-        }.unwrap();
+    fn delete(&self, table_name: &String, tuple_id: TupleId) -> Result<(), StorageError> {
+        self.with_tuples_mut(table_name, |tuples| {
+            tuples.retain(|tuple| tuple.id.clone().unwrap() != tuple_id);
+
+            Ok(())
+        })
+    }
+
+    fn create_table(&self, table_name: TableName, columns: Vec<ColumnCatalog>) -> Result<TableName, StorageError> {
+        self.with_inner_mut(|inner| {
+            let table_id = inner.root.add_table(table_name.clone(), columns)?;
+            inner.tables.push((table_name, vec![]));
+
+            Ok(table_id)
+        })
+    }
+
+    fn drop_table(&self, table_name: &String) -> Result<(), StorageError> {
+        self.with_inner_mut(|inner| {
+            inner.root.drop_table(table_name)?;
+            inner.tables.retain(|(t_name, _)| t_name.as_str() != table_name);
+
+            Ok(())
+        })
+    }
+
+    fn drop_data(&self, table_name: &String) -> Result<(), StorageError> {
+        self.with_tuples_mut(table_name, |tuples| {
+            tuples.clear();
+
+            Ok(())
+        })
+    }
+
+    fn table(&self, table_name: &String) -> Option<&TableCatalog> {
+        self.inner()
+            .root
+            .get_table(table_name)
+            .or_else(|| SystemTable::from_name(table_name).map(|system_table| system_table.catalog()))
+    }
 
-        tuples.retain(|tuple| tuple.id.clone().unwrap() != tuple_id);
+    fn show_tables(&self) -> Result<Vec<String>, StorageError> {
+        todo!()
+    }
+
+    fn create_schema(&self, _schema_name: &str) -> Result<(), StorageError> {
+        Err(StorageError::TransactionError("the memory storage has only the default schema"))
+    }
+
+    fn drop_schema(&self, schema_name: &str) -> Result<(), StorageError> {
+        Err(CatalogError::NotFound("schema", schema_name.to_string()).into())
+    }
 
+    fn show_schemas(&self) -> Result<Vec<String>, StorageError> {
+        Ok(vec![])
+    }
+
+    fn create_view(&self, _view: ViewCatalog) -> Result<(), StorageError> {
+        Err(StorageError::TransactionError("the memory storage does not support views"))
+    }
+
+    fn drop_view(&self, view_name: &String) -> Result<(), StorageError> {
+        Err(CatalogError::NotFound("view", view_name.to_string()).into())
+    }
+
+    fn view(&self, _view_name: &String) -> Option<ViewCatalog> {
+        None
+    }
+
+    fn show_views(&self) -> Result<Vec<ViewCatalog>, StorageError> {
+        Ok(vec![])
+    }
+
+    fn savepoint(&mut self, _name: String) -> Result<(), StorageError> {
+        Err(StorageError::TransactionError("the memory storage writes directly"))
+    }
+
+    fn rollback_to_savepoint(&mut self, _name: &str) -> Result<(), StorageError> {
+        Err(StorageError::TransactionError("the savepoint does not exist"))
+    }
+
+    fn release_savepoint(&mut self, _name: &str) -> Result<(), StorageError> {
+        Err(StorageError::TransactionError("the savepoint does not exist"))
+    }
+
+    fn mark_statement(&mut self) -> Result<(), StorageError> {
         Ok(())
     }
 
+    fn rollback_statement(&mut self) -> Result<(), StorageError> {
+        Err(StorageError::TransactionError("the memory storage writes directly"))
+    }
+
+    fn release_statement(&mut self) -> Result<(), StorageError> {
+        Ok(())
+    }
+
+    async fn lock_tuple(
+        &self,
+        _table_name: &String,
+        _tuple_id: &TupleId,
+        _lock_mode: LockMode,
+        _lock_wait: LockWait
+    ) -> Result<bool, StorageError> {
+        Err(StorageError::TransactionError("the memory storage does not support row locks"))
+    }
+
     async fn commit(self) -> Result<(), StorageError> {
         Ok(())
     }
 }
 
-pub struct MemTraction<'a> {
+pub struct MemTraction {
     offset: usize,
     limit: Option<usize>,
     projections: Projections,
-    iter: slice::Iter<'a, Tuple>
+    iter: vec::IntoIter<Tuple>
 }
 
-impl Iter for MemTraction<'_> {
+impl Iter for MemTraction {
     fn next_tuple(&mut self) -> Result<Option<Tuple>, StorageError> {
         while self.offset > 0 {
             let _ = self.iter.next();
@@ -234,7 +325,6 @@ impl Iter for MemTraction<'_> {
 
         self.iter
             .next()
-            .cloned()
             .map(|tuple| tuple_projection(&mut self.limit, &self.projections, tuple))
             .transpose()
     }
@@ -252,8 +342,12 @@ pub(crate) mod test {
     use crate::types::tuple::Tuple;
     use crate::types::value::DataValue;
 
-    pub fn data_filling(columns: Vec<ColumnRef>, table: &mut impl Transaction) -> Result<(), StorageError> {
-        table.append(Tuple {
+    pub fn data_filling(
+        table_name: &String,
+        columns: Vec<ColumnRef>,
+        transaction: &impl Transaction
+    ) -> Result<(), StorageError> {
+        transaction.append(table_name, Tuple {
             id: Some(Arc::new(DataValue::Int32(Some(1)))),
             columns: columns.clone(),
             values: vec![
@@ -261,7 +355,7 @@ pub(crate) mod test {
                 Arc::new(DataValue::Boolean(Some(true)))
             ],
         }, false)?;
-        table.append(Tuple {
+        transaction.append(table_name, Tuple {
             id: Some(Arc::new(DataValue::Int32(Some(2)))),
             columns: columns.clone(),
             values: vec![
@@ -295,16 +389,17 @@ pub(crate) mod test {
             .map(|col_ref| ColumnCatalog::clone(&col_ref))
             .collect_vec();
 
-        let table_id = storage.create_table(Arc::new("test".to_string()), source_columns).await?;
+        let transaction = storage.transaction().await?;
+        let table_id = transaction.create_table(Arc::new("test".to_string()), source_columns)?;
 
-        let table_catalog = storage.table(&"test".to_string()).await;
+        let table_catalog = transaction.table(&"test".to_string());
         assert!(table_catalog.is_some());
         assert!(table_catalog.unwrap().get_column_id_by_name(&"c1".to_string()).is_some());
 
-        let mut transaction = storage.transaction(&table_id).await.unwrap();
-        data_filling(columns, &mut transaction)?;
+        data_filling(&table_id, columns, &transaction)?;
 
         let mut iter = transaction.read(
+            &table_id,
             (Some(1), Some(1)),
             vec![ScalarExpression::InputRef { index: 0, ty: LogicalType::Integer }]
         )?;
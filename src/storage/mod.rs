@@ -1,16 +1,21 @@
 pub mod memory;
 mod table_codec;
 pub mod kip;
+pub mod lock;
+pub(crate) mod system;
 
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::ops::SubAssign;
 use async_trait::async_trait;
+use bytes::Bytes;
 use kip_db::error::CacheError;
-use kip_db::kernel::lsm::mvcc;
 use kip_db::KernelError;
-use crate::catalog::{CatalogError, ColumnCatalog, TableCatalog, TableName};
+use parking_lot::Mutex;
+use crate::catalog::{CatalogError, ColumnCatalog, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, TableCatalog, TableName, ViewCatalog};
 use crate::expression::ScalarExpression;
 use crate::expression::simplify::ConstantBinary;
+use crate::storage::kip::{KipTransaction, record_read};
+use crate::storage::lock::{LockMode, LockWait};
 use crate::storage::table_codec::TableCodec;
 use crate::types::errors::TypeError;
 use crate::types::index::{Index, IndexMetaRef};
@@ -20,25 +25,17 @@ use crate::types::tuple::{Tuple, TupleId};
 pub trait Storage: Sync + Send + Clone + 'static {
     type TransactionType: Transaction;
 
-    async fn create_table(
-        &self,
-        table_name: TableName,
-        columns: Vec<ColumnCatalog>
-    ) -> Result<TableName, StorageError>;
-
-    async fn drop_table(&self, name: &String) -> Result<(), StorageError>;
-    async fn drop_data(&self, name: &String) -> Result<(), StorageError>;
-
-    async fn transaction(&self, name: &String) -> Option<Self::TransactionType>;
-    async fn table(&self, name: &String) -> Option<&TableCatalog>;
-
-    async fn show_tables(&self) -> Result<Vec<String>, StorageError>;
+    /// Start a transaction, all the tables are read and written through it
+    /// with the same snapshot until `commit`, dropping it discards the writes.
+    async fn transaction(&self) -> Result<Self::TransactionType, StorageError>;
 }
 
 /// Optional bounds of the reader, of the form (offset, limit).
 pub(crate) type Bounds = (Option<usize>, Option<usize>);
 type Projections = Vec<ScalarExpression>;
 
+/// Tips: the executors of a statement share the transaction,
+/// so the writes take `&self` and are visible to the scans started after them.
 #[async_trait]
 pub trait Transaction: Sync + Send + 'static {
     type IterType<'a>: Iter;
@@ -48,25 +45,110 @@ pub trait Transaction: Sync + Send + 'static {
     /// The projections is column indices.
     fn read(
         &self,
+        table_name: &String,
         bounds: Bounds,
         projection: Projections,
     ) -> Result<Self::IterType<'_>, StorageError>;
 
     fn read_by_index(
         &self,
+        table_name: &String,
         bounds: Bounds,
         projection: Projections,
         index_meta: IndexMetaRef,
         binaries: Vec<ConstantBinary>
     ) -> Result<IndexIter<'_>, StorageError>;
 
-    fn add_index(&mut self, index: Index, tuple_ids: Vec<TupleId>, is_unique: bool) -> Result<(), StorageError>;
+    /// Point lookups of tuples by their primary keys, missing keys are skipped.
+    fn read_by_tuple_ids(
+        &self,
+        table_name: &String,
+        projection: Projections,
+        tuple_ids: Vec<TupleId>
+    ) -> Result<Vec<Tuple>, StorageError>;
+
+    fn add_index(
+        &self,
+        table_name: &String,
+        index: Index,
+        tuple_ids: Vec<TupleId>,
+        is_unique: bool
+    ) -> Result<(), StorageError>;
+
+    fn del_index(&self, table_name: &String, index: &Index) -> Result<(), StorageError>;
+
+    fn append(&self, table_name: &String, tuple: Tuple, is_overwrite: bool) -> Result<(), StorageError>;
+
+    fn delete(&self, table_name: &String, tuple_id: TupleId) -> Result<(), StorageError>;
+
+    fn create_table(
+        &self,
+        table_name: TableName,
+        columns: Vec<ColumnCatalog>
+    ) -> Result<TableName, StorageError>;
+
+    fn drop_table(&self, table_name: &String) -> Result<(), StorageError>;
+
+    fn drop_data(&self, table_name: &String) -> Result<(), StorageError>;
+
+    fn table(&self, table_name: &String) -> Option<&TableCatalog>;
+
+    fn show_tables(&self) -> Result<Vec<String>, StorageError>;
+
+    fn create_schema(&self, schema_name: &str) -> Result<(), StorageError>;
 
-    fn del_index(&mut self, index: &Index) -> Result<(), StorageError>;
+    /// Remove the schema, the tables in it must be dropped before.
+    fn drop_schema(&self, schema_name: &str) -> Result<(), StorageError>;
 
-    fn append(&mut self, tuple: Tuple, is_overwrite: bool) -> Result<(), StorageError>;
+    /// The created schemas, without the default schema and `information_schema`.
+    fn show_schemas(&self) -> Result<Vec<String>, StorageError>;
 
-    fn delete(&mut self, tuple_id: TupleId) -> Result<(), StorageError>;
+    /// Whether the schema exists, the default schema and `information_schema` always exist.
+    fn has_schema(&self, schema_name: &str) -> Result<bool, StorageError> {
+        Ok(schema_name == DEFAULT_SCHEMA_NAME
+            || schema_name == INFORMATION_SCHEMA_NAME
+            || self.show_schemas()?.iter().any(|name| name == schema_name))
+    }
+
+    /// Create or replace the view.
+    fn create_view(&self, view: ViewCatalog) -> Result<(), StorageError>;
+
+    fn drop_view(&self, view_name: &String) -> Result<(), StorageError>;
+
+    fn view(&self, view_name: &String) -> Option<ViewCatalog>;
+
+    fn show_views(&self) -> Result<Vec<ViewCatalog>, StorageError>;
+
+    /// Mark the writes so far, the later writes can be discarded by `rollback_to_savepoint`.
+    ///
+    /// A savepoint with the same name as an earlier one hides it until released
+    fn savepoint(&mut self, name: String) -> Result<(), StorageError>;
+
+    /// Discard the writes after the savepoint, which is kept while the later ones are removed.
+    fn rollback_to_savepoint(&mut self, name: &str) -> Result<(), StorageError>;
+
+    /// Remove the savepoint and the later ones, the writes are kept.
+    fn release_savepoint(&mut self, name: &str) -> Result<(), StorageError>;
+
+    /// Mark the writes before a statement of the transaction block,
+    /// so the writes of the statement are discarded by `rollback_statement` if it fails.
+    fn mark_statement(&mut self) -> Result<(), StorageError>;
+
+    /// Discard the writes after `mark_statement`, the savepoints before it are kept.
+    fn rollback_statement(&mut self) -> Result<(), StorageError>;
+
+    /// Remove the mark of the succeeded statement, the writes are kept.
+    fn release_statement(&mut self) -> Result<(), StorageError>;
+
+    /// Lock the tuple until the transaction commits or rolls back,
+    /// `false` is returned if it is skipped by `LockWait::SkipLocked`.
+    async fn lock_tuple(
+        &self,
+        table_name: &String,
+        tuple_id: &TupleId,
+        lock_mode: LockMode,
+        lock_wait: LockWait
+    ) -> Result<bool, StorageError>;
 
     async fn commit(self) -> Result<(), StorageError>;
 }
@@ -76,24 +158,27 @@ pub struct IndexIter<'a> {
     projections: Projections,
     table_codec: &'a TableCodec,
     tuple_ids: VecDeque<TupleId>,
-    tx: &'a mvcc::Transaction
+    transaction: &'a KipTransaction,
+    read_keys: Option<&'a Mutex<HashSet<Bytes>>>,
 }
 
 impl Iter for IndexIter<'_> {
+    /// Tips: the tuple ids of an index may refer to the tuples deleted in the transaction, which are skipped
     fn next_tuple(&mut self) -> Result<Option<Tuple>, StorageError> {
-        if let Some(tuple_id) = self.tuple_ids.pop_front() {
+        while let Some(tuple_id) = self.tuple_ids.pop_front() {
             let key = self.table_codec.encode_tuple_key(&tuple_id)?;
+            record_read(self.read_keys, &key);
 
-            Ok(self.tx.get(&key)?
-                .map(|bytes| tuple_projection(
+            if let Some(bytes) = self.transaction.get(&key)? {
+                return tuple_projection(
                     &mut None,
                     &self.projections,
                     self.table_codec.decode_tuple(&bytes)
-                ))
-                .transpose()?)
-        } else {
-            Ok(None)
+                ).map(Some);
+            }
         }
+
+        Ok(None)
     }
 }
 
@@ -145,6 +230,33 @@ pub enum StorageError {
 
     #[error("The column has been declared unique and the value already exists")]
     DuplicateUniqueValue,
+
+    #[error("table not found: {0}")]
+    TableNotFound(String),
+
+    #[error("transaction error: {0}")]
+    TransactionError(&'static str),
+
+    /// The transaction can be retried from the start
+    #[error("the transaction conflicts with a concurrent one, please retry")]
+    TransactionConflict,
+
+    #[error("could not obtain the lock of the row")]
+    LockNotAvailable,
+
+    #[error("the lock wait timed out")]
+    LockTimeout,
+
+    /// The transaction can be retried from the start
+    #[error("deadlock detected, please retry")]
+    Deadlock,
+}
+
+impl StorageError {
+    /// Whether the transaction failed on the concurrent transactions and may succeed when retried
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, StorageError::TransactionConflict | StorageError::Deadlock)
+    }
 }
 
 impl From<KernelError> for StorageError {
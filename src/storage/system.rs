@@ -0,0 +1,392 @@
+use std::sync::Arc;
+use std::vec;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use sqlparser::ast::DataType;
+use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnRef, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, split_table_name, TableCatalog};
+use crate::storage::{Bounds, Iter, Projections, StorageError, Transaction, tuple_projection};
+use crate::types::{ColumnId, LogicalType};
+use crate::types::tuple::Tuple;
+use crate::types::value::DataValue;
+
+const SYSTEM_TABLES: [SystemTable; 7] = [
+    SystemTable::Schemata,
+    SystemTable::Tables,
+    SystemTable::Columns,
+    SystemTable::TableConstraints,
+    SystemTable::KeyColumnUsage,
+    SystemTable::Statistics,
+    SystemTable::Views,
+];
+
+lazy_static! {
+    static ref SYSTEM_TABLE_CATALOGS: Vec<TableCatalog> = SYSTEM_TABLES
+        .iter()
+        .map(SystemTable::build_catalog)
+        .collect();
+}
+
+/// The virtual tables of `information_schema`, whose rows are generated from the catalogs
+/// of the tables seen by the transaction instead of being stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SystemTable {
+    Schemata,
+    Tables,
+    Columns,
+    TableConstraints,
+    KeyColumnUsage,
+    Statistics,
+    Views,
+}
+
+/// A table seen in `information_schema`, of the form (schema name, table name, catalog).
+type SchemaTable<'a> = (&'a str, &'a str, &'a TableCatalog);
+
+/// A primary key or unique constraint, of the form (name, type, columns).
+type Constraint = (String, &'static str, Vec<ColumnRef>);
+
+impl SystemTable {
+    /// The system table of the name qualified by `information_schema`, e.g. `information_schema.tables`.
+    pub(crate) fn from_name(table_name: &str) -> Option<Self> {
+        let (schema, name) = split_table_name(table_name);
+
+        if schema != INFORMATION_SCHEMA_NAME {
+            return None;
+        }
+        SYSTEM_TABLES
+            .into_iter()
+            .find(|system_table| system_table.name() == name)
+    }
+
+    pub(crate) fn catalog(&self) -> &'static TableCatalog {
+        &SYSTEM_TABLE_CATALOGS[*self as usize]
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SystemTable::Schemata => "schemata",
+            SystemTable::Tables => "tables",
+            SystemTable::Columns => "columns",
+            SystemTable::TableConstraints => "table_constraints",
+            SystemTable::KeyColumnUsage => "key_column_usage",
+            SystemTable::Statistics => "statistics",
+            SystemTable::Views => "views",
+        }
+    }
+
+    fn columns(&self) -> Vec<(&'static str, LogicalType)> {
+        let varchar = LogicalType::Varchar(None);
+
+        match self {
+            SystemTable::Schemata => vec![
+                ("catalog_name", varchar),
+                ("schema_name", varchar),
+            ],
+            SystemTable::Tables => vec![
+                ("table_catalog", varchar),
+                ("table_schema", varchar),
+                ("table_name", varchar),
+                ("table_type", varchar),
+            ],
+            SystemTable::Columns => vec![
+                ("table_catalog", varchar),
+                ("table_schema", varchar),
+                ("table_name", varchar),
+                ("column_name", varchar),
+                ("ordinal_position", LogicalType::Integer),
+                ("column_default", varchar),
+                ("is_nullable", varchar),
+                ("data_type", varchar),
+                ("character_maximum_length", LogicalType::Integer),
+                ("numeric_precision", LogicalType::Integer),
+                ("numeric_scale", LogicalType::Integer),
+            ],
+            SystemTable::TableConstraints => vec![
+                ("constraint_catalog", varchar),
+                ("constraint_schema", varchar),
+                ("constraint_name", varchar),
+                ("table_catalog", varchar),
+                ("table_schema", varchar),
+                ("table_name", varchar),
+                ("constraint_type", varchar),
+            ],
+            SystemTable::KeyColumnUsage => vec![
+                ("constraint_catalog", varchar),
+                ("constraint_schema", varchar),
+                ("constraint_name", varchar),
+                ("table_catalog", varchar),
+                ("table_schema", varchar),
+                ("table_name", varchar),
+                ("column_name", varchar),
+                ("ordinal_position", LogicalType::Integer),
+            ],
+            SystemTable::Statistics => vec![
+                ("table_catalog", varchar),
+                ("table_schema", varchar),
+                ("table_name", varchar),
+                ("non_unique", LogicalType::Boolean),
+                ("index_schema", varchar),
+                ("index_name", varchar),
+                ("seq_in_index", LogicalType::Integer),
+                ("column_name", varchar),
+            ],
+            SystemTable::Views => vec![
+                ("table_catalog", varchar),
+                ("table_schema", varchar),
+                ("table_name", varchar),
+                ("view_definition", varchar),
+            ],
+        }
+    }
+
+    fn build_catalog(&self) -> TableCatalog {
+        let columns = self.columns()
+            .into_iter()
+            .map(|(name, ty)| ColumnCatalog::new(
+                name.to_string(),
+                true,
+                ColumnDesc::new(ty, false, false),
+                None
+            ))
+            .collect_vec();
+
+        TableCatalog::new(Arc::new(format!("{}.{}", INFORMATION_SCHEMA_NAME, self.name())), columns)
+            .expect("the columns of system tables are distinct")
+    }
+
+    /// The rows of the system table, the offset and limit are applied before the projections.
+    pub(crate) fn read<T: Transaction>(
+        &self,
+        transaction: &T,
+        (offset, limit): Bounds,
+        projections: Projections
+    ) -> Result<SystemIter, StorageError> {
+        let columns = self.catalog().all_columns();
+        let tuples = self.rows(transaction)?
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit.unwrap_or(usize::MAX))
+            .map(|values| Tuple {
+                id: None,
+                columns: columns.clone(),
+                values: values.into_iter().map(Arc::new).collect(),
+            })
+            .collect_vec();
+
+        Ok(SystemIter {
+            projections,
+            tuples: tuples.into_iter(),
+        })
+    }
+
+    fn rows<T: Transaction>(&self, transaction: &T) -> Result<Vec<Vec<DataValue>>, StorageError> {
+        let user_tables = transaction.show_tables()?;
+        let tables: Vec<SchemaTable> = user_tables
+            .iter()
+            .filter_map(|name| {
+                let (schema, name_in_schema) = split_table_name(name);
+
+                transaction.table(name).map(|table| (schema, name_in_schema, table))
+            })
+            .chain(SYSTEM_TABLES.iter().map(|system_table| {
+                (INFORMATION_SCHEMA_NAME, system_table.name(), system_table.catalog())
+            }))
+            .collect_vec();
+
+        Ok(match self {
+            SystemTable::Schemata => [DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME]
+                .into_iter()
+                .map(String::from)
+                .chain(transaction.show_schemas()?)
+                .map(|schema| vec![utf8(DEFAULT_DATABASE_NAME), DataValue::Utf8(Some(schema))])
+                .collect(),
+            SystemTable::Tables => {
+                let views = transaction.show_views()?;
+
+                tables
+                    .iter()
+                    .map(|(schema, name, table)| {
+                        let table_type = if *schema == INFORMATION_SCHEMA_NAME {
+                            "SYSTEM VIEW"
+                        } else if views.iter().any(|view| view.materialized && view.name == table.name) {
+                            "MATERIALIZED VIEW"
+                        } else {
+                            "BASE TABLE"
+                        };
+
+                        [table_prefix(schema, name), vec![utf8(table_type)]].concat()
+                    })
+                    .chain(views.iter().filter(|view| !view.materialized).map(|view| {
+                        let (schema, name) = split_table_name(&view.name);
+
+                        [table_prefix(schema, name), vec![utf8("VIEW")]].concat()
+                    }))
+                    .collect()
+            }
+            SystemTable::Columns => tables
+                .iter()
+                .flat_map(|(schema, name, table)| {
+                    table.visible_columns()
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, column)| [table_prefix(schema, name), column_values(i, &column)].concat())
+                        .collect_vec()
+                })
+                .collect(),
+            SystemTable::TableConstraints => tables
+                .iter()
+                .flat_map(|(schema, name, table)| {
+                    constraints(table)
+                        .into_iter()
+                        .map(|(constraint_name, constraint_type, _)| [
+                            vec![utf8(DEFAULT_DATABASE_NAME), utf8(schema), utf8(&constraint_name)],
+                            table_prefix(schema, name),
+                            vec![utf8(constraint_type)],
+                        ].concat())
+                        .collect_vec()
+                })
+                .collect(),
+            SystemTable::KeyColumnUsage => tables
+                .iter()
+                .flat_map(|(schema, name, table)| {
+                    constraints(table)
+                        .into_iter()
+                        .flat_map(|(constraint_name, _, columns)| {
+                            columns.iter()
+                                .enumerate()
+                                .map(|(i, column)| [
+                                    vec![utf8(DEFAULT_DATABASE_NAME), utf8(schema), utf8(&constraint_name)],
+                                    table_prefix(schema, name),
+                                    vec![utf8(&column.name), DataValue::Int32(Some(i as i32 + 1))],
+                                ].concat())
+                                .collect_vec()
+                        })
+                        .collect_vec()
+                })
+                .collect(),
+            SystemTable::Statistics => tables
+                .iter()
+                .flat_map(|(schema, name, table)| index_rows(schema, name, table))
+                .collect(),
+            SystemTable::Views => transaction.show_views()?
+                .into_iter()
+                .filter(|view| !view.materialized)
+                .map(|view| {
+                    let (schema, name) = split_table_name(&view.name);
+
+                    [table_prefix(schema, name), vec![DataValue::Utf8(Some(view.query.clone()))]].concat()
+                })
+                .collect(),
+        })
+    }
+}
+
+/// The `table_catalog`, `table_schema` and `table_name` of the table.
+fn table_prefix(schema: &str, name: &str) -> Vec<DataValue> {
+    vec![utf8(DEFAULT_DATABASE_NAME), utf8(schema), utf8(name)]
+}
+
+fn utf8(value: &str) -> DataValue {
+    DataValue::Utf8(Some(value.to_string()))
+}
+
+fn column_values(i: usize, column: &ColumnRef) -> Vec<DataValue> {
+    let (max_length, precision, scale) = match column.datatype() {
+        LogicalType::Varchar(len) => (len.map(|len| len as i32), None, None),
+        LogicalType::Decimal(precision, scale) => (
+            None,
+            precision.map(|precision| precision as i32),
+            scale.map(|scale| scale as i32)
+        ),
+        _ => (None, None, None),
+    };
+    let data_type = DataType::try_from(column.datatype())
+        .map(|data_type| data_type.to_string())
+        .unwrap_or_else(|_| column.datatype().to_string());
+
+    vec![
+        utf8(&column.name),
+        DataValue::Int32(Some(i as i32 + 1)),
+        DataValue::Utf8(None),
+        utf8(if column.nullable { "YES" } else { "NO" }),
+        DataValue::Utf8(Some(data_type)),
+        DataValue::Int32(max_length),
+        DataValue::Int32(precision),
+        DataValue::Int32(scale),
+    ]
+}
+
+/// The primary key is named `pk_{column}` like the unique indexes `uk_{column}`.
+fn primary_key(table: &TableCatalog) -> Option<(String, Vec<ColumnRef>)> {
+    let columns = table.visible_columns()
+        .into_iter()
+        .filter(|column| column.desc.is_primary)
+        .collect_vec();
+
+    (!columns.is_empty())
+        .then(|| (format!("pk_{}", columns.iter().map(|column| &column.name).join("_")), columns))
+}
+
+fn constraints(table: &TableCatalog) -> Vec<Constraint> {
+    let unique_indexes = table.indexes
+        .iter()
+        .filter(|index_meta| index_meta.is_unique)
+        .map(|index_meta| (index_meta.name.clone(), "UNIQUE", index_columns(table, &index_meta.column_ids)));
+
+    primary_key(table)
+        .map(|(name, columns)| (name, "PRIMARY KEY", columns))
+        .into_iter()
+        .chain(unique_indexes)
+        .collect()
+}
+
+fn index_columns(table: &TableCatalog, column_ids: &[ColumnId]) -> Vec<ColumnRef> {
+    column_ids
+        .iter()
+        .filter_map(|column_id| table.get_column_by_id(column_id).cloned())
+        .collect()
+}
+
+/// The primary key and the indexes of the table, one row per column of each.
+fn index_rows(schema: &str, name: &str, table: &TableCatalog) -> Vec<Vec<DataValue>> {
+    let primary_key = primary_key(table)
+        .map(|(index_name, columns)| (index_name, false, columns))
+        .into_iter();
+    let indexes = table.indexes
+        .iter()
+        .map(|index_meta| (index_meta.name.clone(), !index_meta.is_unique, index_columns(table, &index_meta.column_ids)));
+
+    primary_key
+        .chain(indexes)
+        .flat_map(|(index_name, non_unique, columns)| {
+            columns.iter()
+                .enumerate()
+                .map(|(i, column)| [
+                    table_prefix(schema, name),
+                    vec![
+                        DataValue::Boolean(Some(non_unique)),
+                        utf8(schema),
+                        utf8(&index_name),
+                        DataValue::Int32(Some(i as i32 + 1)),
+                        utf8(&column.name),
+                    ],
+                ].concat())
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// The generated rows of a system table.
+pub struct SystemIter {
+    projections: Projections,
+    tuples: vec::IntoIter<Tuple>,
+}
+
+impl Iter for SystemIter {
+    fn next_tuple(&mut self) -> Result<Option<Tuple>, StorageError> {
+        self.tuples
+            .next()
+            .map(|tuple| tuple_projection(&mut None, &self.projections, tuple))
+            .transpose()
+    }
+}
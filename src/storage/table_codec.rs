@@ -1,6 +1,6 @@
 use bytes::Bytes;
 use lazy_static::lazy_static;
-use crate::catalog::{ColumnCatalog, TableCatalog, TableName};
+use crate::catalog::{ColumnCatalog, TableCatalog, TableName, ViewCatalog};
 use crate::types::errors::TypeError;
 use crate::types::index::{Index, IndexId, IndexMeta};
 use crate::types::tuple::{Tuple, TupleId};
@@ -11,6 +11,12 @@ lazy_static! {
     static ref ROOT_BYTES: Vec<u8> = {
         b"Root".to_vec()
     };
+    static ref SCHEMA_BYTES: Vec<u8> = {
+        b"Schema".to_vec()
+    };
+    static ref VIEW_BYTES: Vec<u8> = {
+        b"View".to_vec()
+    };
 }
 
 #[derive(Clone)]
@@ -118,6 +124,28 @@ impl TableCodec {
         (op(BOUND_MIN_TAG), op(BOUND_MAX_TAG))
     }
 
+    pub fn schema_bound() -> (Vec<u8>, Vec<u8>) {
+        let op = |bound_id| {
+            let mut key_prefix = SCHEMA_BYTES.clone();
+
+            key_prefix.push(bound_id);
+            key_prefix
+        };
+
+        (op(BOUND_MIN_TAG), op(BOUND_MAX_TAG))
+    }
+
+    pub fn view_bound() -> (Vec<u8>, Vec<u8>) {
+        let op = |bound_id| {
+            let mut key_prefix = VIEW_BYTES.clone();
+
+            key_prefix.push(bound_id);
+            key_prefix
+        };
+
+        (op(BOUND_MIN_TAG), op(BOUND_MAX_TAG))
+    }
+
     pub fn columns_bound(name: &String) -> (Vec<u8>, Vec<u8>) {
         let op = |bound_id| {
             let mut key_prefix = Self::key_prefix(CodecType::Column, &name);
@@ -237,6 +265,48 @@ impl TableCodec {
     pub fn decode_root_table(bytes: &[u8]) -> Result<String, TypeError> {
         Ok(String::from_utf8(bytes.to_vec())?)
     }
+
+    /// Key: Schema_0_SchemaName
+    /// Value: SchemaName
+    ///
+    /// The tables of a schema are prefixed by its name, see `table_name_in_schema`
+    pub fn encode_schema(schema_name: &str) -> (Bytes, Bytes) {
+        let key = Self::encode_schema_key(schema_name);
+
+        (Bytes::from(key), Bytes::from(schema_name.to_string().into_bytes()))
+    }
+
+    pub fn encode_schema_key(schema_name: &str) -> Vec<u8> {
+        let mut key = SCHEMA_BYTES.clone();
+        key.push(BOUND_MIN_TAG);
+        key.extend_from_slice(schema_name.as_bytes());
+
+        key
+    }
+
+    pub fn decode_schema(bytes: &[u8]) -> Result<String, TypeError> {
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    /// Key: View_0_ViewName
+    /// Value: ViewCatalog
+    pub fn encode_view(view: &ViewCatalog) -> Result<(Bytes, Bytes), TypeError> {
+        let key = Self::encode_view_key(&view.name);
+
+        Ok((Bytes::from(key), Bytes::from(bincode::serialize(view)?)))
+    }
+
+    pub fn encode_view_key(view_name: &String) -> Vec<u8> {
+        let mut key = VIEW_BYTES.clone();
+        key.push(BOUND_MIN_TAG);
+        key.extend_from_slice(view_name.as_bytes());
+
+        key
+    }
+
+    pub fn decode_view(bytes: &[u8]) -> Result<ViewCatalog, TypeError> {
+        Ok(bincode::deserialize(bytes)?)
+    }
 }
 
 #[cfg(test)]
@@ -247,7 +317,7 @@ mod tests {
     use bytes::Bytes;
     use itertools::Itertools;
     use rust_decimal::Decimal;
-    use crate::catalog::{ColumnCatalog, ColumnDesc, TableCatalog};
+    use crate::catalog::{ColumnCatalog, ColumnDesc, TableCatalog, ViewCatalog};
     use crate::storage::table_codec::TableCodec;
     use crate::types::errors::TypeError;
     use crate::types::index::{Index, IndexMeta};
@@ -304,6 +374,35 @@ mod tests {
         assert_eq!(table_name, table_catalog.name.as_str());
     }
 
+    #[test]
+    fn test_schema() -> Result<(), TypeError> {
+        let (key, bytes) = TableCodec::encode_schema("s1");
+        let (min, max) = TableCodec::schema_bound();
+
+        assert_eq!(TableCodec::decode_schema(&bytes)?, "s1");
+        assert!(min.as_slice() < key.as_ref() && key.as_ref() < max.as_slice());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_view() -> Result<(), TypeError> {
+        let view = ViewCatalog {
+            name: Arc::new("s1.v1".to_string()),
+            columns: vec!["c1".to_string()],
+            query: "SELECT c1 FROM t1".to_string(),
+            search_path: vec!["kipsql".to_string()],
+            materialized: false,
+        };
+        let (key, bytes) = TableCodec::encode_view(&view)?;
+        let (min, max) = TableCodec::view_bound();
+
+        assert_eq!(TableCodec::decode_view(&bytes)?, view);
+        assert!(min.as_slice() < key.as_ref() && key.as_ref() < max.as_slice());
+
+        Ok(())
+    }
+
     #[test]
     fn test_table_codec_index_meta() -> Result<(), TypeError> {
         let index_meta = IndexMeta {
@@ -358,6 +457,7 @@ mod tests {
                     column_datatype: LogicalType::Invalid,
                     is_primary: false,
                     is_unique: false,
+                    is_hidden: false,
                 },
                 None
             );
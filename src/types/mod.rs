@@ -328,6 +328,40 @@ impl TryFrom<sqlparser::ast::DataType> for LogicalType {
     }
 }
 
+impl TryFrom<&LogicalType> for sqlparser::ast::DataType {
+    type Error = TypeError;
+
+    /// The SQL type which is parsed back to the logical type, e.g. in `SHOW CREATE TABLE`.
+    fn try_from(value: &LogicalType) -> Result<Self, Self::Error> {
+        use sqlparser::ast::{CharacterLength, DataType};
+
+        Ok(match value {
+            LogicalType::Invalid | LogicalType::SqlNull => return Err(TypeError::InvalidType),
+            LogicalType::Boolean => DataType::Boolean,
+            LogicalType::Tinyint => DataType::TinyInt(None),
+            LogicalType::UTinyint => DataType::UnsignedTinyInt(None),
+            LogicalType::Smallint => DataType::SmallInt(None),
+            LogicalType::USmallint => DataType::UnsignedSmallInt(None),
+            LogicalType::Integer => DataType::Int(None),
+            LogicalType::UInteger => DataType::UnsignedInt(None),
+            LogicalType::Bigint => DataType::BigInt(None),
+            LogicalType::UBigint => DataType::UnsignedBigInt(None),
+            LogicalType::Float => DataType::Float(None),
+            LogicalType::Double => DataType::Double,
+            LogicalType::Varchar(len) => DataType::Varchar(
+                len.map(|len| CharacterLength { length: len as u64, unit: None })
+            ),
+            LogicalType::Date => DataType::Date,
+            LogicalType::DateTime => DataType::Datetime(None),
+            LogicalType::Decimal(precision, scale) => DataType::Decimal(match (precision, scale) {
+                (Some(precision), Some(scale)) => ExactNumberInfo::PrecisionAndScale(*precision as u64, *scale as u64),
+                (Some(precision), None) => ExactNumberInfo::Precision(*precision as u64),
+                _ => ExactNumberInfo::None,
+            }),
+        })
+    }
+}
+
 impl std::fmt::Display for LogicalType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_ref())
@@ -1,6 +1,6 @@
 use std::time::Instant;
 use sqllogictest::{AsyncDB, DBOutput, DefaultColumnType};
-use kip_sql::db::{Database, DatabaseError};
+use kip_sql::db::{Database, DatabaseError, QueryResult};
 use kip_sql::storage::kip::KipStorage;
 
 pub struct KipSQL {
@@ -14,13 +14,22 @@ impl AsyncDB for KipSQL {
 
     async fn run(&mut self, sql: &str) -> Result<DBOutput<Self::ColumnType>, Self::Error> {
         let start = Instant::now();
-        let tuples = self.db.run(sql).await?;
+        // Tips: a record is a single statement
+        let result = self.db.run(sql).await?
+            .pop()
+            .unwrap_or(QueryResult::Rows(vec![]));
         println!("|— Input SQL:");
         println!(" |— {}", sql);
         println!(" |— Time consuming: {:?}", start.elapsed());
 
+        let tuples = match result {
+            QueryResult::Rows(tuples) => tuples,
+            QueryResult::Command { affected_rows, .. } => {
+                return Ok(DBOutput::StatementComplete(affected_rows as u64));
+            }
+        };
         if tuples.is_empty() {
-            return Ok(DBOutput::StatementComplete(0));
+            return Ok(DBOutput::Rows { types: vec![], rows: vec![] });
         }
 
         let types = vec![DefaultColumnType::Any; tuples[0].columns.len()];